@@ -0,0 +1,352 @@
+//! Lightweight static analysis: language detection, line counting, and
+//! regex-based symbol extraction. Deliberately heuristic — good enough for
+//! prompts and reports without dragging in a parser per language.
+
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileAnalysis {
+    pub path: String,
+    pub language: String,
+    pub total_lines: usize,
+    pub code_lines: usize,
+    pub comment_lines: usize,
+    pub blank_lines: usize,
+    pub size_bytes: u64,
+}
+
+pub fn language_for_path(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "rs" => "Rust",
+        "py" => "Python",
+        "js" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "jsx" => "JavaScript",
+        "go" => "Go",
+        "java" => "Java",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "hpp" => "C++",
+        "rb" => "Ruby",
+        "sh" | "bash" => "Shell",
+        "md" => "Markdown",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "json" => "JSON",
+        "html" => "HTML",
+        "css" => "CSS",
+        "sql" => "SQL",
+        _ => "Other",
+    }
+}
+
+/// Line-comment prefix for a language, used by the line classifier.
+fn comment_prefix(language: &str) -> Option<&'static str> {
+    match language {
+        "Rust" | "JavaScript" | "TypeScript" | "Go" | "Java" | "C" | "C++" => Some("//"),
+        "Python" | "Ruby" | "Shell" | "TOML" | "YAML" => Some("#"),
+        "SQL" => Some("--"),
+        _ => None,
+    }
+}
+
+pub fn analyze_file(path: &Path) -> Result<FileAnalysis> {
+    let content = std::fs::read_to_string(path)?;
+    let size_bytes = content.len() as u64;
+    let language = language_for_path(path).to_string();
+    let prefix = comment_prefix(&language);
+    let mut code_lines = 0;
+    let mut comment_lines = 0;
+    let mut blank_lines = 0;
+    let mut total_lines = 0;
+    for line in content.lines() {
+        total_lines += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            blank_lines += 1;
+        } else if prefix.is_some_and(|p| trimmed.starts_with(p)) {
+            comment_lines += 1;
+        } else {
+            code_lines += 1;
+        }
+    }
+    Ok(FileAnalysis {
+        path: path.display().to_string(),
+        language,
+        total_lines,
+        code_lines,
+        comment_lines,
+        blank_lines,
+        size_bytes,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: String,
+    pub line: usize,
+}
+
+/// Regex-based symbol extraction for the languages we see most. Imprecise
+/// by design; a declaration-shaped line is close enough for an outline.
+pub fn extract_symbols(content: &str, language: &str) -> Vec<Symbol> {
+    let patterns: &[(&str, &str)] = match language {
+        "Rust" => &[
+            (
+                "fn",
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?fn\s+([A-Za-z0-9_]+)",
+            ),
+            (
+                "struct",
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?struct\s+([A-Za-z0-9_]+)",
+            ),
+            (
+                "enum",
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?enum\s+([A-Za-z0-9_]+)",
+            ),
+            (
+                "trait",
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?trait\s+([A-Za-z0-9_]+)",
+            ),
+            (
+                "type",
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?type\s+([A-Za-z0-9_]+)",
+            ),
+        ],
+        "Python" => &[
+            ("def", r"^\s*(?:async\s+)?def\s+([A-Za-z0-9_]+)"),
+            ("class", r"^\s*class\s+([A-Za-z0-9_]+)"),
+        ],
+        "JavaScript" | "TypeScript" => &[
+            (
+                "function",
+                r"^\s*(?:export\s+)?(?:async\s+)?function\s+([A-Za-z0-9_$]+)",
+            ),
+            ("class", r"^\s*(?:export\s+)?class\s+([A-Za-z0-9_$]+)"),
+            (
+                "interface",
+                r"^\s*(?:export\s+)?interface\s+([A-Za-z0-9_$]+)",
+            ),
+            ("type", r"^\s*(?:export\s+)?type\s+([A-Za-z0-9_$]+)\s*="),
+        ],
+        "Go" => &[
+            ("func", r"^func\s+(?:\([^)]*\)\s+)?([A-Za-z0-9_]+)"),
+            ("type", r"^type\s+([A-Za-z0-9_]+)"),
+        ],
+        _ => return Vec::new(),
+    };
+    let compiled: Vec<(&str, regex::Regex)> = patterns
+        .iter()
+        .map(|(kind, p)| (*kind, regex::Regex::new(p).expect("static regex")))
+        .collect();
+    let mut symbols = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        for (kind, re) in &compiled {
+            if let Some(caps) = re.captures(line) {
+                symbols.push(Symbol {
+                    name: caps[1].to_string(),
+                    kind: kind.to_string(),
+                    line: i + 1,
+                });
+                break;
+            }
+        }
+    }
+    symbols
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolLocation {
+    pub path: std::path::PathBuf,
+    pub line: usize,
+    pub kind: String,
+}
+
+/// Locate a symbol by name across a tree. `name` may carry a `::` module
+/// qualifier (`foo::bar`), in which case every qualifier segment must
+/// appear in the defining file's path.
+pub fn find_symbol(root: &Path, name: &str) -> Result<Vec<SymbolLocation>> {
+    let mut segments: Vec<&str> = name.split("::").collect();
+    let bare = segments.pop().unwrap_or(name);
+    let mut hits = Vec::new();
+    for path in crate::commands::files::walk_files(root, &[])? {
+        let language = language_for_path(&path);
+        if matches!(language, "Other" | "Markdown" | "TOML" | "YAML" | "JSON") {
+            continue;
+        }
+        let path_str = path.display().to_string();
+        if !segments.iter().all(|seg| {
+            Path::new(&path_str)
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with(seg))
+        }) {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for symbol in extract_symbols(&content, language) {
+            if symbol.name == bare {
+                hits.push(SymbolLocation {
+                    path: path.clone(),
+                    line: symbol.line,
+                    kind: symbol.kind,
+                });
+            }
+        }
+    }
+    Ok(hits)
+}
+
+/// Files worth bundling alongside `file` in a prompt: locally resolvable
+/// imports plus the conventional test file locations.
+pub fn related_files(file: &Path) -> Vec<std::path::PathBuf> {
+    let mut related = Vec::new();
+    let dir = file.parent().unwrap_or(Path::new("."));
+    let Ok(content) = std::fs::read_to_string(file) else {
+        return related;
+    };
+    let language = language_for_path(file);
+
+    // Sibling modules named by imports.
+    let import_re = match language {
+        "Rust" => regex::Regex::new(r"(?m)^\s*(?:pub\s+)?(?:mod|use crate::)\s*([A-Za-z0-9_]+)"),
+        "Python" => regex::Regex::new(r"(?m)^\s*(?:from|import)\s+\.?([A-Za-z0-9_]+)"),
+        "JavaScript" | "TypeScript" => {
+            regex::Regex::new(r#"(?m)from\s+["']\./([A-Za-z0-9_\-]+)["']"#)
+        }
+        _ => return related,
+    }
+    .expect("static regex");
+    let ext = file.extension().and_then(|e| e.to_str()).unwrap_or("");
+    for caps in import_re.captures_iter(&content) {
+        let name = &caps[1];
+        for candidate in [
+            dir.join(format!("{name}.{ext}")),
+            dir.join(name).join(format!("mod.{ext}")),
+            dir.join(name).join(format!("index.{ext}")),
+        ] {
+            if candidate.exists() && candidate != file && !related.contains(&candidate) {
+                related.push(candidate);
+                break;
+            }
+        }
+    }
+
+    // Conventional test file locations.
+    if let Some(stem) = file.file_stem().and_then(|s| s.to_str()) {
+        for candidate in [
+            dir.join(format!("{stem}_test.{ext}")),
+            dir.join(format!("{stem}.test.{ext}")),
+            dir.join("tests").join(format!("{stem}.{ext}")),
+            Path::new("tests").join(format!("{stem}.{ext}")),
+        ] {
+            if candidate.exists() && !related.contains(&candidate) {
+                related.push(candidate);
+            }
+        }
+    }
+    related
+}
+
+/// A direct dependency from a manifest. `ecosystem` uses the OSV names
+/// so advisory queries can pass it through unchanged.
+#[derive(Debug, Clone, Serialize)]
+pub struct Dependency {
+    pub name: String,
+    /// The raw version requirement as written in the manifest.
+    pub requirement: String,
+    pub ecosystem: &'static str,
+}
+
+/// Direct dependencies from the common manifest formats: Cargo.toml,
+/// package.json, and requirements.txt.
+pub fn parse_dependencies(root: &Path) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    if let Ok(raw) = std::fs::read_to_string(root.join("Cargo.toml")) {
+        if let Ok(value) = raw.parse::<toml::Value>() {
+            for section in ["dependencies", "dev-dependencies"] {
+                if let Some(table) = value.get(section).and_then(|d| d.as_table()) {
+                    for (name, spec) in table {
+                        let requirement = spec
+                            .as_str()
+                            .or_else(|| spec.get("version").and_then(|v| v.as_str()))
+                            .unwrap_or("*")
+                            .to_string();
+                        deps.push(Dependency {
+                            name: name.clone(),
+                            requirement,
+                            ecosystem: "crates.io",
+                        });
+                    }
+                }
+            }
+        }
+    }
+    if let Ok(raw) = std::fs::read_to_string(root.join("package.json")) {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) {
+            for section in ["dependencies", "devDependencies"] {
+                if let Some(obj) = value.get(section).and_then(|d| d.as_object()) {
+                    for (name, spec) in obj {
+                        deps.push(Dependency {
+                            name: name.clone(),
+                            requirement: spec.as_str().unwrap_or("*").to_string(),
+                            ecosystem: "npm",
+                        });
+                    }
+                }
+            }
+        }
+    }
+    if let Ok(raw) = std::fs::read_to_string(root.join("requirements.txt")) {
+        for line in raw.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with('-') {
+                continue;
+            }
+            let split = line.find(['=', '>', '<', '~', '!', ';', '[']);
+            let (name, requirement) = match split {
+                Some(i) => (&line[..i], line[i..].trim_start_matches(';').trim()),
+                None => (line, ""),
+            };
+            deps.push(Dependency {
+                name: name.trim().to_string(),
+                requirement: requirement.to_string(),
+                ecosystem: "PyPI",
+            });
+        }
+    }
+    deps
+}
+
+/// Dependency manifest summary: the direct dependency names.
+pub fn check_dependencies(root: &Path) -> Vec<String> {
+    parse_dependencies(root)
+        .into_iter()
+        .map(|d| d.name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_detection() {
+        assert_eq!(language_for_path(Path::new("a/b.rs")), "Rust");
+        assert_eq!(language_for_path(Path::new("x.unknown")), "Other");
+    }
+
+    #[test]
+    fn extracts_rust_symbols() {
+        let content = "pub struct Foo;\nimpl Foo {\n    pub async fn bar(&self) {}\n}\n";
+        let symbols = extract_symbols(content, "Rust");
+        let names: Vec<_> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Foo", "bar"]);
+        assert_eq!(symbols[1].kind, "fn");
+        assert_eq!(symbols[1].line, 3);
+    }
+}
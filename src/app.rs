@@ -0,0 +1,252 @@
+//! Shared per-invocation state handed to every command handler.
+
+use anyhow::{bail, Context as _, Result};
+use tokio_util::sync::CancellationToken;
+
+use crate::cancel::INTERRUPTED;
+use crate::config::{Config, Profile};
+use crate::llm::{ChatMessage, ChatRequest, ChatResponse, Provider, ProviderRegistry};
+use crate::render::Renderer;
+
+/// Sentinel error raised after `--show-prompt` prints its preview;
+/// `main` maps it to a successful exit, mirroring [`INTERRUPTED`].
+pub const DRY_RUN_LLM: &str = "dry-run-llm";
+
+pub struct AppContext {
+    pub config: Config,
+    pub render: Renderer,
+    pub verbose: bool,
+    pub profile_name: String,
+    pub model_override: Option<String>,
+    pub provider_override: Option<String>,
+    pub cancel: CancellationToken,
+    /// The workspace root every path-relative feature operates on:
+    /// `--workspace` when given, else detected from the current directory.
+    pub workspace: std::path::PathBuf,
+    /// `None` when `--no-redact` was given.
+    pub redactor: Option<crate::redact::Redactor>,
+    /// Path denylist; `None` when `--allow-denied` was given.
+    pub deny: Option<crate::redact::PathPolicy>,
+    /// `--show-prompt`: preview the assembled request instead of sending.
+    pub show_prompt: bool,
+    /// Token usage across this invocation's LLM calls, consumed by the
+    /// local stats recorder.
+    pub usage: crate::stats::SharedTally,
+}
+
+impl AppContext {
+    /// The active profile with CLI overrides applied.
+    pub fn profile(&self) -> Result<Profile> {
+        let mut profile = self.config.profile(&self.profile_name)?.clone();
+        if let Some(p) = &self.provider_override {
+            profile.provider = p.clone();
+            // A provider switch invalidates a profile-pinned api_base.
+            profile.api_base = None;
+        }
+        if let Some(m) = &self.model_override {
+            profile.model = m.clone();
+        }
+        Ok(profile)
+    }
+
+    pub fn provider(&self) -> Result<Box<dyn Provider>> {
+        let profile = self.profile()?;
+        ProviderRegistry::create(&self.config, &profile)
+    }
+
+    /// Build a request from profile defaults plus the given messages.
+    /// Under `--show-prompt` the fully assembled request is printed and a
+    /// [`DRY_RUN_LLM`] error unwinds before anything reaches a provider.
+    pub fn chat_request(&self, messages: Vec<ChatMessage>) -> Result<ChatRequest> {
+        let profile = self.profile()?;
+        // Per-model presets fill whatever the profile leaves unset.
+        let caps = self.config.model_caps.get(&profile.model);
+        let req = ChatRequest {
+            temperature: profile
+                .temperature
+                .or_else(|| caps.and_then(|c| c.temperature)),
+            max_tokens: profile
+                .max_tokens
+                .or_else(|| caps.and_then(|c| c.max_tokens)),
+            stop: caps.map(|c| c.stop.clone()).unwrap_or_default(),
+            reasoning_effort: profile.reasoning_effort,
+            model: profile.model,
+            messages,
+        };
+        if self.show_prompt {
+            self.preview_prompt(&req);
+            bail!(DRY_RUN_LLM);
+        }
+        self.preflight_context(req)
+    }
+
+    /// Estimate the prompt against the model's context window and apply
+    /// the configured `context_overflow` policy. Chunking commands split
+    /// their input before it gets here, so `chunk` behaves like `error`
+    /// for everything else.
+    fn preflight_context(&self, mut req: ChatRequest) -> Result<ChatRequest> {
+        let estimated: usize = req
+            .messages
+            .iter()
+            .map(|m| crate::context::estimate_tokens(&m.content))
+            .sum();
+        // Leave room for the completion; the provider counts both sides.
+        let reserve = req.max_tokens.unwrap_or(1024) as usize;
+        let window = self.config.context_window_for(&req.model);
+        if estimated + reserve <= window {
+            return Ok(req);
+        }
+        if self.config.context_overflow == crate::config::ContextOverflowPolicy::Fallback {
+            if let Some(fallback) = &self.config.fallback_model {
+                let fallback_window = self.config.context_window_for(fallback);
+                if *fallback != req.model && estimated + reserve <= fallback_window {
+                    self.render.warn(&format!(
+                        "prompt (~{estimated} tokens) exceeds {}'s context window \
+                         of {window}; falling back to {fallback}",
+                        req.model
+                    ));
+                    req.model = fallback.clone();
+                    return Ok(req);
+                }
+            }
+        }
+        bail!(crate::error::SwError::ContextOverflow {
+            model: req.model,
+            estimated_tokens: estimated,
+            context_window: window,
+        })
+    }
+
+    /// Print the exact messages a provider would receive — after
+    /// truncation, redaction, and template rendering — with a token
+    /// estimate.
+    fn preview_prompt(&self, req: &ChatRequest) {
+        #[derive(serde::Serialize)]
+        struct Preview<'a> {
+            model: &'a str,
+            messages: &'a [ChatMessage],
+            estimated_tokens: usize,
+        }
+        let estimated: usize = req
+            .messages
+            .iter()
+            .map(|m| crate::context::estimate_tokens(&m.content))
+            .sum();
+        let preview = Preview {
+            model: &req.model,
+            messages: &req.messages,
+            estimated_tokens: estimated,
+        };
+        self.render.emit(&preview, || {
+            let mut s = String::new();
+            for m in &req.messages {
+                s.push_str(&format!("--- {:?} ---\n{}\n", m.role, m.content));
+            }
+            if req.messages.is_empty() {
+                s.push_str("(messages are assembled later by this command)\n");
+            }
+            s.push_str(&format!(
+                "estimated {estimated} prompt token(s) for {}",
+                req.model
+            ));
+            s
+        });
+    }
+
+    /// One-shot completion with profile defaults.
+    pub async fn complete(&self, messages: Vec<ChatMessage>) -> Result<ChatResponse> {
+        let req = self.chat_request(messages)?;
+        let provider = self.provider()?;
+        if self.verbose {
+            self.render
+                .status(&format!("model: {} via {}", req.model, provider.name()));
+        }
+        let resp = tokio::select! {
+            r = provider.send(&req) => r?,
+            _ = self.cancel.cancelled() => bail!(INTERRUPTED),
+        };
+        self.report_meta(&resp);
+        Ok(resp)
+    }
+
+    /// Print per-call metrics on stderr when `-v` is set, and fold the
+    /// call's usage into the invocation tally.
+    pub fn report_meta(&self, resp: &ChatResponse) {
+        if self.verbose {
+            self.render.status(&resp.meta.summary());
+        }
+        let mut tally = self.usage.lock().unwrap();
+        tally.prompt_tokens += resp.meta.prompt_tokens.unwrap_or(0);
+        tally.completion_tokens += resp.meta.completion_tokens.unwrap_or(0);
+        tally.model = Some(resp.model.clone());
+    }
+
+    /// Copy an artifact to the system clipboard for `--copy`, warning
+    /// instead of failing when no clipboard is available.
+    pub fn copy_artifact(&self, text: &str) {
+        if !self.config.clipboard {
+            self.render
+                .warn("--copy ignored: clipboard disabled in config");
+            return;
+        }
+        match crate::platform::copy_to_clipboard(text) {
+            Ok(()) => self.render.status("copied to clipboard"),
+            Err(e) => self.render.warn(&format!("{e:#}")),
+        }
+    }
+
+    /// Fail when the content policy forbids sending `path` to a provider.
+    /// Commands call this before reading any user-named file into a
+    /// prompt; `--allow-denied` disables the check.
+    pub fn ensure_sendable(&self, path: &std::path::Path) -> Result<()> {
+        if let Some(policy) = &self.deny {
+            if let Some(pattern) = policy.matched(path) {
+                bail!(crate::error::SwError::Blocked {
+                    path: path.display().to_string(),
+                    pattern: pattern.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Mask secrets in content bound for a prompt, reporting what was
+    /// masked on stderr. Pass-through when `--no-redact` is set.
+    pub fn redact(&self, text: &str) -> String {
+        let Some(redactor) = &self.redactor else {
+            return text.to_string();
+        };
+        let (out, hits) = redactor.redact(text);
+        for hit in hits {
+            self.render
+                .warn(&format!("redacted {} match(es) of {}", hit.count, hit.rule));
+        }
+        out
+    }
+
+    /// Resolve the system prompt from `--system`, `--system-file`, or a
+    /// configured persona. Returns `None` when nothing was requested.
+    pub fn system_prompt(&self, args: &crate::cli::SystemArgs) -> Result<Option<String>> {
+        if let Some(text) = &args.system {
+            return Ok(Some(text.clone()));
+        }
+        if let Some(path) = &args.system_file {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read system prompt from {}", path.display()))?;
+            return Ok(Some(text.trim().to_string()));
+        }
+        if let Some(name) = &args.persona {
+            let text = self.config.personas.get(name).with_context(|| {
+                format!("persona '{name}' not found; add [persona] entries to config")
+            })?;
+            return Ok(Some(text.clone()));
+        }
+        Ok(None)
+    }
+
+    /// Context window for the active model.
+    pub fn context_window(&self) -> Result<usize> {
+        let profile = self.profile()?;
+        Ok(self.config.context_window_for(&profile.model))
+    }
+}
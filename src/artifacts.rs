@@ -0,0 +1,206 @@
+//! Binary artifact storage for provider output that is not text.
+//!
+//! Images and audio returned by a provider or tool never go to stdout —
+//! raw bytes corrupt the terminal and break the data-only contract.
+//! They land under `.sw/artifacts/<run>/` instead, each run carrying a
+//! `manifest.json`, and commands print the saved paths.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactRecord {
+    /// File name within the run directory.
+    pub file: String,
+    pub bytes: u64,
+    pub sha256: String,
+    /// What produced the artifact (command name, tool, model).
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    artifacts: Vec<ArtifactRecord>,
+}
+
+/// One run's artifact directory; every `save` updates the manifest so a
+/// partial run still leaves an accurate record.
+pub struct ArtifactStore {
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+/// Sniff a file extension from magic bytes; unknown formats fall back to
+/// `bin` rather than guessing from a provider-supplied name.
+pub fn detect_extension(bytes: &[u8]) -> &'static str {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => "png",
+        [0xFF, 0xD8, 0xFF, ..] => "jpg",
+        [b'G', b'I', b'F', b'8', ..] => "gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => "wav",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "webp",
+        [0x49, 0x44, 0x33, ..] | [0xFF, 0xFB, ..] => "mp3",
+        [b'<', b's', b'v', b'g', ..] | [b'<', b'?', b'x', b'm', b'l', ..] => "svg",
+        _ => "bin",
+    }
+}
+
+impl ArtifactStore {
+    /// Open `.sw/artifacts/<command>-<timestamp>/` under the workspace,
+    /// creating it on first save rather than eagerly.
+    pub fn begin(workspace: &Path, command: &str) -> Self {
+        let run = format!("{command}-{}", Utc::now().format("%Y%m%d-%H%M%S"));
+        Self {
+            dir: workspace.join(".sw").join("artifacts").join(run),
+            manifest: Manifest::default(),
+        }
+    }
+
+    /// Where this run's artifacts live.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write one artifact and its manifest entry; returns the saved path.
+    /// `name_hint` names the file sans extension — the extension is
+    /// sniffed from the bytes, never trusted from the provider.
+    pub fn save(&mut self, name_hint: &str, bytes: &[u8], source: &str) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        let file = format!("{}.{}", sanitize_name(name_hint), detect_extension(bytes));
+        let path = self.dir.join(&file);
+        std::fs::write(&path, bytes)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        self.manifest.artifacts.push(ArtifactRecord {
+            file,
+            bytes: bytes.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(bytes)),
+            source: source.to_string(),
+            created_at: Utc::now(),
+        });
+        let manifest_path = self.dir.join("manifest.json");
+        std::fs::write(
+            &manifest_path,
+            serde_json::to_string_pretty(&self.manifest)?,
+        )
+        .with_context(|| format!("failed to write {}", manifest_path.display()))?;
+        Ok(path)
+    }
+
+    /// Records saved so far, in save order.
+    pub fn records(&self) -> &[ArtifactRecord] {
+        &self.manifest.artifacts
+    }
+}
+
+/// Replace inline `data:<mime>;base64,<payload>` URIs with the paths of
+/// decoded artifact files. Text without such URIs passes through
+/// untouched (and no run directory is created). Returns the rewritten
+/// text and the saved paths.
+pub fn rewrite_data_uris(
+    text: &str,
+    store: &mut ArtifactStore,
+    source: &str,
+) -> Result<(String, Vec<PathBuf>)> {
+    use base64::Engine;
+    if !text.contains(";base64,") {
+        return Ok((text.to_string(), Vec::new()));
+    }
+    // The length floor keeps prose that merely mentions data URIs intact.
+    let re = regex::Regex::new(r"data:[a-z]+/[A-Za-z0-9.+-]+;base64,([A-Za-z0-9+/=]{64,})")
+        .expect("static regex");
+    let mut out = String::new();
+    let mut saved = Vec::new();
+    let mut last = 0;
+    for caps in re.captures_iter(text) {
+        let whole = caps.get(0).expect("full match");
+        let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(&caps[1]) else {
+            continue;
+        };
+        out.push_str(&text[last..whole.start()]);
+        let path = store.save(&format!("artifact-{}", saved.len() + 1), &bytes, source)?;
+        out.push_str(&path.display().to_string());
+        saved.push(path);
+        last = whole.end();
+    }
+    out.push_str(&text[last..]);
+    Ok((out, saved))
+}
+
+/// Keep artifact names path-safe: providers may hand back anything.
+fn sanitize_name(hint: &str) -> String {
+    let cleaned: String = hint
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_matches('-').to_string();
+    if cleaned.is_empty() {
+        "artifact".to_string()
+    } else {
+        cleaned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_common_formats() {
+        assert_eq!(
+            detect_extension(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A]),
+            "png"
+        );
+        assert_eq!(detect_extension(&[0xFF, 0xD8, 0xFF, 0xE0]), "jpg");
+        assert_eq!(detect_extension(b"<svg xmlns=\"x\">"), "svg");
+        assert_eq!(detect_extension(b"mystery bytes"), "bin");
+    }
+
+    #[test]
+    fn data_uris_become_artifact_paths() {
+        use base64::Engine;
+        let tmp =
+            std::env::temp_dir().join(format!("sw-artifacts-uri-test-{}", std::process::id()));
+        let mut png = vec![0x89, b'P', b'N', b'G'];
+        png.resize(64, 0);
+        let payload = base64::engine::general_purpose::STANDARD.encode(&png);
+        let text = format!("Here you go: data:image/png;base64,{payload} — enjoy.");
+        let mut store = ArtifactStore::begin(&tmp, "ask");
+        let (rewritten, saved) = rewrite_data_uris(&text, &mut store, "test").unwrap();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].exists());
+        assert!(rewritten.contains("artifact-1.png"));
+        assert!(!rewritten.contains(";base64,"));
+        // Prose without a payload is untouched.
+        let (plain, none) = rewrite_data_uris("no images here", &mut store, "test").unwrap();
+        assert_eq!(plain, "no images here");
+        assert!(none.is_empty());
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+
+    #[test]
+    fn save_writes_file_and_manifest() {
+        let tmp = std::env::temp_dir().join(format!("sw-artifacts-test-{}", std::process::id()));
+        let mut store = ArtifactStore::begin(&tmp, "diagram");
+        let path = store
+            .save("chart/../weird name", &[0x89, b'P', b'N', b'G', 0], "test")
+            .unwrap();
+        assert!(path.ends_with("chart----weird-name.png"));
+        assert!(path.exists());
+        assert!(store.dir().join("manifest.json").exists());
+        assert_eq!(store.records().len(), 1);
+        assert_eq!(store.records()[0].bytes, 5);
+        std::fs::remove_dir_all(&tmp).unwrap();
+    }
+}
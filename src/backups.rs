@@ -0,0 +1,141 @@
+//! Managed backups for destructive rewrites.
+//!
+//! Instead of scattering `*.backup` files next to sources, every backup
+//! goes under `.sw/backups/<timestamp>/` with an index, old batches are
+//! pruned automatically, and `sw backups list/restore` inspects them.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Timestamped batches kept when pruning; older ones are deleted.
+const KEEP_BATCHES: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupBatch {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub path: String,
+    pub size: u64,
+}
+
+fn backup_root() -> PathBuf {
+    PathBuf::from(".sw").join("backups")
+}
+
+/// Copy `path` into the current batch (one timestamped directory per
+/// second, so files rewritten by one command land together) and record
+/// it in the batch index. Old batches are pruned afterwards.
+pub fn backup_file(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let id = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    let dir = backup_root().join(&id);
+    let rel = path.strip_prefix(".").unwrap_or(path);
+    let dest = dir.join("files").join(rel);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::copy(path, &dest).with_context(|| format!("failed to back up {}", path.display()))?;
+    crate::platform::copy_permissions(path, &dest)?;
+
+    let index_path = dir.join("index.json");
+    let mut batch = match std::fs::read_to_string(&index_path) {
+        Ok(raw) => serde_json::from_str(&raw)
+            .with_context(|| format!("corrupt backup index at {}", index_path.display()))?,
+        Err(_) => BackupBatch {
+            id,
+            created_at: Utc::now(),
+            files: Vec::new(),
+        },
+    };
+    batch.files.push(BackupEntry {
+        path: crate::platform::to_portable(rel),
+        size: std::fs::metadata(path)?.len(),
+    });
+    std::fs::write(&index_path, serde_json::to_string_pretty(&batch)?)?;
+    prune()?;
+    Ok(())
+}
+
+/// All batches, oldest first.
+pub fn list_backups() -> Result<Vec<BackupBatch>> {
+    let root = backup_root();
+    let mut batches = Vec::new();
+    if !root.exists() {
+        return Ok(batches);
+    }
+    for entry in std::fs::read_dir(&root)? {
+        let index_path = entry?.path().join("index.json");
+        if !index_path.exists() {
+            continue;
+        }
+        let raw = std::fs::read_to_string(&index_path)?;
+        let batch: BackupBatch = serde_json::from_str(&raw)
+            .with_context(|| format!("corrupt backup index at {}", index_path.display()))?;
+        batches.push(batch);
+    }
+    batches.sort_by_key(|b| b.created_at);
+    Ok(batches)
+}
+
+/// Copy a batch's files back into the tree; `file` restricts the restore
+/// to one original path. Returns how many files were restored.
+pub fn restore_backup(id: &str, file: Option<&Path>) -> Result<usize> {
+    let dir = backup_root().join(id);
+    let index_path = dir.join("index.json");
+    if !index_path.exists() {
+        bail!("backup '{id}' not found");
+    }
+    let batch: BackupBatch = serde_json::from_str(&std::fs::read_to_string(&index_path)?)?;
+    let wanted = file.map(|f| crate::platform::to_portable(f.strip_prefix(".").unwrap_or(f)));
+    let mut restored = 0usize;
+    for entry in &batch.files {
+        if wanted.as_deref().is_some_and(|w| w != entry.path) {
+            continue;
+        }
+        let src = dir.join("files").join(&entry.path);
+        let dest = PathBuf::from(&entry.path);
+        if let Some(parent) = dest.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::copy(&src, &dest)
+            .with_context(|| format!("failed to restore {}", dest.display()))?;
+        crate::platform::copy_permissions(&src, &dest)?;
+        restored += 1;
+    }
+    if restored == 0 {
+        if let Some(w) = wanted {
+            bail!("backup '{id}' has no entry for {w}");
+        }
+    }
+    Ok(restored)
+}
+
+/// Delete the oldest batches beyond [`KEEP_BATCHES`].
+fn prune() -> Result<()> {
+    let root = backup_root();
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(&root)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    // Timestamped names sort chronologically.
+    dirs.sort();
+    while dirs.len() > KEEP_BATCHES {
+        let oldest = dirs.remove(0);
+        std::fs::remove_dir_all(&oldest)
+            .with_context(|| format!("failed to prune backup {}", oldest.display()))?;
+    }
+    Ok(())
+}
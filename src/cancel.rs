@@ -0,0 +1,25 @@
+//! Cooperative cancellation wired to Ctrl-C.
+//!
+//! A single [`CancellationToken`] is created at startup and handed to every
+//! command through the app context. Long-running work `select!`s against
+//! `cancelled()` so an interrupt aborts in-flight provider requests,
+//! finalizes any partial session records, and restores auto-checkpoints
+//! before the process exits.
+
+use tokio_util::sync::CancellationToken;
+
+/// Message used for the interrupt error so `classify_error` maps it to a
+/// stable code and exit status.
+pub const INTERRUPTED: &str = "interrupted";
+
+/// Install the Ctrl-C listener and return the token it cancels.
+pub fn install_ctrl_c() -> CancellationToken {
+    let token = CancellationToken::new();
+    let t = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            t.cancel();
+        }
+    });
+    token
+}
@@ -0,0 +1,169 @@
+//! Workspace checkpoints: cheap full-copy snapshots. By default they
+//! live under the OS data dir, namespaced per workspace so snapshots
+//! never pollute the repo and do not fragment when commands run from a
+//! subdirectory; `checkpoints_in_repo = true` keeps the old
+//! `.sw-checkpoints/` layout.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::commands::files::walk_files;
+use crate::fsutil::hash_file;
+
+pub const CHECKPOINT_DIR: &str = ".sw-checkpoints";
+
+/// Files larger than this are not snapshotted.
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointManifest {
+    pub id: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<CheckpointFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointFile {
+    pub path: String,
+    pub size: u64,
+    pub hash: String,
+}
+
+fn checkpoint_root(workspace: &Path, in_repo: bool) -> Result<PathBuf> {
+    if in_repo {
+        return Ok(workspace.join(CHECKPOINT_DIR));
+    }
+    // Hash the canonical workspace path so distinct projects with the
+    // same directory name do not share a namespace; keep the name in
+    // front so the data dir stays human-navigable.
+    let canonical = workspace
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.to_path_buf());
+    use sha2::Digest;
+    let digest = sha2::Sha256::digest(canonical.to_string_lossy().as_bytes());
+    let hash = &format!("{digest:x}")[..12];
+    let name = canonical
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("workspace");
+    Ok(crate::config::Config::data_dir()?
+        .join("checkpoints")
+        .join(format!("{name}-{hash}")))
+}
+
+/// Directory holding checkpoint `id`, checking the legacy in-repo
+/// location so snapshots made before the move stay restorable.
+fn checkpoint_dir(workspace: &Path, id: &str, in_repo: bool) -> Result<PathBuf> {
+    let dir = checkpoint_root(workspace, in_repo)?.join(id);
+    if dir.join("manifest.json").exists() {
+        return Ok(dir);
+    }
+    let legacy = workspace.join(CHECKPOINT_DIR).join(id);
+    if !in_repo && legacy.join("manifest.json").exists() {
+        return Ok(legacy);
+    }
+    bail!("checkpoint '{id}' not found");
+}
+
+pub fn create_checkpoint(
+    workspace: &Path,
+    description: Option<String>,
+    in_repo: bool,
+) -> Result<CheckpointManifest> {
+    let id = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    let dir = checkpoint_root(workspace, in_repo)?.join(&id);
+    let files_dir = dir.join("files");
+    std::fs::create_dir_all(&files_dir)?;
+
+    let mut manifest_files = Vec::new();
+    for path in walk_files(workspace, &[])? {
+        let meta = std::fs::metadata(&path)?;
+        if meta.len() > MAX_FILE_BYTES {
+            continue;
+        }
+        let rel = path
+            .strip_prefix(workspace)
+            .context("walked file outside workspace")?;
+        let dest = files_dir.join(rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&path, &dest)
+            .with_context(|| format!("failed to snapshot {}", path.display()))?;
+        manifest_files.push(CheckpointFile {
+            path: crate::platform::to_portable(rel),
+            size: meta.len(),
+            hash: hash_file(&path)?,
+        });
+    }
+
+    let manifest = CheckpointManifest {
+        id,
+        description,
+        created_at: Utc::now(),
+        files: manifest_files,
+    };
+    std::fs::write(
+        dir.join("manifest.json"),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(manifest)
+}
+
+pub fn list_checkpoints(workspace: &Path, in_repo: bool) -> Result<Vec<CheckpointManifest>> {
+    let mut roots = vec![checkpoint_root(workspace, in_repo)?];
+    if !in_repo {
+        // Snapshots made before the move to the data dir still count.
+        roots.push(workspace.join(CHECKPOINT_DIR));
+    }
+    let mut manifests = Vec::new();
+    for root in roots {
+        if !root.exists() {
+            continue;
+        }
+        for entry in std::fs::read_dir(&root)? {
+            let entry = entry?;
+            let manifest_path = entry.path().join("manifest.json");
+            if !manifest_path.exists() {
+                continue;
+            }
+            let raw = std::fs::read_to_string(&manifest_path)?;
+            let manifest: CheckpointManifest = serde_json::from_str(&raw)
+                .with_context(|| format!("corrupt manifest at {}", manifest_path.display()))?;
+            manifests.push(manifest);
+        }
+    }
+    manifests.sort_by_key(|m| m.created_at);
+    Ok(manifests)
+}
+
+pub fn load_checkpoint(workspace: &Path, id: &str, in_repo: bool) -> Result<CheckpointManifest> {
+    let manifest_path = checkpoint_dir(workspace, id, in_repo)?.join("manifest.json");
+    let raw = std::fs::read_to_string(&manifest_path)?;
+    serde_json::from_str(&raw)
+        .with_context(|| format!("corrupt manifest at {}", manifest_path.display()))
+}
+
+pub fn restore_checkpoint(workspace: &Path, id: &str, in_repo: bool) -> Result<usize> {
+    let dir = checkpoint_dir(workspace, id, in_repo)?;
+    let manifest: CheckpointManifest =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("manifest.json"))?)?;
+    let files_dir = dir.join("files");
+    let mut restored = 0usize;
+    for file in &manifest.files {
+        let src = files_dir.join(&file.path);
+        let dest = workspace.join(&file.path);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(&src, &dest)
+            .with_context(|| format!("failed to restore {}", dest.display()))?;
+        crate::platform::copy_permissions(&src, &dest)?;
+        restored += 1;
+    }
+    Ok(restored)
+}
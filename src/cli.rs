@@ -0,0 +1,1631 @@
+//! Command-line interface definitions.
+
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::render::OutputFormat;
+
+#[derive(Debug, Parser)]
+#[command(name = "sw", version, about = "LLM-powered software assistant")]
+pub struct Cli {
+    /// Output format for data on stdout.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Suppress status output on stderr.
+    #[arg(long, short, global = true)]
+    pub quiet: bool,
+
+    /// Verbose diagnostics on stderr.
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+
+    /// Config profile to use.
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Override the model for this invocation.
+    #[arg(long, global = true)]
+    pub model: Option<String>,
+
+    /// Override the provider for this invocation.
+    #[arg(long, global = true)]
+    pub provider: Option<String>,
+
+    /// Disable secret redaction of content sent to providers.
+    #[arg(long, global = true)]
+    pub no_redact: bool,
+
+    /// Send files the `deny_paths` content policy would block.
+    #[arg(long, global = true)]
+    pub allow_denied: bool,
+
+    /// Operate on this workspace root instead of auto-detecting it from
+    /// the current directory.
+    #[arg(long, global = true, value_name = "PATH")]
+    pub workspace: Option<PathBuf>,
+
+    /// Print the assembled prompt and a token estimate instead of calling
+    /// the provider.
+    #[arg(long, global = true, alias = "dry-run-llm")]
+    pub show_prompt: bool,
+
+    /// Stream NDJSON events (delta/usage/done/error) on stdout while the
+    /// model responds.
+    #[arg(long, global = true)]
+    pub json_stream: bool,
+
+    /// Write the result to a file (respecting --format) and print only a
+    /// status line, keeping warnings off the captured output.
+    #[arg(long, global = true)]
+    pub output: Option<PathBuf>,
+
+    /// Print model output as-is instead of styling markdown for the
+    /// terminal.
+    #[arg(long, global = true)]
+    pub raw: bool,
+
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// Set up configuration interactively.
+    Init,
+    /// Ask the model a one-off question.
+    Ask(AskArgs),
+    /// Interactive chat session.
+    Chat(ChatArgs),
+    /// Summarize a file.
+    Summarize(SummarizeArgs),
+    /// Explain code.
+    Explain(ExplainArgs),
+    /// Review code or a diff.
+    Review(ReviewArgs),
+    /// Generate a commit message from staged changes.
+    CommitMsg(CommitMsgArgs),
+    /// Lint existing commit messages against the configured convention.
+    CommitLint(CommitLintArgs),
+    /// Generate a file from an instruction.
+    Generate(GenerateArgs),
+    /// Propose and apply diffs.
+    Diff(DiffArgs),
+    /// Search files (ripgrep frontend).
+    Grep(GrepArgs),
+    /// Ranked repository outline (paths, key symbols, sizes).
+    Map(MapArgs),
+    /// Mermaid diagrams of the codebase.
+    Diagram(DiagramArgs),
+    /// File operations: list, compare, sync, duplicates, analyze, security.
+    Files(FilesArgs),
+    /// Run scripts.
+    Script(ScriptArgs),
+    /// Run a command and explain its failure.
+    Run(RunArgs),
+    /// CI helpers.
+    Ci(CiArgs),
+    /// Test suite analysis.
+    Tests(TestsArgs),
+    /// Workspace checkpoints.
+    Checkpoint(CheckpointArgs),
+    /// Managed backups taken before destructive rewrites.
+    Backups(BackupsArgs),
+    /// Project templates.
+    Template(TemplateArgs),
+    /// Batch operations over many files.
+    Batch(BatchArgs),
+    /// Guided migration between idioms or frameworks.
+    Migrate(MigrateArgs),
+    /// String catalogs and machine translation.
+    I18n(I18nArgs),
+    /// Model management.
+    Models(ModelsArgs),
+    /// Session management.
+    Session(SessionArgs),
+    /// Autonomous agent (experimental).
+    Agent(AgentArgs),
+    /// Start work from a tracker issue.
+    Issue(IssueArgs),
+    /// Dependency auditing.
+    Deps(DepsArgs),
+    /// Diagnostics for bug reports.
+    Debug(DebugArgs),
+    /// Local usage statistics (never uploaded).
+    Stats(StatsArgs),
+    /// Serve the configured provider stack over local HTTP.
+    Serve(ServeArgs),
+    /// Run one task against several models side by side.
+    Compare(CompareArgs),
+    /// Repeat a previously recorded invocation.
+    Rerun(RerunArgs),
+    /// Invocation history.
+    History(HistoryArgs),
+    /// Generated-code provenance trail.
+    Provenance(ProvenanceArgs),
+    /// Unattended batch reports.
+    Report(ReportArgs),
+    /// Release preparation.
+    Release(ReleaseArgs),
+}
+
+impl Commands {
+    /// Stable command name (including the subcommand) used by the local
+    /// stats recorder.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Commands::Init => "init",
+            Commands::Ask(_) => "ask",
+            Commands::Chat(_) => "chat",
+            Commands::Summarize(_) => "summarize",
+            Commands::Explain(_) => "explain",
+            Commands::Review(_) => "review",
+            Commands::CommitMsg(_) => "commit-msg",
+            Commands::CommitLint(_) => "commit-lint",
+            Commands::Generate(_) => "generate",
+            Commands::Diff(a) => match &a.command {
+                DiffCommands::Propose(_) => "diff propose",
+                DiffCommands::Apply(_) => "diff apply",
+            },
+            Commands::Grep(_) => "grep",
+            Commands::Map(_) => "map",
+            Commands::Diagram(_) => "diagram",
+            Commands::Files(a) => match &a.command {
+                FilesCommands::List(_) => "files list",
+                FilesCommands::Compare(_) => "files compare",
+                FilesCommands::Sync(_) => "files sync",
+                FilesCommands::Duplicates(_) => "files duplicates",
+                FilesCommands::Analyze(_) => "files analyze",
+                FilesCommands::Security(_) => "files security",
+                FilesCommands::Stats(_) => "files stats",
+            },
+            Commands::Script(_) => "script run",
+            Commands::Run(_) => "run",
+            Commands::Ci(a) => match &a.command {
+                CiCommands::Triage(_) => "ci triage",
+            },
+            Commands::Tests(a) => match &a.command {
+                TestsCommands::Flaky(_) => "tests flaky",
+            },
+            Commands::Checkpoint(a) => match &a.command {
+                CheckpointCommands::Create(_) => "checkpoint create",
+                CheckpointCommands::List(_) => "checkpoint list",
+                CheckpointCommands::Show(_) => "checkpoint show",
+                CheckpointCommands::Restore(_) => "checkpoint restore",
+            },
+            Commands::Backups(a) => match &a.command {
+                BackupsCommands::List => "backups list",
+                BackupsCommands::Restore(_) => "backups restore",
+            },
+            Commands::Template(a) => match &a.command {
+                TemplateCommands::List => "template list",
+                TemplateCommands::Generate(_) => "template generate",
+                TemplateCommands::Upgrade(_) => "template upgrade",
+            },
+            Commands::Batch(_) => "batch transform",
+            Commands::Migrate(_) => "migrate",
+            Commands::I18n(a) => match &a.command {
+                I18nCommands::Extract(_) => "i18n extract",
+                I18nCommands::Translate(_) => "i18n translate",
+            },
+            Commands::Models(a) => match &a.command {
+                ModelsCommands::List(_) => "models list",
+                ModelsCommands::Refresh(_) => "models refresh",
+            },
+            Commands::Session(a) => match &a.command {
+                SessionCommands::List => "session list",
+                SessionCommands::Show(_) => "session show",
+                SessionCommands::Clear(_) => "session clear",
+                SessionCommands::Publish(_) => "session publish",
+                SessionCommands::Export(_) => "session export",
+                SessionCommands::Import(_) => "session import",
+            },
+            Commands::Agent(_) => "agent",
+            Commands::Issue(a) => match &a.command {
+                IssueCommands::Start(_) => "issue start",
+            },
+            Commands::Deps(a) => match &a.command {
+                DepsCommands::Audit(_) => "deps audit",
+            },
+            Commands::Debug(_) => "debug bundle",
+            Commands::Stats(_) => "stats",
+            Commands::Serve(a) => match &a.command {
+                ServeCommands::Openai(_) => "serve openai",
+            },
+            Commands::Compare(_) => "compare",
+            Commands::Rerun(_) => "rerun",
+            Commands::History(a) => match &a.command {
+                HistoryCommands::List => "history list",
+            },
+            Commands::Report(a) => match &a.command {
+                ReportCommands::Nightly(_) => "report nightly",
+            },
+            Commands::Provenance(a) => match &a.command {
+                ProvenanceCommands::Show(_) => "provenance show",
+            },
+            Commands::Release(a) => match &a.command {
+                ReleaseCommands::Prepare(_) => "release prepare",
+            },
+        }
+    }
+}
+
+/// System prompt selection, shared by the conversational commands.
+#[derive(Debug, Args)]
+pub struct SystemArgs {
+    /// System prompt text.
+    #[arg(long)]
+    pub system: Option<String>,
+
+    /// Read the system prompt from a file.
+    #[arg(long, conflicts_with = "system")]
+    pub system_file: Option<PathBuf>,
+
+    /// Named persona from config (`[persona]` table).
+    #[arg(long, conflicts_with_all = ["system", "system_file"])]
+    pub persona: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct AskArgs {
+    /// The question; read from stdin when omitted.
+    pub prompt: Option<String>,
+
+    #[command(flatten)]
+    pub system: SystemArgs,
+
+    /// Record the exchange in a named session.
+    #[arg(long)]
+    pub session: Option<String>,
+
+    /// Continue from the previous ask without naming a session.
+    #[arg(long = "continue", conflicts_with = "session")]
+    pub continue_last: bool,
+
+    /// Stream the response token by token.
+    #[arg(long)]
+    pub stream: bool,
+
+    /// Request N candidate answers and pick one to keep.
+    #[arg(long, value_name = "N", conflicts_with = "stream")]
+    pub choices: Option<usize>,
+
+    /// Attach a file's contents as context (repeatable).
+    #[arg(long = "file")]
+    pub files: Vec<PathBuf>,
+
+    /// Attach a command's captured output as context (repeatable).
+    #[arg(long = "exec")]
+    pub execs: Vec<String>,
+
+    /// Rows sampled into the prompt when an attachment is a CSV/JSON
+    /// data file.
+    #[arg(long, value_name = "N", default_value_t = 20)]
+    pub rows: usize,
+
+    /// Comma-separated columns to keep when profiling a data file.
+    #[arg(long, value_name = "COLS")]
+    pub columns: Option<String>,
+
+    /// Copy the answer to the system clipboard.
+    #[arg(long)]
+    pub copy: bool,
+
+    /// Record the prompt from the default microphone (Enter stops).
+    #[arg(long, conflicts_with = "prompt")]
+    pub voice: bool,
+
+    /// Transcribe this audio file as the prompt instead of recording.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["prompt", "voice"])]
+    pub audio: Option<PathBuf>,
+
+    /// Stream a reasoning model's thinking tokens dimmed on stderr.
+    #[arg(long)]
+    pub show_thinking: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ChatArgs {
+    /// Session to load and append to.
+    #[arg(long, default_value = "chat")]
+    pub session: String,
+
+    #[command(flatten)]
+    pub system: SystemArgs,
+
+    /// Stream a reasoning model's thinking tokens dimmed on stderr.
+    #[arg(long)]
+    pub show_thinking: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SummarizeArgs {
+    /// File to summarize.
+    pub file: PathBuf,
+
+    /// Chunk size in (estimated) tokens for large files.
+    #[arg(long, default_value_t = 4000)]
+    pub chunk_tokens: usize,
+
+    /// Partial summaries merged per synthesis call; huge files are reduced
+    /// tree-wise so no single merge exceeds the context window.
+    #[arg(long, default_value_t = 8)]
+    pub fan_in: usize,
+
+    /// Maximum simultaneous chunk requests.
+    #[arg(long, default_value_t = 4)]
+    pub concurrency: usize,
+
+    /// Output format; free prose when unset.
+    #[arg(long, value_enum)]
+    pub style: Option<SummaryStyle>,
+
+    /// Who the summary is written for.
+    #[arg(long, value_enum)]
+    pub audience: Option<SummaryAudience>,
+
+    /// How much detail to keep.
+    #[arg(long, value_enum)]
+    pub length: Option<SummaryLength>,
+
+    /// Respond in this language instead of English.
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Rows sampled into the prompt for CSV/JSON data files.
+    #[arg(long, value_name = "N", default_value_t = 20)]
+    pub rows: usize,
+
+    /// Comma-separated columns to keep when profiling a data file.
+    #[arg(long, value_name = "COLS")]
+    pub columns: Option<String>,
+
+    /// Record the exchange in a named session for follow-up questions.
+    #[arg(long)]
+    pub session: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum SummaryStyle {
+    Bullets,
+    Abstract,
+    #[value(name = "tl;dr", alias = "tldr")]
+    TlDr,
+    Changelog,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum SummaryAudience {
+    Dev,
+    Manager,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum SummaryLength {
+    Short,
+    Medium,
+    Long,
+}
+
+#[derive(Debug, Args)]
+pub struct ExplainArgs {
+    /// File to explain. Optional with --symbol, where it narrows the search.
+    #[arg(required_unless_present_any = ["symbol", "staged", "diff_file"])]
+    pub file: Option<PathBuf>,
+
+    /// Explain a diff from a file, hunk by hunk.
+    #[arg(long, conflicts_with_all = ["file", "lines", "symbol"])]
+    pub diff_file: Option<PathBuf>,
+
+    /// Explain the staged git diff, hunk by hunk.
+    #[arg(long, conflicts_with_all = ["file", "lines", "symbol", "diff_file"])]
+    pub staged: bool,
+
+    /// Line range `start:end` (1-based, inclusive) to focus on.
+    #[arg(long)]
+    pub lines: Option<String>,
+
+    /// Explain a symbol by name (optionally `module::name`): its definition
+    /// is located across the tree and bundled with its call sites.
+    #[arg(long, conflicts_with = "lines")]
+    pub symbol: Option<String>,
+
+    /// Record the exchange in a named session for follow-up questions.
+    #[arg(long)]
+    pub session: Option<String>,
+}
+
+/// Finding severity levels, ordered from most to least serious.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl Severity {
+    /// Numeric rank for threshold comparisons; higher is more severe.
+    pub fn rank(self) -> u8 {
+        match self {
+            Severity::Critical => 3,
+            Severity::High => 2,
+            Severity::Medium => 1,
+            Severity::Low => 0,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ReviewArgs {
+    /// File to review.
+    pub file: Option<PathBuf>,
+
+    /// Review the staged git diff.
+    #[arg(long, conflicts_with = "file")]
+    pub staged: bool,
+
+    /// Review a diff from a file.
+    #[arg(long, conflicts_with_all = ["file", "staged"])]
+    pub diff_file: Option<PathBuf>,
+
+    /// Review focus: a built-in preset (security, performance, style,
+    /// tests, api) or a custom rubric from `[review_focus]` in config.
+    #[arg(long)]
+    pub focus: Option<String>,
+
+    /// Exit non-zero when findings at or above this severity exist.
+    #[arg(long, value_enum)]
+    pub fail_on: Option<Severity>,
+
+    /// Record the exchange in a named session for follow-up questions.
+    #[arg(long)]
+    pub session: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CommitMsgArgs {
+    /// Include unstaged changes too.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Copy the message to the system clipboard.
+    #[arg(long)]
+    pub copy: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CommitLintArgs {
+    /// Git revision range to lint (e.g. `main..HEAD`).
+    #[arg(long, default_value = "HEAD~10..HEAD")]
+    pub range: String,
+
+    /// Ask the model for conforming rewrites of violating subjects.
+    #[arg(long)]
+    pub suggest: bool,
+
+    /// Write a `git rebase -i` todo list rewording the violating commits.
+    #[arg(long)]
+    pub script: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    /// What to generate.
+    pub instruction: String,
+
+    /// Output file path.
+    #[arg(long, short)]
+    pub out: PathBuf,
+
+    /// Overwrite the output file if it exists.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Have the model critique and revise its own output before the file
+    /// is written, up to this many rounds.
+    #[arg(long, value_name = "ROUNDS", num_args = 0..=1, default_missing_value = "2", default_value_t = 0)]
+    pub self_review: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    #[command(subcommand)]
+    pub command: DiffCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DiffCommands {
+    /// Ask the model for a change to a file as a unified diff.
+    Propose(DiffProposeArgs),
+    /// Apply a unified diff to the working tree.
+    Apply(DiffApplyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct DiffProposeArgs {
+    /// Instruction describing the desired change; optional with --test.
+    pub instruction: Option<String>,
+
+    /// File the change applies to.
+    #[arg(long, short, required_unless_present = "test")]
+    pub file: Option<PathBuf>,
+
+    /// Run this test, capture its failure, and propose a fix for it.
+    #[arg(long, conflicts_with = "file")]
+    pub test: Option<String>,
+
+    /// Write the proposed diff to this path instead of stdout.
+    #[arg(long, short)]
+    pub out: Option<PathBuf>,
+
+    /// Copy the proposed diff to the system clipboard.
+    #[arg(long)]
+    pub copy: bool,
+
+    /// Have the model critique and revise the proposed diff (with parse
+    /// feedback) before presenting it, up to this many rounds.
+    #[arg(long, value_name = "ROUNDS", num_args = 0..=1, default_missing_value = "2", default_value_t = 0)]
+    pub self_review: usize,
+
+    /// Propose N distinct approaches (minimal fix, refactor, …) and pick
+    /// one to keep.
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub alternatives: usize,
+}
+
+/// How `diff apply` writes changes to the working tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[value(rename_all = "lowercase")]
+pub enum ApplyBackend {
+    /// The built-in hunk applier.
+    Builtin,
+    /// Delegate to `git apply` (validated with `--check` first).
+    Git,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffApplyArgs {
+    /// Unified diff file to apply.
+    pub patch: PathBuf,
+
+    /// Validate and report without writing.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Skip creating backups of modified files.
+    #[arg(long)]
+    pub no_backup: bool,
+
+    /// Patch application backend.
+    #[arg(long, value_enum, default_value_t = ApplyBackend::Builtin)]
+    pub backend: ApplyBackend,
+
+    /// With the git backend, also stage the result in the index.
+    #[arg(long, requires = "backend")]
+    pub cached: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct GrepArgs {
+    /// Regex pattern to search for.
+    pub pattern: String,
+
+    /// Directory or file to search (defaults to the workspace root).
+    pub path: Option<PathBuf>,
+
+    /// Case-insensitive search.
+    #[arg(long, short = 'i')]
+    pub ignore_case: bool,
+
+    /// Lines of context around each match.
+    #[arg(long, short = 'C')]
+    pub context: Option<usize>,
+
+    /// Emit structured match records as JSON.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Use the built-in searcher even when ripgrep is installed.
+    #[arg(long)]
+    pub no_rg: bool,
+
+    /// Restrict the search to paths matching this glob (repeatable).
+    #[arg(long = "glob")]
+    pub globs: Vec<String>,
+
+    /// Print match counts per file instead of matches.
+    #[arg(long, conflicts_with = "files_with_matches")]
+    pub count: bool,
+
+    /// Print only the paths of files containing matches.
+    #[arg(long, short = 'l')]
+    pub files_with_matches: bool,
+
+    /// Stop searching a file after this many matches.
+    #[arg(long)]
+    pub max_count: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct FilesArgs {
+    #[command(subcommand)]
+    pub command: FilesCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FilesCommands {
+    /// List files under a directory.
+    List(FilesListArgs),
+    /// Compare two directory trees.
+    Compare(FilesCompareArgs),
+    /// One-way sync from source to target.
+    Sync(FilesSyncArgs),
+    /// Find duplicate files by content hash.
+    Duplicates(FilesDuplicatesArgs),
+    /// Per-file code metrics.
+    Analyze(FilesAnalyzeArgs),
+    /// Scan for security issues.
+    Security(FilesSecurityArgs),
+    /// Code statistics per language and directory.
+    Stats(FilesStatsArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct FilesListArgs {
+    /// Directory to list (defaults to CWD).
+    pub path: Option<PathBuf>,
+
+    /// Comma-separated gitignore-style patterns to exclude.
+    #[arg(long)]
+    pub exclude: Option<String>,
+
+    /// Restrict to one workspace package.
+    #[arg(long)]
+    pub package: Option<String>,
+
+    /// Only list files with this CODEOWNERS owner (e.g. @org/team).
+    #[arg(long)]
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct FilesCompareArgs {
+    pub left: PathBuf,
+    pub right: PathBuf,
+
+    /// Comma-separated gitignore-style patterns to exclude.
+    #[arg(long)]
+    pub exclude: Option<String>,
+
+    /// Minimum content similarity (0..=1) to report a removed+added pair
+    /// as a rename.
+    #[arg(long, default_value_t = 0.7)]
+    pub rename_threshold: f64,
+}
+
+#[derive(Debug, Args)]
+pub struct FilesSyncArgs {
+    pub source: PathBuf,
+    pub target: PathBuf,
+
+    /// Show what would change without writing.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Sync both ways; when both sides changed, the newest file wins.
+    #[arg(long)]
+    pub bidirectional: bool,
+
+    /// Remove target files that do not exist in the source.
+    #[arg(long, conflicts_with = "bidirectional")]
+    pub delete: bool,
+
+    /// Comma-separated gitignore-style patterns to exclude.
+    #[arg(long)]
+    pub exclude: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct FilesDuplicatesArgs {
+    /// Directory to scan (defaults to CWD).
+    pub path: Option<PathBuf>,
+
+    /// Comma-separated gitignore-style patterns to exclude.
+    #[arg(long)]
+    pub exclude: Option<String>,
+
+    /// Also report near-duplicate code files (minhash over normalized
+    /// token shingles) at or above this similarity score.
+    #[arg(long, value_name = "THRESHOLD", num_args = 0..=1, default_missing_value = "0.7")]
+    pub similar: Option<f64>,
+}
+
+#[derive(Debug, Args)]
+pub struct FilesAnalyzeArgs {
+    /// Directory to analyze (defaults to CWD).
+    pub path: Option<PathBuf>,
+
+    /// Restrict to one workspace package.
+    #[arg(long)]
+    pub package: Option<String>,
+
+    /// Also write the analysis to PATH as `csv` (for spreadsheets) or
+    /// `html` (a self-contained sortable report).
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "PATH"])]
+    pub export: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct FilesStatsArgs {
+    /// Directory to measure (defaults to the workspace root).
+    pub path: Option<PathBuf>,
+
+    /// Comma-separated gitignore-style patterns to exclude.
+    #[arg(long)]
+    pub exclude: Option<String>,
+
+    /// Restrict to one workspace package.
+    #[arg(long)]
+    pub package: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct FilesSecurityArgs {
+    /// Directory to scan (defaults to CWD).
+    pub path: Option<PathBuf>,
+
+    /// Only report high-severity findings.
+    #[arg(long)]
+    pub high_only: bool,
+
+    /// Probe found GitHub tokens against the API so revoked credentials
+    /// are downgraded (network access: sends each token to github.com).
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Skip files larger than this many bytes.
+    #[arg(long, default_value_t = 1024 * 1024)]
+    pub max_file_bytes: u64,
+
+    /// Exit non-zero when findings at or above this severity exist.
+    #[arg(long, value_enum)]
+    pub fail_on: Option<Severity>,
+}
+
+#[derive(Debug, Args)]
+pub struct ScriptArgs {
+    #[command(subcommand)]
+    pub command: ScriptCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ScriptCommands {
+    /// Execute a script file.
+    Run(ScriptRunArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ScriptRunArgs {
+    /// Script file to execute.
+    pub file: PathBuf,
+
+    /// Arguments passed to the script.
+    #[arg(trailing_var_arg = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Command and arguments to execute (after `--`).
+    #[arg(trailing_var_arg = true, required = true)]
+    pub command: Vec<String>,
+
+    /// Always propose a fix diff on failure instead of asking.
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Just propagate the exit status; no model call on failure.
+    #[arg(long, conflicts_with = "fix")]
+    pub no_summary: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CiArgs {
+    #[command(subcommand)]
+    pub command: CiCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CiCommands {
+    /// Isolate the first real failure in a CI log and diagnose it.
+    Triage(CiTriageArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CiTriageArgs {
+    /// Log file to triage (`-` reads stdin).
+    #[arg(long, value_name = "FILE", conflicts_with = "url")]
+    pub log: Option<PathBuf>,
+
+    /// Raw log URL to download and triage.
+    #[arg(long, value_name = "URL")]
+    pub url: Option<String>,
+
+    /// Lines of log context around the failure sent to the model.
+    #[arg(long, default_value_t = 80)]
+    pub context: usize,
+
+    /// Skip the model call; just print the isolated failure excerpt.
+    #[arg(long)]
+    pub no_diagnosis: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TestsArgs {
+    #[command(subcommand)]
+    pub command: TestsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TestsCommands {
+    /// Find intermittently failing tests across JUnit reports.
+    Flaky(TestsFlakyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct TestsFlakyArgs {
+    /// JUnit XML reports to aggregate (globs allowed).
+    #[arg(long, value_name = "GLOB", required = true, num_args = 1..)]
+    pub junit: Vec<String>,
+
+    /// Only consider tests seen in at least this many runs.
+    #[arg(long, default_value_t = 3)]
+    pub min_runs: u64,
+
+    /// How many flaky tests to analyze, worst first.
+    #[arg(long, default_value_t = 10)]
+    pub top: usize,
+
+    /// Skip the model call; just print the flakiness table.
+    #[arg(long)]
+    pub no_analysis: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckpointArgs {
+    #[command(subcommand)]
+    pub command: CheckpointCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CheckpointCommands {
+    /// Snapshot the workspace.
+    Create(CheckpointCreateArgs),
+    /// List checkpoints.
+    List(CheckpointListArgs),
+    /// Show one checkpoint's manifest with per-file sizes and hashes.
+    Show(CheckpointShowArgs),
+    /// Restore a checkpoint into the workspace.
+    Restore(CheckpointRestoreArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CheckpointCreateArgs {
+    /// Human description of the snapshot.
+    #[arg(long, short)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckpointListArgs {
+    /// Only checkpoints newer than this (e.g. 7d, 12h, 30m).
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// Only checkpoints that snapshotted PATH (workspace-relative).
+    #[arg(long, value_name = "PATH")]
+    pub contains_file: Option<String>,
+
+    /// Only checkpoints whose description contains this (case-insensitive).
+    #[arg(long)]
+    pub description_match: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckpointShowArgs {
+    /// Checkpoint id (from `checkpoint list`).
+    pub id: String,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckpointRestoreArgs {
+    /// Checkpoint id (from `checkpoint list`).
+    pub id: String,
+}
+
+#[derive(Debug, Args)]
+pub struct BackupsArgs {
+    #[command(subcommand)]
+    pub command: BackupsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BackupsCommands {
+    /// List backup batches and the files they hold.
+    List,
+    /// Restore a batch (or one file from it) into the tree.
+    Restore(BackupsRestoreArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct BackupsRestoreArgs {
+    /// Backup id (from `backups list`).
+    pub id: String,
+
+    /// Restore only this file from the batch.
+    #[arg(long)]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct TemplateArgs {
+    #[command(subcommand)]
+    pub command: TemplateCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TemplateCommands {
+    /// List built-in templates.
+    List,
+    /// Render a template into a directory.
+    Generate(TemplateGenerateArgs),
+    /// Diff a generated project against its template's current files.
+    Upgrade(TemplateUpgradeArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct TemplateGenerateArgs {
+    /// Template name.
+    pub template: String,
+
+    /// Target directory.
+    #[arg(long, short, default_value = ".")]
+    pub out: PathBuf,
+
+    /// Template variable `key=value` (repeatable). A value of `@path`
+    /// reads the file at `path`; `env:NAME` reads the environment.
+    #[arg(long = "var")]
+    pub vars: Vec<String>,
+
+    /// TOML file of `key = "value"` variables; `--var` entries win.
+    #[arg(long)]
+    pub vars_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct TemplateUpgradeArgs {
+    /// Template to upgrade against; defaults to the one recorded at
+    /// generate time.
+    #[arg(long)]
+    pub template: Option<String>,
+
+    /// Project directory.
+    #[arg(long, short, default_value = ".")]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct BatchArgs {
+    #[command(subcommand)]
+    pub command: BatchCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BatchCommands {
+    /// Apply an instruction to every matching file, rewriting in place.
+    Transform(BatchTransformArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct BatchTransformArgs {
+    /// Instruction applied to each file.
+    pub instruction: String,
+
+    /// Glob of files to transform.
+    #[arg(long)]
+    pub glob: String,
+
+    /// Restrict the glob to one workspace package.
+    #[arg(long)]
+    pub package: Option<String>,
+
+    /// Only transform files with this CODEOWNERS owner (e.g. @org/team).
+    #[arg(long)]
+    pub owner: Option<String>,
+
+    /// Show target files without calling the model.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct MigrateArgs {
+    /// The idiom being migrated away from (e.g. "React class components").
+    #[arg(long)]
+    pub from: String,
+
+    /// The idiom being migrated to (e.g. "hooks").
+    #[arg(long)]
+    pub to: String,
+
+    /// Directory to scan for affected files.
+    #[arg(long, default_value = ".")]
+    pub path: PathBuf,
+
+    /// Shell command run after each rewrite; a failure rolls that file
+    /// back and queues it for manual work (e.g. "cargo check").
+    #[arg(long, value_name = "CMD")]
+    pub validate: Option<String>,
+
+    /// List the affected files from the pre-scan and stop.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct I18nArgs {
+    #[command(subcommand)]
+    pub command: I18nCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum I18nCommands {
+    /// Find user-facing strings and write a translation catalog.
+    Extract(I18nExtractArgs),
+    /// Machine-translate a catalog, emitting a diff for review.
+    Translate(I18nTranslateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct I18nExtractArgs {
+    /// Directory to scan.
+    #[arg(long, default_value = ".")]
+    pub path: PathBuf,
+
+    /// Catalog format.
+    #[arg(long, default_value = "json")]
+    pub format: String,
+
+    /// Catalog file (defaults to `i18n/messages.<format>`).
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct I18nTranslateArgs {
+    /// Target language code (e.g. de, fr, ja).
+    #[arg(long)]
+    pub lang: String,
+
+    /// Catalog to translate.
+    #[arg(long, default_value = "i18n/messages.json")]
+    pub catalog: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ModelsArgs {
+    #[command(subcommand)]
+    pub command: ModelsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ModelsCommands {
+    /// List models available from the provider.
+    List(ModelsListArgs),
+    /// Refresh the model cache from the provider.
+    Refresh(ModelsRefreshArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ModelsListArgs {
+    /// Serve from the local cache without contacting the provider.
+    #[arg(long)]
+    pub cached: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ModelsRefreshArgs {
+    /// Refresh every provider used by a configured profile, concurrently.
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SessionArgs {
+    #[command(subcommand)]
+    pub command: SessionCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionCommands {
+    /// List stored sessions.
+    List,
+    /// Print a session transcript.
+    Show(SessionShowArgs),
+    /// Delete a session.
+    Clear(SessionShowArgs),
+    /// Render a transcript to markdown and upload it to a gist or paste
+    /// service, after a secret scan and confirmation.
+    Publish(SessionPublishArgs),
+    /// Write a session to a portable file for archiving or sharing.
+    Export(SessionExportArgs),
+    /// Load a previously exported session file.
+    Import(SessionImportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SessionShowArgs {
+    /// Session name.
+    pub name: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SessionPublishArgs {
+    /// Session name.
+    pub name: String,
+
+    /// Skip the confirmation prompt (required off a terminal).
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Publish even when the secret scan reports findings.
+    #[arg(long)]
+    pub allow_findings: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SessionExportArgs {
+    /// Session name.
+    #[arg(long)]
+    pub name: String,
+
+    /// Export format.
+    #[arg(long, default_value = "json")]
+    pub format: String,
+
+    /// Write to this file instead of stdout.
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct SessionImportArgs {
+    /// Exported session file (JSON).
+    #[arg(long)]
+    pub file: PathBuf,
+
+    /// Import under this name instead of the one recorded in the file.
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Replace an existing session of the same name.
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AgentArgs {
+    /// High-level instruction for the agent.
+    pub instruction: String,
+
+    /// Approve every proposed action without asking.
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Stop after this many executed actions.
+    #[arg(long, default_value_t = 12)]
+    pub max_steps: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct IssueArgs {
+    #[command(subcommand)]
+    pub command: IssueCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum IssueCommands {
+    /// Fetch an issue, draft a plan, branch, and seed a session.
+    Start(IssueStartArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct IssueStartArgs {
+    /// Issue URL, or a bare number resolved against the origin remote.
+    pub issue: String,
+
+    /// Branch name (defaults to issue-<number>-<title-slug>).
+    #[arg(long)]
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct DebugArgs {
+    #[command(subcommand)]
+    pub command: DebugCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DebugCommands {
+    /// Collect redacted config, version info, recent transcripts, and a
+    /// provider latency probe into a tarball for attaching to issues.
+    Bundle(DebugBundleArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct DebugBundleArgs {
+    /// Output path (defaults to sw-debug-<timestamp>.tar.gz).
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+
+    /// Skip the network probe.
+    #[arg(long)]
+    pub no_probe: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Window to report over (e.g. 7d, 12h, 30m).
+    #[arg(long, default_value = "7d")]
+    pub since: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    #[command(subcommand)]
+    pub command: ServeCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ServeCommands {
+    /// Expose an OpenAI-compatible /v1/chat/completions endpoint that
+    /// routes through the active profile's provider, keys, limits, and
+    /// redaction.
+    Openai(ServeOpenaiArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ProvenanceArgs {
+    #[command(subcommand)]
+    pub command: ProvenanceCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProvenanceCommands {
+    /// Show the recorded provenance of a file.
+    Show(ProvenanceShowArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ProvenanceShowArgs {
+    /// File to look up in `.sw/provenance.jsonl`.
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct ReleaseArgs {
+    #[command(subcommand)]
+    pub command: ReleaseCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReleaseCommands {
+    /// Draft release notes, version bump diffs, and a tag message.
+    Prepare(ReleasePrepareArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ReleasePrepareArgs {
+    /// Which version position to bump.
+    #[arg(long, default_value = "patch", conflicts_with = "version")]
+    pub bump: String,
+
+    /// Exact next version, overriding --bump.
+    #[arg(long)]
+    pub version: Option<String>,
+
+    /// Directory the release files are written into
+    /// (defaults to `.sw/release`).
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    #[command(subcommand)]
+    pub command: ReportCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommands {
+    /// Run the configured analyses and write a dated report.
+    Nightly(ReportNightlyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ReportNightlyArgs {
+    /// Output directory (defaults to `[report].dir`, `.sw/reports`).
+    #[arg(long)]
+    pub dir: Option<PathBuf>,
+
+    /// Comma-separated sections to run, overriding `[report].sections`
+    /// (security, todos, deps, overview).
+    #[arg(long)]
+    pub sections: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct DepsArgs {
+    #[command(subcommand)]
+    pub command: DepsCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DepsCommands {
+    /// Report licenses and known advisories for direct dependencies.
+    Audit(DepsAuditArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct DepsAuditArgs {
+    /// Directory containing the manifests (defaults to the current one).
+    pub path: Option<PathBuf>,
+
+    /// Skip registry and OSV lookups; report only what manifests say.
+    #[arg(long)]
+    pub offline: bool,
+
+    /// Ask the model for a read on risky upgrades.
+    #[arg(long)]
+    pub summarize: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct MapArgs {
+    /// Root to map (defaults to the current directory).
+    pub path: Option<PathBuf>,
+
+    /// Rebuild from scratch, ignoring the per-file cache.
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Token budget for the text outline; lower-ranked files degrade to
+    /// path-only lines.
+    #[arg(long, default_value_t = 4000)]
+    pub budget: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct DiagramArgs {
+    /// Root to diagram (defaults to the workspace).
+    pub path: Option<PathBuf>,
+
+    /// Diagram kind: `modules` (import graph) or `sequence` (model-drafted).
+    #[arg(long, default_value = "modules")]
+    pub kind: String,
+
+    /// Flow a sequence diagram should trace (e.g. "request handling").
+    #[arg(long)]
+    pub focus: Option<String>,
+
+    /// Only the N highest-ranked files appear as module nodes.
+    #[arg(long, default_value_t = 25)]
+    pub max_nodes: usize,
+
+    /// Let the model tidy the diagram (group subgraphs, shorten labels).
+    #[arg(long)]
+    pub refine: bool,
+
+    /// Where to write the Mermaid source.
+    #[arg(long, default_value = "diagram.mmd")]
+    pub out: PathBuf,
+
+    /// Also render an SVG next to the .mmd (requires mmdc on PATH).
+    #[arg(long)]
+    pub svg: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CompareArgs {
+    /// Comma-separated models to run the task against.
+    #[arg(long, value_delimiter = ',', required = true)]
+    pub models: Vec<String>,
+
+    /// Prompt to send to every model.
+    #[arg(long, required_unless_present_any = ["file", "diff_file"])]
+    pub prompt: Option<String>,
+
+    /// Review this file with every model instead of a prompt.
+    #[arg(long, conflicts_with = "prompt")]
+    pub file: Option<PathBuf>,
+
+    /// Review this diff with every model instead of a prompt.
+    #[arg(long, conflicts_with_all = ["prompt", "file"])]
+    pub diff_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct RerunArgs {
+    /// Replay the most recent invocation (the default).
+    #[arg(long, conflicts_with = "id")]
+    pub last: bool,
+
+    /// Replay a specific entry from `history list`.
+    #[arg(long)]
+    pub id: Option<u64>,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub command: HistoryCommands,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HistoryCommands {
+    /// List recorded invocations.
+    List,
+}
+
+#[derive(Debug, Args)]
+pub struct ServeOpenaiArgs {
+    /// Port to listen on.
+    #[arg(long, default_value_t = 8787)]
+    pub port: u16,
+
+    /// Address to bind.
+    #[arg(long, default_value = "127.0.0.1")]
+    pub bind: String,
+}
+
+/// Merge `[defaults]` from config into the raw command line before clap
+/// sees it. Dotted keys name a subcommand path plus a flag
+/// (`files.security.high_only = true`); defaults are inserted after the
+/// matched subcommand tokens, and a flag the user passed always wins.
+pub fn apply_default_flags(
+    mut args: Vec<String>,
+    defaults: &std::collections::BTreeMap<String, toml::Value>,
+) -> Vec<String> {
+    let mut flat = Vec::new();
+    for (key, value) in defaults {
+        flatten_defaults(key, value, &mut flat);
+    }
+    for (path, flag, value) in flat {
+        let Some(insert_at) = match_command_path(&args, &path) else {
+            continue;
+        };
+        let bare = format!("--{flag}");
+        let assigned = format!("--{flag}=");
+        if args.iter().any(|a| *a == bare || a.starts_with(&assigned)) {
+            continue;
+        }
+        for (i, token) in render_default_flag(&flag, &value).into_iter().enumerate() {
+            args.insert(insert_at + i, token);
+        }
+    }
+    args
+}
+
+/// Flatten nested default tables into `(command path, flag, value)`
+/// triples, converting snake_case keys to flag spelling.
+fn flatten_defaults(
+    prefix: &str,
+    value: &toml::Value,
+    out: &mut Vec<(Vec<String>, String, toml::Value)>,
+) {
+    if let toml::Value::Table(table) = value {
+        for (key, nested) in table {
+            flatten_defaults(&format!("{prefix}.{key}"), nested, out);
+        }
+        return;
+    }
+    let mut parts: Vec<String> = prefix.split('.').map(|p| p.replace('_', "-")).collect();
+    let flag = parts.pop().expect("dotted key has at least one segment");
+    if parts.is_empty() {
+        return;
+    }
+    out.push((parts, flag, value.clone()));
+}
+
+/// Find the command path in the raw args, returning the index just after
+/// its last component, or `None` when this invocation is a different
+/// command. The scan stops at `--`.
+fn match_command_path(args: &[String], path: &[String]) -> Option<usize> {
+    let mut next = 0;
+    for (i, token) in args.iter().enumerate().skip(1) {
+        if token == "--" {
+            return None;
+        }
+        if *token == path[next] {
+            next += 1;
+            if next == path.len() {
+                return Some(i + 1);
+            }
+        }
+    }
+    None
+}
+
+fn render_default_flag(flag: &str, value: &toml::Value) -> Vec<String> {
+    match value {
+        toml::Value::Boolean(true) => vec![format!("--{flag}")],
+        toml::Value::Boolean(false) => Vec::new(),
+        toml::Value::String(s) => vec![format!("--{flag}={s}")],
+        toml::Value::Integer(n) => vec![format!("--{flag}={n}")],
+        toml::Value::Float(n) => vec![format!("--{flag}={n}")],
+        toml::Value::Array(items) => items
+            .iter()
+            .flat_map(|item| render_default_flag(flag, item))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults(toml: &str) -> std::collections::BTreeMap<String, toml::Value> {
+        ::toml::from_str(toml).unwrap()
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn injects_defaults_after_the_subcommand() {
+        let d = defaults("[ask]\nstream = true\n[review]\nfail_on = \"high\"\n");
+        assert_eq!(
+            apply_default_flags(argv(&["sw", "ask", "hi"]), &d),
+            argv(&["sw", "ask", "--stream", "hi"])
+        );
+        assert_eq!(
+            apply_default_flags(argv(&["sw", "review", "src/main.rs"]), &d),
+            argv(&["sw", "review", "--fail-on=high", "src/main.rs"])
+        );
+    }
+
+    #[test]
+    fn explicit_flags_win_over_defaults() {
+        let d = defaults("[review]\nfail_on = \"high\"\n");
+        let args = argv(&["sw", "review", "--fail-on", "low", "x"]);
+        assert_eq!(apply_default_flags(args.clone(), &d), args);
+    }
+
+    #[test]
+    fn nested_paths_and_other_commands() {
+        let d = defaults("[files.security]\nhigh_only = true\n");
+        assert_eq!(
+            apply_default_flags(argv(&["sw", "files", "security", "."]), &d),
+            argv(&["sw", "files", "security", "--high-only", "."])
+        );
+        let other = argv(&["sw", "files", "list", "."]);
+        assert_eq!(apply_default_flags(other.clone(), &d), other);
+    }
+}
@@ -0,0 +1,308 @@
+//! `sw agent` — a propose/approve loop: the model plans one action at a
+//! time (read, grep, diff, run), each is shown for approval, and the
+//! result feeds back into the conversation until the instruction is done.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::cli::AgentArgs;
+use crate::llm::ChatMessage;
+
+/// Cap on any single result fed back into the conversation.
+const MAX_RESULT_BYTES: usize = 12 * 1024;
+
+/// Matches returned from a grep action.
+const MAX_GREP_MATCHES: usize = 50;
+
+/// Consecutive unparseable replies tolerated before giving up.
+const MAX_PARSE_RETRIES: usize = 3;
+
+const AGENT_SYSTEM: &str = "You are a careful software agent working inside the user's \
+     repository. Move toward the instruction one small action at a time. \
+     Reply with exactly one JSON object and nothing else:\n\
+     {\"action\":\"read\",\"path\":\"src/lib.rs\"} — read a file\n\
+     {\"action\":\"grep\",\"pattern\":\"regex\"} — search the tree\n\
+     {\"action\":\"diff\",\"diff\":\"--- a/f\\n+++ b/f\\n@@ ...\"} — apply a unified diff\n\
+     {\"action\":\"run\",\"command\":\"cargo test\"} — run a shell command\n\
+     {\"action\":\"done\",\"summary\":\"...\"} — finish, summarizing what changed\n\
+     Every action is shown to the user for approval. Verify your changes \
+     (build, tests) before finishing.";
+
+/// One proposed step, parsed from the model's JSON reply.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+pub enum Action {
+    Read { path: std::path::PathBuf },
+    Grep { pattern: String },
+    Diff { diff: String },
+    Run { command: String },
+    Done { summary: String },
+}
+
+impl Action {
+    /// One-line description shown in the approval prompt and the output.
+    pub fn describe(&self) -> String {
+        match self {
+            Action::Read { path } => format!("read {}", path.display()),
+            Action::Grep { pattern } => format!("grep /{pattern}/"),
+            Action::Diff { diff } => {
+                let files = crate::diff::parse_diff_target_files(diff)
+                    .map(|t| {
+                        t.iter()
+                            .map(|p| p.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_else(|_| "<invalid diff>".to_string());
+                format!("apply diff to {files}")
+            }
+            Action::Run { command } => format!("run `{command}`"),
+            Action::Done { .. } => "finish".to_string(),
+        }
+    }
+}
+
+/// Parse the model reply into an action; tolerates a code fence or prose
+/// around the JSON object.
+pub fn parse_action(content: &str) -> Result<Action> {
+    let text = crate::commands::generate::strip_code_fence(content).trim();
+    if let Ok(action) = serde_json::from_str::<Action>(text) {
+        return Ok(action);
+    }
+    let start = text.find('{').context("no JSON object in the reply")?;
+    let end = text.rfind('}').context("no JSON object in the reply")?;
+    serde_json::from_str(&text[start..=end]).context("reply is not a valid action")
+}
+
+/// Clip a result to the feedback budget, keeping the tail — failures and
+/// matches of interest come last.
+fn clip(text: &str) -> String {
+    if text.len() <= MAX_RESULT_BYTES {
+        return text.to_string();
+    }
+    let mut start = text.len() - MAX_RESULT_BYTES;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[truncated]\n{}", &text[start..])
+}
+
+fn exec_read(path: &std::path::Path, ctx: &AppContext) -> Result<String> {
+    let full = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        ctx.workspace.join(path)
+    };
+    ctx.ensure_sendable(&full)?;
+    crate::fsutil::ensure_text_file(&full)?;
+    let content = std::fs::read_to_string(&full)
+        .with_context(|| format!("failed to read {}", full.display()))?;
+    Ok(format!(
+        "Contents of `{}`:\n```\n{}\n```",
+        path.display(),
+        clip(&ctx.redact(&content))
+    ))
+}
+
+fn exec_grep(pattern: &str, ctx: &AppContext) -> Result<String> {
+    let re = regex::Regex::new(pattern).with_context(|| format!("invalid pattern '{pattern}'"))?;
+    let mut matches = Vec::new();
+    'files: for path in crate::commands::files::walk_files(&ctx.workspace, &[])? {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let rel = path.strip_prefix(&ctx.workspace).unwrap_or(&path);
+        for (idx, line) in content.lines().enumerate() {
+            if re.is_match(line) {
+                matches.push(format!("{}:{}: {}", rel.display(), idx + 1, line.trim()));
+                if matches.len() >= MAX_GREP_MATCHES {
+                    matches.push("[more matches omitted]".to_string());
+                    break 'files;
+                }
+            }
+        }
+    }
+    if matches.is_empty() {
+        return Ok(format!("no matches for /{pattern}/"));
+    }
+    Ok(ctx.redact(&matches.join("\n")))
+}
+
+async fn exec_diff(diff: &str, ctx: &AppContext) -> Result<String> {
+    let diffs = crate::diff::parse_unified_diff(diff)?;
+    let mut applied = Vec::new();
+    for file_diff in &diffs {
+        let target = file_diff
+            .target_path()
+            .context("diff section has no target path")?
+            .clone();
+        if !ctx.config.allow_outside_workspace {
+            crate::fsutil::ensure_within_workspace(&target, &ctx.workspace)?;
+        }
+        if file_diff.is_deletion() {
+            tokio::fs::remove_file(&target)
+                .await
+                .with_context(|| format!("failed to delete {}", target.display()))?;
+            applied.push(format!("{} (deleted)", target.display()));
+            continue;
+        }
+        let current = if file_diff.is_creation() {
+            String::new()
+        } else {
+            crate::fsutil::ensure_text_file(&target)?;
+            crate::fsutil::read_file_to_string_async(&target).await?
+        };
+        let updated = crate::diff::apply_file_diff(file_diff, &current)
+            .with_context(|| format!("failed to apply hunks to {}", target.display()))?;
+        if !file_diff.is_creation() {
+            crate::fsutil::backup_file_async(&target).await?;
+        }
+        crate::fsutil::write_file_async(&target, &updated).await?;
+        crate::provenance::track(ctx.config.provenance, &target, "agent", None, diff)?;
+        applied.push(target.display().to_string());
+    }
+    Ok(format!("applied to {}", applied.join(", ")))
+}
+
+fn exec_run(command: &str) -> Result<String> {
+    let output = crate::platform::shell_exec(command)?;
+    let mut text = String::new();
+    text.push_str(&String::from_utf8_lossy(&output.stdout));
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    let status = output
+        .status
+        .code()
+        .map_or("unknown".to_string(), |c| c.to_string());
+    Ok(format!("exit status {status}\n```\n{}\n```", clip(&text)))
+}
+
+/// Ask the user about one proposed action. Scripted runs must opt in
+/// with `--yes`; silently approving would defeat the loop's point.
+fn approve(desc: &str, args: &AgentArgs, ctx: &AppContext) -> Result<bool> {
+    use std::io::IsTerminal;
+    if args.yes {
+        ctx.render.status(&format!("auto-approved: {desc}"));
+        return Ok(true);
+    }
+    if !std::io::stdin().is_terminal() {
+        bail!("agent needs a terminal to approve actions; rerun with --yes");
+    }
+    ctx.render
+        .status(&format!("proposed: {desc} — apply? [y/N]"));
+    let mut line = String::new();
+    std::io::stdin()
+        .read_line(&mut line)
+        .context("failed to read approval")?;
+    Ok(matches!(line.trim(), "y" | "Y" | "yes"))
+}
+
+#[derive(Serialize)]
+struct AgentOutput {
+    instruction: String,
+    steps: Vec<String>,
+    summary: String,
+    model: String,
+}
+
+pub async fn cmd_agent(args: &AgentArgs, ctx: &AppContext) -> Result<()> {
+    let mut prompt = format!("Instruction: {}", args.instruction);
+    if let Some(outline) = crate::repomap::prompt_block(&ctx.workspace, 2000) {
+        prompt = format!("Repository outline:\n\n{outline}\n\n{prompt}");
+    }
+    let mut messages = vec![ChatMessage::system(AGENT_SYSTEM), ChatMessage::user(prompt)];
+
+    let mut steps = Vec::new();
+    let mut parse_failures = 0;
+    let (summary, model) = loop {
+        if steps.len() >= args.max_steps {
+            bail!(
+                "stopping after {} step(s) without finishing; raise --max-steps to continue",
+                args.max_steps
+            );
+        }
+        let resp = ctx.complete(messages.clone()).await?;
+        messages.push(ChatMessage {
+            role: crate::llm::Role::Assistant,
+            content: resp.content.clone(),
+        });
+        let action = match parse_action(&resp.content) {
+            Ok(action) => {
+                parse_failures = 0;
+                action
+            }
+            Err(e) => {
+                parse_failures += 1;
+                if parse_failures >= MAX_PARSE_RETRIES {
+                    bail!("model kept replying without a parseable action: {e:#}");
+                }
+                messages.push(ChatMessage::user(format!(
+                    "That reply was not a valid action ({e:#}). Reply with \
+                     exactly one JSON action object."
+                )));
+                continue;
+            }
+        };
+        if let Action::Done { summary } = action {
+            break (summary, resp.model);
+        }
+
+        let desc = action.describe();
+        if !approve(&desc, args, ctx)? {
+            messages.push(ChatMessage::user(
+                "The user declined that action. Propose a different one, or \
+                 finish with a done action explaining where things stand.",
+            ));
+            continue;
+        }
+        steps.push(desc.clone());
+        let result = match &action {
+            Action::Read { path } => exec_read(path, ctx),
+            Action::Grep { pattern } => exec_grep(pattern, ctx),
+            Action::Diff { diff } => exec_diff(diff, ctx).await,
+            Action::Run { command } => exec_run(command),
+            Action::Done { .. } => unreachable!("handled above"),
+        };
+        let feedback = match result {
+            Ok(output) => format!("Result of {desc}:\n{output}"),
+            Err(e) => format!("{desc} failed: {e:#}"),
+        };
+        if ctx.verbose {
+            ctx.render.status(&format!("step {}: {desc}", steps.len()));
+        }
+        messages.push(ChatMessage::user(feedback));
+    };
+
+    ctx.render
+        .status(&format!("finished after {} step(s)", steps.len()));
+    let output = AgentOutput {
+        instruction: args.instruction.clone(),
+        steps,
+        summary,
+        model,
+    };
+    ctx.render
+        .emit(&output, || ctx.render.markdown(&output.summary));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_actions_with_and_without_fences() {
+        let plain = parse_action(r#"{"action":"read","path":"src/main.rs"}"#).unwrap();
+        assert_eq!(plain.describe(), "read src/main.rs");
+
+        let fenced =
+            parse_action("```json\n{\"action\":\"run\",\"command\":\"cargo test\"}\n```").unwrap();
+        assert_eq!(fenced.describe(), "run `cargo test`");
+
+        let prose =
+            parse_action("I'll search first. {\"action\":\"grep\",\"pattern\":\"todo\"}").unwrap();
+        assert_eq!(prose.describe(), "grep /todo/");
+
+        assert!(parse_action("let me think about this").is_err());
+    }
+}
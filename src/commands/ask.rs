@@ -0,0 +1,398 @@
+//! `sw ask` — one-off questions, optionally in a named session.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::AskArgs;
+use crate::context::build_messages_with_truncation;
+use crate::llm::Role;
+use crate::session::{SessionRecord, SessionStore, LAST_SESSION};
+
+#[derive(Serialize)]
+struct AskOutput {
+    answer: String,
+    model: String,
+    meta: crate::llm::CallMeta,
+}
+
+/// Cap per attachment so a stray binary dump cannot blow the prompt.
+const MAX_ATTACHMENT_BYTES: usize = 48 * 1024;
+
+fn truncate_attachment(content: &str) -> (String, bool) {
+    if content.len() <= MAX_ATTACHMENT_BYTES {
+        return (content.to_string(), false);
+    }
+    let mut end = MAX_ATTACHMENT_BYTES;
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    (content[..end].to_string(), true)
+}
+
+fn fence_tag(path: &std::path::Path) -> &str {
+    path.extension().and_then(|e| e.to_str()).unwrap_or("")
+}
+
+/// Render `--file` and `--exec` attachments into fenced context blocks
+/// prepended to the question.
+async fn build_attachments(args: &AskArgs, ctx: &AppContext) -> Result<String> {
+    let mut blocks = String::new();
+    for path in &args.files {
+        ctx.ensure_sendable(path)?;
+        let content = ctx.redact(&crate::fsutil::read_file_to_string_async(path).await?);
+        // Tabular data files are attached as a schema plus sampled rows;
+        // the raw megabytes stay on disk.
+        if let Some(p) =
+            crate::datafile::profile(path, &content, args.rows, args.columns.as_deref())?
+        {
+            ctx.render.status(&format!(
+                "{}: attaching schema and {} sampled row(s) instead of the raw data",
+                path.display(),
+                p.sample.len()
+            ));
+            blocks.push_str(&format!(
+                "{}\n\n",
+                p.prompt_block(&path.display().to_string()).trim_end()
+            ));
+            continue;
+        }
+        let (body, truncated) = truncate_attachment(&content);
+        if truncated {
+            ctx.render.warn(&format!(
+                "{} truncated to {MAX_ATTACHMENT_BYTES} bytes",
+                path.display()
+            ));
+        }
+        blocks.push_str(&format!(
+            "Contents of `{}`{}:\n```{}\n{}\n```\n\n",
+            path.display(),
+            if truncated { " (truncated)" } else { "" },
+            fence_tag(path),
+            body.trim_end_matches('\n'),
+        ));
+    }
+    for command in &args.execs {
+        let output = crate::platform::shell_exec(command)
+            .with_context(|| format!("failed to run `{command}`"))?;
+        let mut combined = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.trim().is_empty() {
+            combined.push_str("\n[stderr]\n");
+            combined.push_str(&stderr);
+        }
+        let (body, truncated) = truncate_attachment(&ctx.redact(&combined));
+        blocks.push_str(&format!(
+            "Output of `{command}` (exit status {}){}:\n```\n{}\n```\n\n",
+            output
+                .status
+                .code()
+                .map_or("unknown".to_string(), |c| c.to_string()),
+            if truncated { " (truncated)" } else { "" },
+            body.trim_end_matches('\n'),
+        ));
+    }
+    Ok(blocks)
+}
+
+#[derive(Serialize)]
+struct ChoicesOutput {
+    choices: Vec<String>,
+    /// 1-based index of the candidate stored in the session.
+    picked: usize,
+    model: String,
+}
+
+/// `--choices N`: request N candidates in parallel, render them as a
+/// numbered list, and store only the picked one in the session. The pick
+/// is read interactively on a terminal; otherwise the first candidate
+/// wins, so scripted runs stay non-blocking.
+async fn ask_choices(
+    args: &AskArgs,
+    ctx: &AppContext,
+    store: &SessionStore,
+    session_name: &str,
+    prompt_with_context: &str,
+    messages: Vec<crate::llm::ChatMessage>,
+    n: usize,
+) -> Result<()> {
+    anyhow::ensure!((2..=9).contains(&n), "--choices takes 2 to 9");
+    let base_req = ctx.chat_request(messages)?;
+    let provider = ctx.provider()?;
+    ctx.render.status(&format!("requesting {n} candidates"));
+    // Not every provider honours the `n` parameter, so candidates come
+    // from parallel identical calls; sampling keeps them distinct.
+    let calls = (0..n).map(|_| {
+        let req = base_req.clone();
+        let provider = provider.as_ref();
+        async move { provider.send(&req).await }
+    });
+    let results = tokio::select! {
+        r = futures_util::future::join_all(calls) => r,
+        _ = ctx.cancel.cancelled() => anyhow::bail!(crate::cancel::INTERRUPTED),
+    };
+    let mut model = String::new();
+    let mut choices = Vec::new();
+    for result in results {
+        match result {
+            Ok(resp) => {
+                ctx.report_meta(&resp);
+                model = resp.model;
+                choices.push(resp.content);
+            }
+            Err(e) => ctx.render.warn(&format!("candidate failed: {e:#}")),
+        }
+    }
+    anyhow::ensure!(!choices.is_empty(), "all {n} candidates failed");
+
+    let picked = if ctx.render.is_text() {
+        for (i, choice) in choices.iter().enumerate() {
+            ctx.render
+                .data(&format!("--- {} ---\n{}\n\n", i + 1, choice.trim_end()));
+        }
+        pick_choice(choices.len(), ctx)
+    } else {
+        1
+    };
+    let answer = choices[picked - 1].clone();
+
+    if args.copy {
+        ctx.copy_artifact(&answer);
+    }
+    store.append_capped(
+        session_name,
+        &SessionRecord::now(Role::User, prompt_with_context, None),
+        ctx.config.session_max_record_bytes,
+    )?;
+    store.append_capped(
+        session_name,
+        &SessionRecord::now(Role::Assistant, &answer, Some(model.clone())),
+        ctx.config.session_max_record_bytes,
+    )?;
+    ctx.render.emit(
+        &ChoicesOutput {
+            choices,
+            picked,
+            model,
+        },
+        || format!("kept choice {picked}"),
+    );
+    Ok(())
+}
+
+/// Ask which candidate to keep; defaults to 1 off a terminal or on any
+/// input that is not a valid index. Shared with `diff propose
+/// --alternatives`.
+pub fn pick_choice(count: usize, ctx: &AppContext) -> usize {
+    use std::io::IsTerminal;
+    if !std::io::stdin().is_terminal() {
+        return 1;
+    }
+    ctx.render
+        .status(&format!("keep which choice? [1-{count}, default 1]"));
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line).is_err() {
+        return 1;
+    }
+    match line.trim().parse::<usize>() {
+        Ok(i) if (1..=count).contains(&i) => i,
+        _ => 1,
+    }
+}
+
+pub async fn cmd_ask(args: &AskArgs, ctx: &AppContext) -> Result<()> {
+    let prompt = if args.voice || args.audio.is_some() {
+        let recording = match &args.audio {
+            Some(path) => path.clone(),
+            None => crate::stt::record_audio(ctx)?,
+        };
+        let transcript = crate::stt::transcribe(&recording, ctx).await;
+        if args.audio.is_none() {
+            let _ = std::fs::remove_file(&recording);
+        }
+        let transcript = transcript?;
+        // Echo what was heard so a bad take can be interrupted early.
+        ctx.render.status(&format!("heard: {transcript}"));
+        transcript
+    } else {
+        match &args.prompt {
+            Some(p) => p.clone(),
+            None => {
+                use std::io::Read;
+                let mut buf = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buf)
+                    .context("failed to read prompt from stdin")?;
+                buf.trim().to_string()
+            }
+        }
+    };
+    anyhow::ensure!(!prompt.is_empty(), "empty prompt");
+
+    let attachments = build_attachments(args, ctx).await?;
+    let prompt_with_context = if attachments.is_empty() {
+        prompt.clone()
+    } else {
+        format!("{attachments}{prompt}")
+    };
+
+    let store = SessionStore::open()?;
+    let session_name = args
+        .session
+        .clone()
+        .unwrap_or_else(|| LAST_SESSION.to_string());
+    let history = if args.session.is_some() || args.continue_last {
+        store.load(&session_name)?
+    } else {
+        // A fresh ask restarts the implicit "last" session.
+        store.clear(&session_name)?;
+        Vec::new()
+    };
+
+    let system = ctx.system_prompt(&args.system)?;
+    let messages = build_messages_with_truncation(
+        system.as_deref(),
+        &history,
+        &prompt_with_context,
+        ctx.context_window()?,
+        &ctx.profile()?.provider,
+    );
+
+    if let Some(n) = args.choices {
+        return ask_choices(
+            args,
+            ctx,
+            &store,
+            &session_name,
+            &prompt_with_context,
+            messages,
+            n,
+        )
+        .await;
+    }
+
+    let events = ctx.render.streams_events();
+    let streamed = events || (args.stream && ctx.render.is_text());
+    let mut response = if streamed {
+        let req = ctx.chat_request(messages)?;
+        let provider = ctx.provider()?;
+        let render = ctx.render.clone();
+        let partial = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+        let sink = partial.clone();
+        let styler = std::sync::Arc::new(std::sync::Mutex::new(ctx.render.markdown_stream()));
+        let md = styler.clone();
+        let show_thinking = args.show_thinking;
+        let mut on_delta = move |delta: crate::llm::Delta| match delta {
+            crate::llm::Delta::Content(delta) => {
+                if events {
+                    render.emit_record(&serde_json::json!({"event": "delta", "content": delta}));
+                } else {
+                    render.data(&md.lock().unwrap().push(delta));
+                }
+                sink.lock().unwrap().push_str(delta);
+            }
+            crate::llm::Delta::Thinking(delta) => {
+                if events {
+                    render.emit_record(&serde_json::json!({"event": "thinking", "content": delta}));
+                } else if show_thinking {
+                    // Thinking goes to stderr dimmed; stdout stays answer-only.
+                    eprint!("\x1b[2m{delta}\x1b[0m");
+                }
+            }
+        };
+        let resp = tokio::select! {
+            r = provider.send_stream(&req, &mut on_delta) => r,
+            _ = ctx.cancel.cancelled() => {
+                // Finalize the partial exchange so the session stays coherent.
+                if events {
+                    ctx.render
+                        .emit_record(&serde_json::json!({"event": "error", "message": crate::cancel::INTERRUPTED}));
+                } else {
+                    let tail = styler.lock().unwrap().finish();
+                    ctx.render.data(&format!("{tail}\n"));
+                }
+                let partial = partial.lock().unwrap().clone();
+                store.append_capped(&session_name, &SessionRecord::now(Role::User, &prompt_with_context, None), ctx.config.session_max_record_bytes)?;
+                store.append_capped(&session_name, &SessionRecord::now(
+                        Role::Assistant,
+                        format!("{partial}\n[response interrupted]"),
+                        None,
+                    ), ctx.config.session_max_record_bytes)?;
+                anyhow::bail!(crate::cancel::INTERRUPTED);
+            }
+        };
+        let resp = match resp {
+            Ok(resp) => resp,
+            Err(e) => {
+                if events {
+                    ctx.render.emit_record(
+                        &serde_json::json!({"event": "error", "message": format!("{e:#}")}),
+                    );
+                }
+                return Err(e);
+            }
+        };
+        if events {
+            ctx.render.emit_record(&serde_json::json!({
+                "event": "usage",
+                "prompt_tokens": resp.meta.prompt_tokens,
+                "completion_tokens": resp.meta.completion_tokens,
+            }));
+            ctx.render
+                .emit_record(&serde_json::json!({"event": "done", "model": resp.model.as_str()}));
+        } else {
+            let tail = styler.lock().unwrap().finish();
+            ctx.render.data(&format!("{tail}\n"));
+        }
+        ctx.report_meta(&resp);
+        resp
+    } else {
+        ctx.complete(messages).await?
+    };
+
+    // Providers that return images or audio inline do so as data: URIs;
+    // decode them to artifact files so the terminal and the session stay
+    // text-only.
+    let mut artifact_store = crate::artifacts::ArtifactStore::begin(&ctx.workspace, "ask");
+    let (content, saved) = crate::artifacts::rewrite_data_uris(
+        &response.content,
+        &mut artifact_store,
+        &response.model,
+    )?;
+    response.content = content;
+    for path in &saved {
+        ctx.render
+            .status(&format!("artifact saved to {}", path.display()));
+    }
+    if !artifact_store.records().is_empty() {
+        ctx.render.status(&format!(
+            "manifest at {}",
+            artifact_store.dir().join("manifest.json").display()
+        ));
+    }
+    if !streamed {
+        ctx.render.emit(
+            &AskOutput {
+                answer: response.content.clone(),
+                model: response.model.clone(),
+                meta: response.meta.clone(),
+            },
+            || ctx.render.markdown(&response.content),
+        );
+    }
+
+    if args.copy {
+        ctx.copy_artifact(&response.content);
+    }
+    store.append_capped(
+        &session_name,
+        &SessionRecord::now(Role::User, &prompt_with_context, None),
+        ctx.config.session_max_record_bytes,
+    )?;
+    store.append_capped(
+        &session_name,
+        &SessionRecord::now(Role::Assistant, &response.content, Some(response.model)),
+        ctx.config.session_max_record_bytes,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,62 @@
+//! `sw backups` — inspect and restore the managed backup store.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::backups::{list_backups, restore_backup};
+use crate::cli::BackupsRestoreArgs;
+
+#[derive(Serialize)]
+struct ListEntry {
+    id: String,
+    created_at: String,
+    files: Vec<String>,
+}
+
+pub async fn cmd_backups_list(ctx: &AppContext) -> Result<()> {
+    let entries: Vec<ListEntry> = list_backups()?
+        .into_iter()
+        .map(|b| ListEntry {
+            id: b.id,
+            created_at: b.created_at.to_rfc3339(),
+            files: b.files.into_iter().map(|f| f.path).collect(),
+        })
+        .collect();
+    ctx.render.emit(&entries, || {
+        entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "{}  {}  ({} files: {})",
+                    e.id,
+                    e.created_at,
+                    e.files.len(),
+                    e.files.join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct RestoreOutput {
+    id: String,
+    restored: usize,
+}
+
+pub async fn cmd_backups_restore(args: &BackupsRestoreArgs, ctx: &AppContext) -> Result<()> {
+    let restored = restore_backup(&args.id, args.file.as_deref())?;
+    ctx.render.status(&format!(
+        "restored {restored} file(s) from backup {}",
+        args.id
+    ));
+    let out = RestoreOutput {
+        id: args.id.clone(),
+        restored,
+    };
+    ctx.render.emit(&out, String::new);
+    Ok(())
+}
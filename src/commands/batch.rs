@@ -0,0 +1,246 @@
+//! `sw batch` — apply an instruction across many files.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::checkpoint::{create_checkpoint, restore_checkpoint};
+use crate::cli::BatchTransformArgs;
+use crate::commands::generate::strip_code_fence;
+use crate::fsutil::{backup_file_async, read_file_to_string_async, write_file_async};
+use crate::llm::ChatMessage;
+
+#[derive(Serialize)]
+struct BatchOutput {
+    transformed: Vec<String>,
+    skipped: Vec<String>,
+    failed: Vec<String>,
+    dry_run: bool,
+}
+
+/// Per-file record of the last successful transform. A re-run skips any
+/// file whose current content and instruction both match its entry, so
+/// iterating on a batch only pays for files that actually changed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BatchManifest {
+    entries: BTreeMap<String, BatchEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BatchEntry {
+    instruction_hash: String,
+    /// Hash of the file as written by the transform; on re-run the input
+    /// is that same content, so a match means nothing to redo.
+    output_hash: String,
+}
+
+fn manifest_path(workspace: &Path) -> PathBuf {
+    workspace.join(".sw").join("batch.json")
+}
+
+fn load_manifest(workspace: &Path) -> BatchManifest {
+    std::fs::read_to_string(manifest_path(workspace))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(workspace: &Path, manifest: &BatchManifest) -> Result<()> {
+    let path = manifest_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(manifest)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn content_hash(content: &str) -> String {
+    use sha2::Digest;
+    format!("{:x}", sha2::Sha256::digest(content.as_bytes()))
+}
+
+enum Outcome {
+    Transformed { output_hash: String },
+    Skipped,
+}
+
+pub async fn cmd_batch_transform(args: &BatchTransformArgs, ctx: &AppContext) -> Result<()> {
+    let pattern = match &args.package {
+        Some(name) => {
+            let pkg = crate::workspace::find_package(&ctx.workspace, name)?;
+            format!("{}/{}", pkg.root.display(), args.glob)
+        }
+        None => args.glob.clone(),
+    };
+    let mut paths: Vec<std::path::PathBuf> = glob::glob(&pattern)
+        .context("invalid --glob pattern")?
+        .filter_map(|p| p.ok())
+        .filter(|p| p.is_file())
+        .collect();
+    // Globs like `**/*` sweep in binaries; skip them rather than fail the
+    // run — a text rewrite of a binary is always corruption.
+    paths.retain(|p| {
+        if crate::fsutil::is_binary(p) {
+            ctx.render
+                .warn(&format!("{}: skipping binary file", p.display()));
+            false
+        } else {
+            true
+        }
+    });
+    if let Some(filter) = &args.owner {
+        let owners = crate::owners::Owners::load(&ctx.workspace)?
+            .context("--owner requires a CODEOWNERS file in the workspace")?;
+        paths.retain(|p| {
+            let rel = p.strip_prefix(&ctx.workspace).unwrap_or(p);
+            owners
+                .owners_for(rel)
+                .is_some_and(|o| crate::owners::owner_matches(filter, o))
+        });
+    }
+
+    if args.dry_run {
+        let listed: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+        ctx.render
+            .status(&format!("{} file(s) match", listed.len()));
+        let out = BatchOutput {
+            transformed: listed,
+            skipped: Vec::new(),
+            failed: Vec::new(),
+            dry_run: true,
+        };
+        ctx.render.emit(&out, || out.transformed.join("\n"));
+        return Ok(());
+    }
+
+    for path in &paths {
+        ctx.ensure_sendable(path)?;
+    }
+    ctx.render
+        .status(&format!("transforming {} file(s)", paths.len()));
+
+    // Auto-checkpoint first so an interrupt can roll the tree back.
+    let workspace = ctx.workspace.clone();
+    let auto = create_checkpoint(
+        &workspace,
+        Some("auto: before batch transform".to_string()),
+        ctx.config.checkpoints_in_repo,
+    )?;
+    ctx.render
+        .status(&format!("auto-checkpoint {} created", auto.id));
+
+    // One worker task per file.
+    let mut manifest = load_manifest(&workspace);
+    let instruction_hash = content_hash(&args.instruction);
+    let mut handles = Vec::new();
+    for path in paths {
+        let instruction = args.instruction.clone();
+        let instruction_hash = instruction_hash.clone();
+        let previous = manifest.entries.get(&path.display().to_string()).cloned();
+        // Batch rewrites are the refactoring path; they honour the same
+        // per-language style hints as generate and diff propose.
+        let style = ctx
+            .config
+            .style_hint_for(&path)
+            .map(|hint| format!("\nStyle guide: {hint}"))
+            .unwrap_or_default();
+        let provider = ctx.provider()?;
+        let req_template = ctx.chat_request(Vec::new())?;
+        handles.push(tokio::spawn(async move {
+            let result: Result<Outcome> = async {
+                let content = read_file_to_string_async(&path).await?;
+                if previous.is_some_and(|p| {
+                    p.instruction_hash == instruction_hash
+                        && p.output_hash == content_hash(&content)
+                }) {
+                    return Ok(Outcome::Skipped);
+                }
+                let mut req = req_template;
+                req.messages = vec![
+                    ChatMessage::system(
+                        "You rewrite files per the instruction. Output only the \
+                         complete new file body — no fences, no commentary.",
+                    ),
+                    ChatMessage::user(format!(
+                        "Instruction: {instruction}{style}\n\nFile `{}`:\n\n{content}",
+                        path.display()
+                    )),
+                ];
+                let resp = provider.send(&req).await?;
+                let mut body = strip_code_fence(&resp.content).to_string();
+                if !body.ends_with('\n') {
+                    body.push('\n');
+                }
+                backup_file_async(&path).await?;
+                write_file_async(&path, &body).await?;
+                Ok(Outcome::Transformed {
+                    output_hash: content_hash(&body),
+                })
+            }
+            .await;
+            (path, result)
+        }));
+    }
+
+    let mut transformed = Vec::new();
+    let mut skipped = Vec::new();
+    let mut failed = Vec::new();
+    let mut interrupted = false;
+    for handle in handles.iter_mut() {
+        let joined = tokio::select! {
+            r = &mut *handle => r,
+            _ = ctx.cancel.cancelled() => {
+                interrupted = true;
+                break;
+            }
+        };
+        let (path, result) = joined.context("batch worker panicked")?;
+        match result {
+            Ok(Outcome::Transformed { output_hash }) => {
+                manifest.entries.insert(
+                    path.display().to_string(),
+                    BatchEntry {
+                        instruction_hash: instruction_hash.clone(),
+                        output_hash,
+                    },
+                );
+                transformed.push(path.display().to_string());
+            }
+            Ok(Outcome::Skipped) => skipped.push(path.display().to_string()),
+            Err(e) => {
+                ctx.render.warn(&format!("{}: {e:#}", path.display()));
+                failed.push(path.display().to_string());
+            }
+        }
+    }
+    if interrupted {
+        for handle in &handles {
+            handle.abort();
+        }
+        let restored = restore_checkpoint(&workspace, &auto.id, ctx.config.checkpoints_in_repo)?;
+        ctx.render.status(&format!(
+            "interrupted — restored {restored} file(s) from auto-checkpoint {}",
+            auto.id
+        ));
+        anyhow::bail!(crate::cancel::INTERRUPTED);
+    }
+
+    save_manifest(&workspace, &manifest)?;
+    ctx.render.status(&format!(
+        "{} transformed, {} skipped (unchanged), {} failed",
+        transformed.len(),
+        skipped.len(),
+        failed.len()
+    ));
+    let out = BatchOutput {
+        transformed,
+        skipped,
+        failed,
+        dry_run: false,
+    };
+    ctx.render.emit(&out, || out.transformed.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,117 @@
+//! `sw chat` — interactive REPL over a persistent session.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+
+use crate::app::AppContext;
+use crate::cli::ChatArgs;
+use crate::context::build_messages_with_truncation;
+use crate::llm::Role;
+use crate::session::{SessionRecord, SessionStore};
+
+pub async fn cmd_chat(args: &ChatArgs, ctx: &AppContext) -> Result<()> {
+    let store = SessionStore::open()?;
+    ctx.render.status(&format!(
+        "chat session '{}' — empty line or Ctrl-D to exit",
+        args.session
+    ));
+
+    let system = ctx.system_prompt(&args.system)?;
+    let stdin = std::io::stdin();
+    loop {
+        eprint!("> ");
+        std::io::stderr().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        let prompt = line.trim();
+        if prompt.is_empty() {
+            break;
+        }
+
+        let history = store.load(&args.session)?;
+        let messages = build_messages_with_truncation(
+            system.as_deref(),
+            &history,
+            prompt,
+            ctx.context_window()?,
+            &ctx.profile()?.provider,
+        );
+
+        let req = ctx.chat_request(messages)?;
+        let provider = ctx.provider()?;
+        let events = ctx.render.streams_events();
+        let render = ctx.render.clone();
+        let partial = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+        let sink = partial.clone();
+        let styler = std::sync::Arc::new(std::sync::Mutex::new(ctx.render.markdown_stream()));
+        let md = styler.clone();
+        let show_thinking = args.show_thinking;
+        let mut on_delta = move |delta: crate::llm::Delta| match delta {
+            crate::llm::Delta::Content(delta) => {
+                if events {
+                    render.emit_record(&serde_json::json!({"event": "delta", "content": delta}));
+                } else {
+                    render.data(&md.lock().unwrap().push(delta));
+                }
+                sink.lock().unwrap().push_str(delta);
+            }
+            crate::llm::Delta::Thinking(delta) => {
+                if events {
+                    render.emit_record(&serde_json::json!({"event": "thinking", "content": delta}));
+                } else if show_thinking {
+                    // Thinking goes to stderr dimmed; stdout stays answer-only.
+                    eprint!("\x1b[2m{delta}\x1b[0m");
+                }
+            }
+        };
+        let resp = tokio::select! {
+            r = provider.send_stream(&req, &mut on_delta) => r,
+            _ = ctx.cancel.cancelled() => {
+                // Keep the transcript coherent before bailing out.
+                if events {
+                    ctx.render
+                        .emit_record(&serde_json::json!({"event": "error", "message": crate::cancel::INTERRUPTED}));
+                } else {
+                    let tail = styler.lock().unwrap().finish();
+                    ctx.render.data(&format!("{tail}\n"));
+                }
+                let partial = partial.lock().unwrap().clone();
+                store.append_capped(&args.session, &SessionRecord::now(Role::User, prompt, None), ctx.config.session_max_record_bytes)?;
+                store.append_capped(&args.session, &SessionRecord::now(
+                        Role::Assistant,
+                        format!("{partial}\n[response interrupted]"),
+                        None,
+                    ), ctx.config.session_max_record_bytes)?;
+                anyhow::bail!(crate::cancel::INTERRUPTED);
+            }
+        }?;
+        if events {
+            ctx.render.emit_record(&serde_json::json!({
+                "event": "usage",
+                "prompt_tokens": resp.meta.prompt_tokens,
+                "completion_tokens": resp.meta.completion_tokens,
+            }));
+            ctx.render
+                .emit_record(&serde_json::json!({"event": "done", "model": resp.model.as_str()}));
+        } else {
+            let tail = styler.lock().unwrap().finish();
+            ctx.render.data(&format!("{tail}\n"));
+        }
+        ctx.report_meta(&resp);
+
+        store.append_capped(
+            &args.session,
+            &SessionRecord::now(Role::User, prompt, None),
+            ctx.config.session_max_record_bytes,
+        )?;
+        store.append_capped(
+            &args.session,
+            &SessionRecord::now(Role::Assistant, &resp.content, Some(resp.model)),
+            ctx.config.session_max_record_bytes,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! `sw checkpoint` — create, list, and restore workspace snapshots.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::checkpoint::{create_checkpoint, list_checkpoints, load_checkpoint, restore_checkpoint};
+use crate::cli::{
+    CheckpointCreateArgs, CheckpointListArgs, CheckpointRestoreArgs, CheckpointShowArgs,
+};
+
+#[derive(Serialize)]
+struct CreateOutput {
+    id: String,
+    files: usize,
+}
+
+pub async fn cmd_checkpoint_create(args: &CheckpointCreateArgs, ctx: &AppContext) -> Result<()> {
+    let workspace = ctx.workspace.clone();
+    let manifest = create_checkpoint(
+        &workspace,
+        args.description.clone(),
+        ctx.config.checkpoints_in_repo,
+    )?;
+    ctx.render.status(&format!(
+        "checkpoint {} created ({} files)",
+        manifest.id,
+        manifest.files.len()
+    ));
+    let out = CreateOutput {
+        id: manifest.id.clone(),
+        files: manifest.files.len(),
+    };
+    ctx.render.emit(&out, || manifest.id.clone());
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ListEntry {
+    id: String,
+    created_at: String,
+    description: Option<String>,
+    files: usize,
+}
+
+pub async fn cmd_checkpoint_list(args: &CheckpointListArgs, ctx: &AppContext) -> Result<()> {
+    let workspace = ctx.workspace.clone();
+    let mut manifests = list_checkpoints(&workspace, ctx.config.checkpoints_in_repo)?;
+    if let Some(spec) = &args.since {
+        let cutoff = chrono::Utc::now() - crate::stats::parse_since(spec)?;
+        manifests.retain(|m| m.created_at >= cutoff);
+    }
+    if let Some(path) = &args.contains_file {
+        // Manifests store portable forward-slash paths.
+        let want = path.replace('\\', "/");
+        manifests.retain(|m| m.files.iter().any(|f| f.path == want));
+    }
+    if let Some(needle) = &args.description_match {
+        let needle = needle.to_lowercase();
+        manifests.retain(|m| {
+            m.description
+                .as_deref()
+                .is_some_and(|d| d.to_lowercase().contains(&needle))
+        });
+    }
+    let entries: Vec<ListEntry> = manifests
+        .into_iter()
+        .map(|m| ListEntry {
+            id: m.id,
+            created_at: m.created_at.to_rfc3339(),
+            description: m.description,
+            files: m.files.len(),
+        })
+        .collect();
+    ctx.render.emit(&entries, || {
+        entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "{}  {}  ({} files)  {}",
+                    e.id,
+                    e.created_at,
+                    e.files,
+                    e.description.as_deref().unwrap_or("")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Ok(())
+}
+
+pub async fn cmd_checkpoint_show(args: &CheckpointShowArgs, ctx: &AppContext) -> Result<()> {
+    let manifest = load_checkpoint(&ctx.workspace, &args.id, ctx.config.checkpoints_in_repo)?;
+    ctx.render.emit(&manifest, || {
+        let total: u64 = manifest.files.iter().map(|f| f.size).sum();
+        let mut s = format!(
+            "{}  {}  ({} files, {total} bytes)  {}\n",
+            manifest.id,
+            manifest.created_at.to_rfc3339(),
+            manifest.files.len(),
+            manifest.description.as_deref().unwrap_or("")
+        );
+        for f in &manifest.files {
+            s.push_str(&format!("{}  {:>10}  {}\n", &f.hash[..12], f.size, f.path));
+        }
+        s.trim_end().to_string()
+    });
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct RestoreOutput {
+    id: String,
+    restored: usize,
+}
+
+pub async fn cmd_checkpoint_restore(args: &CheckpointRestoreArgs, ctx: &AppContext) -> Result<()> {
+    let workspace = ctx.workspace.clone();
+    let restored = restore_checkpoint(&workspace, &args.id, ctx.config.checkpoints_in_repo)?;
+    ctx.render
+        .status(&format!("restored {restored} file(s) from {}", args.id));
+    let out = RestoreOutput {
+        id: args.id.clone(),
+        restored,
+    };
+    ctx.render.emit(&out, String::new);
+    Ok(())
+}
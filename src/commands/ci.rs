@@ -0,0 +1,247 @@
+//! `sw ci triage` — isolate the first real failure in a CI log and
+//! diagnose it.
+//!
+//! CI logs run to megabytes; the isolation happens locally and only a
+//! small excerpt around the failure reaches the model.
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::CiTriageArgs;
+use crate::llm::ChatMessage;
+
+/// Source files from the excerpt listed in the structured output.
+const MAX_MAPPED_FILES: usize = 8;
+
+/// Load the log from `--log` (or stdin via `-`) or `--url`.
+async fn read_log(args: &CiTriageArgs, ctx: &AppContext) -> Result<(String, String)> {
+    if let Some(url) = &args.url {
+        ctx.render.status(&format!("downloading {url}"));
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .user_agent(concat!("sw-assist/", env!("CARGO_PKG_VERSION")))
+            .build()?;
+        let resp = tokio::select! {
+            r = client.get(url).send() => r.context("log download failed")?,
+            _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+        };
+        if !resp.status().is_success() {
+            bail!("log download failed ({})", resp.status());
+        }
+        let body = tokio::select! {
+            r = resp.text() => r.context("log download failed")?,
+            _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+        };
+        return Ok((body, url.clone()));
+    }
+    match &args.log {
+        Some(path) if path.as_os_str() == "-" => {
+            let mut buf = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf)
+                .context("failed to read the log from stdin")?;
+            Ok((buf, "stdin".to_string()))
+        }
+        Some(path) => {
+            let content = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            Ok((content, path.display().to_string()))
+        }
+        None => bail!("nothing to triage; pass --log FILE or --url URL"),
+    }
+}
+
+/// Strip the decoration CI systems wrap around every line — ANSI color
+/// codes, ISO timestamps, and `##[...]`/`[section]` markers — so the
+/// failure patterns match the underlying tool output.
+pub fn clean_line(line: &str) -> String {
+    let ansi = regex::Regex::new(r"\x1b\[[0-9;]*[A-Za-z]").expect("static regex");
+    let stamp = regex::Regex::new(r"^\d{4}-\d{2}-\d{2}T[0-9:.]+Z?\s*").expect("static regex");
+    let line = ansi.replace_all(line, "");
+    stamp.replace(line.trim_end(), "").into_owned()
+}
+
+/// Whether a (cleaned) line marks a real failure rather than noise.
+/// Ordered from tool-specific to generic; warnings never match.
+pub fn is_failure_line(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    if lower.starts_with("warning") || lower.contains(": warning") {
+        return false;
+    }
+    line.starts_with("##[error]")
+        || line.contains("panicked at")
+        || line.contains("Traceback (most recent call last)")
+        || line.contains("npm ERR!")
+        || line.contains("make: ***")
+        || line.contains("undefined reference")
+        || line.starts_with("error[")
+        || line.starts_with("error:")
+        || line.contains(": error:")
+        || line.contains(" error: ")
+        || line.contains("FAILED")
+        || line.contains("AssertionError")
+        || line.starts_with("fatal:")
+        || lower.contains("process completed with exit code")
+}
+
+/// Index of the first failing line, if any pattern matches.
+pub fn first_failure(lines: &[String]) -> Option<usize> {
+    lines.iter().position(|l| is_failure_line(l))
+}
+
+/// A window of `context` lines biased after the failure — tools print
+/// the detail (backtraces, assertions, hints) below the headline.
+fn excerpt(lines: &[String], failure: usize, context: usize) -> (usize, String) {
+    let before = context / 4;
+    let start = failure.saturating_sub(before);
+    let end = (start + context.max(1)).min(lines.len());
+    (start, lines[start..end].join("\n"))
+}
+
+/// Existing workspace files referenced in the excerpt, most useful first.
+fn referenced_files(excerpt: &str, ctx: &AppContext) -> Vec<String> {
+    let file_re =
+        regex::Regex::new(r"[A-Za-z0-9_][A-Za-z0-9_\-./]*\.[a-z]{1,4}\b").expect("static regex");
+    let mut seen = Vec::new();
+    for m in file_re.find_iter(excerpt) {
+        let rel = m.as_str().trim_start_matches("./");
+        if seen.iter().any(|s| s == rel) {
+            continue;
+        }
+        let path = std::path::PathBuf::from(rel);
+        if crate::analysis::language_for_path(&path) == "Other"
+            || !ctx.workspace.join(&path).is_file()
+        {
+            continue;
+        }
+        seen.push(rel.to_string());
+        if seen.len() >= MAX_MAPPED_FILES {
+            break;
+        }
+    }
+    seen
+}
+
+#[derive(Serialize)]
+struct CiTriageOutput {
+    source: String,
+    lines: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    failure_line: Option<usize>,
+    files: Vec<String>,
+    excerpt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diagnosis: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+}
+
+pub async fn cmd_ci_triage(args: &CiTriageArgs, ctx: &AppContext) -> Result<()> {
+    let (raw, source) = read_log(args, ctx).await?;
+    let lines: Vec<String> = raw.lines().map(clean_line).collect();
+    let failure = first_failure(&lines);
+    let (start, excerpt) = match failure {
+        Some(idx) => {
+            ctx.render.status(&format!(
+                "first failure at line {} of {}",
+                idx + 1,
+                lines.len()
+            ));
+            excerpt(&lines, idx, args.context)
+        }
+        None => {
+            // No pattern matched; the tail is where failures explain
+            // themselves, so triage that instead of giving up.
+            ctx.render
+                .warn("no known failure pattern matched; using the log tail");
+            let start = lines.len().saturating_sub(args.context);
+            (start, lines[start..].join("\n"))
+        }
+    };
+    let excerpt = ctx.redact(&excerpt);
+    let files = referenced_files(&excerpt, ctx);
+
+    let mut diagnosis = None;
+    let mut model = None;
+    if !args.no_diagnosis {
+        let mut blocks = String::new();
+        for rel in &files {
+            let Ok(content) = std::fs::read_to_string(ctx.workspace.join(rel)) else {
+                continue;
+            };
+            blocks.push_str(&format!(
+                "Contents of `{rel}`:\n```\n{}\n```\n\n",
+                ctx.redact(&content).trim_end_matches('\n')
+            ));
+        }
+        let prompt = format!(
+            "CI log excerpt starting at line {} of {} ({source}):\n\n```\n{}\n```\n\n{blocks}\
+             Identify the first real failure, what caused it, and which \
+             files are involved. Close with a `## Next steps` section \
+             listing the exact commands to reproduce or fix it locally.",
+            start + 1,
+            lines.len(),
+            excerpt.trim()
+        );
+        let messages = vec![
+            ChatMessage::system(
+                "You triage CI failures from log excerpts. Distinguish the \
+                 root failure from downstream noise; be concrete and brief; \
+                 never invent files or line numbers.",
+            ),
+            ChatMessage::user(prompt),
+        ];
+        let resp = ctx.complete(messages).await?;
+        diagnosis = Some(resp.content);
+        model = Some(resp.model);
+        ctx.render.status(
+            "rerun the failing command with `sw run --fix -- CMD` to get a proposed fix diff",
+        );
+    }
+
+    let output = CiTriageOutput {
+        source,
+        lines: lines.len(),
+        failure_line: failure.map(|i| i + 1),
+        files,
+        excerpt,
+        diagnosis,
+        model,
+    };
+    ctx.render.emit(&output, || match &output.diagnosis {
+        Some(d) => ctx.render.markdown(d),
+        None => output.excerpt.clone(),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warnings_do_not_count_as_failures() {
+        let lines: Vec<String> = [
+            "warning: unused variable `x`",
+            "src/lib.rs:10: warning: something",
+            "error[E0308]: mismatched types",
+            "FAILED tests::it_works",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(first_failure(&lines), Some(2));
+        assert_eq!(first_failure(&lines[..2]), None);
+    }
+
+    #[test]
+    fn ci_decoration_is_stripped_before_matching() {
+        let line = "2024-05-01T12:00:00.123Z \x1b[31merror: build failed\x1b[0m";
+        let cleaned = clean_line(line);
+        assert_eq!(cleaned, "error: build failed");
+        assert!(is_failure_line(&cleaned));
+        assert!(is_failure_line(
+            "##[error]Process completed with exit code 1."
+        ));
+    }
+}
@@ -0,0 +1,142 @@
+//! `sw commit-lint` — check existing commit messages against the
+//! configured convention, complementing `commit-msg` generation.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::CommitLintArgs;
+use crate::commands::commitmsg::lint_subject;
+use crate::gitutil;
+use crate::llm::ChatMessage;
+
+#[derive(Debug, Clone, Serialize)]
+struct LintEntry {
+    hash: String,
+    subject: String,
+    problems: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestion: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LintOutput {
+    range: String,
+    entries: Vec<LintEntry>,
+    violations: usize,
+}
+
+/// Ask the model for a conforming rewrite of one subject line.
+async fn suggest_rewrite(
+    ctx: &AppContext,
+    subject: &str,
+    problems: &[String],
+    types: &str,
+) -> Result<String> {
+    let messages = vec![
+        ChatMessage::system(format!(
+            "Rewrite the given commit subject as a conventional commit \
+             (type(scope): summary). Allowed types: {types}. Keep the \
+             original meaning. Output only the rewritten subject line."
+        )),
+        ChatMessage::user(format!(
+            "Subject: {subject}\nViolations: {}",
+            problems.join("; ")
+        )),
+    ];
+    let resp = ctx.complete(messages).await?;
+    Ok(resp
+        .content
+        .trim()
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Write a `git rebase -i` todo list that rewords the violating commits.
+fn write_reword_script(path: &Path, entries: &[LintEntry]) -> Result<()> {
+    let mut script = String::from("# git rebase -i todo generated by sw commit-lint\n");
+    // Rebase todo lists run oldest first.
+    for e in entries.iter().rev() {
+        let action = if e.problems.is_empty() {
+            "pick"
+        } else {
+            "reword"
+        };
+        script.push_str(&format!("{action} {} {}\n", e.hash, e.subject));
+        if let Some(s) = &e.suggestion {
+            script.push_str(&format!("# suggested: {s}\n"));
+        }
+    }
+    std::fs::write(path, script)?;
+    Ok(())
+}
+
+pub async fn cmd_commit_lint(args: &CommitLintArgs, ctx: &AppContext) -> Result<()> {
+    let commits = gitutil::commits_in_range(&args.range)?;
+    if commits.is_empty() {
+        ctx.render
+            .status(&format!("no commits in range {}", args.range));
+        return Ok(());
+    }
+
+    let style = &ctx.config.commit;
+    let types = style.types.join(", ");
+    let mut entries = Vec::new();
+    for (hash, subject) in commits {
+        let problems = lint_subject(&subject, style);
+        let suggestion = if args.suggest && !problems.is_empty() {
+            Some(suggest_rewrite(ctx, &subject, &problems, &types).await?)
+        } else {
+            None
+        };
+        entries.push(LintEntry {
+            hash,
+            subject,
+            problems,
+            suggestion,
+        });
+    }
+
+    if let Some(path) = &args.script {
+        write_reword_script(path, &entries)?;
+        ctx.render
+            .status(&format!("rebase script written to {}", path.display()));
+    }
+
+    let violations = entries.iter().filter(|e| !e.problems.is_empty()).count();
+    let out = LintOutput {
+        range: args.range.clone(),
+        entries,
+        violations,
+    };
+    ctx.render.emit(&out, || {
+        let mut s = String::new();
+        for e in &out.entries {
+            if e.problems.is_empty() {
+                continue;
+            }
+            s.push_str(&format!("{} {}\n", e.hash, e.subject));
+            for p in &e.problems {
+                s.push_str(&format!("    {p}\n"));
+            }
+            if let Some(sug) = &e.suggestion {
+                s.push_str(&format!("    suggested: {sug}\n"));
+            }
+        }
+        s.push_str(&format!(
+            "{} of {} commit(s) violate the convention",
+            out.violations,
+            out.entries.len()
+        ));
+        s
+    });
+
+    if violations > 0 {
+        bail!("{violations} commit(s) violate the convention");
+    }
+    Ok(())
+}
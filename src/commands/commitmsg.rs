@@ -0,0 +1,188 @@
+//! `sw commit-msg` — draft a commit message from the current diff.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::CommitMsgArgs;
+use crate::config::CommitStyle;
+use crate::gitutil;
+use crate::llm::ChatMessage;
+
+#[derive(Serialize)]
+struct CommitMsgOutput {
+    message: String,
+    model: String,
+}
+
+/// Render the configured style rules as prompt instructions.
+fn style_instructions(style: &CommitStyle) -> String {
+    let mut s = format!(
+        "Write a conventional commit message for the given diff: a concise \
+         subject line (type(scope): summary, <= {} chars), a blank line, \
+         then a short body when the change warrants it. Allowed types: {}.",
+        style.subject_limit,
+        style.types.join(", "),
+    );
+    if style.require_scope {
+        s.push_str(" A (scope) is required in the subject.");
+    }
+    if style.gitmoji {
+        s.push_str(" Prefix the subject with a fitting gitmoji shortcode (e.g. :sparkles:).");
+    }
+    if let Some(lang) = &style.language {
+        s.push_str(&format!(" Write the message in {lang}."));
+    }
+    s.push_str(" Output only the commit message.");
+    s
+}
+
+/// Check a subject line against the configured convention, returning a
+/// human-readable description of each violation.
+pub fn lint_subject(subject: &str, style: &CommitStyle) -> Vec<String> {
+    let mut problems = Vec::new();
+    if subject.chars().count() > style.subject_limit {
+        problems.push(format!(
+            "subject exceeds {} characters ({})",
+            style.subject_limit,
+            subject.chars().count()
+        ));
+    }
+    // An optional leading gitmoji shortcode is fine either way.
+    let checked = regex::Regex::new(r"^:[a-z0-9_+-]+:\s*")
+        .expect("static regex")
+        .replace(subject, "");
+    let re = regex::Regex::new(r"^(?P<type>[a-z]+)(?:\((?P<scope>[^)]+)\))?!?: .+")
+        .expect("static regex");
+    match re.captures(&checked) {
+        None => problems.push("subject is not in `type(scope): summary` form".to_string()),
+        Some(caps) => {
+            let ty = &caps["type"];
+            if !style.types.iter().any(|t| t == ty) {
+                problems.push(format!(
+                    "type '{ty}' is not allowed (expected one of {})",
+                    style.types.join(", ")
+                ));
+            }
+            if style.require_scope && caps.name("scope").is_none() {
+                problems.push("a (scope) is required in the subject".to_string());
+            }
+        }
+    }
+    problems
+}
+
+/// Truncate an over-long subject at a word boundary as a last-resort repair.
+fn truncate_subject(message: &str, limit: usize) -> String {
+    let mut lines: Vec<&str> = message.lines().collect();
+    let subject = lines.first().copied().unwrap_or_default();
+    if subject.chars().count() <= limit {
+        return message.to_string();
+    }
+    let cut: String = subject.chars().take(limit).collect();
+    let trimmed = match cut.rfind(' ') {
+        Some(pos) if pos > limit / 2 => cut[..pos].to_string(),
+        _ => cut,
+    };
+    let owned = trimmed;
+    lines[0] = &owned;
+    lines.join("\n")
+}
+
+pub async fn cmd_commit_msg(args: &CommitMsgArgs, ctx: &AppContext) -> Result<()> {
+    let mut diff = gitutil::staged_diff()?;
+    if args.all {
+        let unstaged = gitutil::working_diff()?;
+        if !unstaged.is_empty() {
+            diff.push('\n');
+            diff.push_str(&unstaged);
+        }
+    }
+    if diff.trim().is_empty() {
+        bail!("no changes to describe — stage something first");
+    }
+    let diff = ctx.redact(&diff);
+    let style = ctx.config.commit.clone();
+
+    let system = style_instructions(&style);
+    let messages = vec![
+        ChatMessage::system(&system),
+        ChatMessage::user(format!("```diff\n{diff}\n```")),
+    ];
+    let resp = ctx.complete(messages).await?;
+    let mut message = resp.content.trim().to_string();
+    let mut model = resp.model;
+
+    let subject = message.lines().next().unwrap_or_default().to_string();
+    let problems = lint_subject(&subject, &style);
+    if !problems.is_empty() {
+        // One corrective retry with the violations spelled out.
+        if ctx.verbose {
+            ctx.render
+                .status(&format!("retrying: {}", problems.join("; ")));
+        }
+        let retry = vec![
+            ChatMessage::system(&system),
+            ChatMessage::user(format!(
+                "```diff\n{diff}\n```\n\nYour previous attempt was:\n{message}\n\n\
+                 It violates the convention: {}. Produce a corrected message.",
+                problems.join("; ")
+            )),
+        ];
+        let resp = ctx.complete(retry).await?;
+        message = resp.content.trim().to_string();
+        model = resp.model;
+
+        let subject = message.lines().next().unwrap_or_default().to_string();
+        let mut remaining = lint_subject(&subject, &style);
+        if remaining.len() == 1 && remaining[0].starts_with("subject exceeds") {
+            // Length is the one violation we can repair mechanically.
+            message = truncate_subject(&message, style.subject_limit);
+            remaining.clear();
+        }
+        for p in &remaining {
+            ctx.render.warn(&format!("style violation: {p}"));
+        }
+    }
+
+    if args.copy {
+        ctx.copy_artifact(&message);
+    }
+    ctx.render.emit(
+        &CommitMsgOutput {
+            message: message.clone(),
+            model,
+        },
+        || message.clone(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_conventional_subjects() {
+        let style = CommitStyle::default();
+        assert!(lint_subject("fix(parser): handle empty hunks", &style).is_empty());
+        assert!(lint_subject("feat: add redaction", &style).is_empty());
+    }
+
+    #[test]
+    fn flags_unknown_type_and_missing_scope() {
+        let style = CommitStyle {
+            require_scope: true,
+            ..CommitStyle::default()
+        };
+        let problems = lint_subject("wip: stuff here", &style);
+        assert_eq!(problems.len(), 2);
+    }
+
+    #[test]
+    fn truncates_over_long_subjects_at_word_boundaries() {
+        let out = truncate_subject("fix: one two three four five\n\nbody", 20);
+        assert_eq!(out.lines().next(), Some("fix: one two three"));
+        assert!(out.contains("body"));
+    }
+}
@@ -0,0 +1,132 @@
+//! `sw compare` — run one task against several models side by side.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::CompareArgs;
+use crate::fsutil::read_file_to_string_async;
+use crate::llm::ChatMessage;
+
+#[derive(Serialize)]
+struct CompareEntry {
+    model: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prompt_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completion_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+}
+
+/// Assemble the shared task from whichever input flag was given.
+async fn build_task(args: &CompareArgs, ctx: &AppContext) -> Result<Vec<ChatMessage>> {
+    if let Some(prompt) = &args.prompt {
+        return Ok(vec![ChatMessage::user(prompt.clone())]);
+    }
+    if let Some(file) = &args.file {
+        ctx.ensure_sendable(file)?;
+        let content = ctx.redact(&read_file_to_string_async(file).await?);
+        return Ok(vec![
+            ChatMessage::system("You review code and point out concrete problems."),
+            ChatMessage::user(format!(
+                "Review `{}`:\n\n```\n{content}\n```",
+                file.display()
+            )),
+        ]);
+    }
+    let diff_file = args.diff_file.as_ref().expect("clap requires an input");
+    ctx.ensure_sendable(diff_file)?;
+    let diff = ctx.redact(&read_file_to_string_async(diff_file).await?);
+    Ok(vec![
+        ChatMessage::system("You review diffs and point out concrete problems."),
+        ChatMessage::user(format!("Review this diff:\n\n```diff\n{diff}\n```")),
+    ])
+}
+
+pub async fn cmd_compare(args: &CompareArgs, ctx: &AppContext) -> Result<()> {
+    let messages = build_task(args, ctx).await?;
+    let base_req = ctx.chat_request(messages)?;
+    let provider = ctx.provider()?;
+
+    let calls = args.models.iter().map(|model| {
+        let mut req = base_req.clone();
+        req.model = model.clone();
+        let provider = provider.as_ref();
+        async move {
+            let started = std::time::Instant::now();
+            let result = provider.send(&req).await;
+            (req.model, started.elapsed(), result)
+        }
+    });
+    let results = tokio::select! {
+        r = futures_util::future::join_all(calls) => r,
+        _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+    };
+
+    let entries: Vec<CompareEntry> = results
+        .into_iter()
+        .map(|(model, elapsed, result)| match result {
+            Ok(resp) => {
+                ctx.report_meta(&resp);
+                let cost = crate::stats::cost_usd(
+                    &ctx.config,
+                    &model,
+                    resp.meta.prompt_tokens.unwrap_or(0),
+                    resp.meta.completion_tokens.unwrap_or(0),
+                );
+                CompareEntry {
+                    model,
+                    ok: true,
+                    content: Some(resp.content),
+                    error: None,
+                    latency_ms: elapsed.as_millis() as u64,
+                    prompt_tokens: resp.meta.prompt_tokens,
+                    completion_tokens: resp.meta.completion_tokens,
+                    cost_usd: cost,
+                }
+            }
+            Err(e) => CompareEntry {
+                model,
+                ok: false,
+                content: None,
+                error: Some(format!("{e:#}")),
+                latency_ms: elapsed.as_millis() as u64,
+                prompt_tokens: None,
+                completion_tokens: None,
+                cost_usd: None,
+            },
+        })
+        .collect();
+
+    if entries.iter().all(|e| !e.ok) {
+        bail!("all {} model(s) failed", entries.len());
+    }
+    ctx.render.emit(&entries, || {
+        let mut s = String::new();
+        for entry in &entries {
+            let mut header = format!("=== {} ({}ms", entry.model, entry.latency_ms);
+            if let (Some(p), Some(c)) = (entry.prompt_tokens, entry.completion_tokens) {
+                header.push_str(&format!(", tokens={p}+{c}"));
+            }
+            if let Some(cost) = entry.cost_usd {
+                header.push_str(&format!(", cost=${cost:.4}"));
+            }
+            header.push_str(") ===\n");
+            s.push_str(&header);
+            match (&entry.content, &entry.error) {
+                (Some(content), _) => s.push_str(&format!("{}\n\n", content.trim_end())),
+                (None, Some(error)) => s.push_str(&format!("error: {error}\n\n")),
+                _ => {}
+            }
+        }
+        s.trim_end().to_string()
+    });
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! `sw debug` — diagnostics bundles for bug reports.
+
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::DebugBundleArgs;
+use crate::config::Config;
+
+#[derive(Serialize)]
+struct BundleOutput {
+    file: String,
+    entries: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Probe {
+    provider: String,
+    ok: bool,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// How many recent session transcripts to include.
+const MAX_TRANSCRIPTS: usize = 3;
+
+/// A copy of the config with every credential masked; env var *names*
+/// are kept since they are needed to reproduce key resolution.
+fn masked_config(config: &Config) -> Config {
+    let mut c = config.clone();
+    for profile in c.profiles.values_mut() {
+        if profile.api_key.is_some() {
+            profile.api_key = Some("***".to_string());
+        }
+        for key in &mut profile.api_keys {
+            *key = "***".to_string();
+        }
+    }
+    for provider in c.providers.values_mut() {
+        if provider.api_key.is_some() {
+            provider.api_key = Some("***".to_string());
+        }
+    }
+    c
+}
+
+/// The most recently written session transcripts, newest first.
+fn recent_transcripts() -> Result<Vec<(String, String)>> {
+    let dir = Config::data_dir()?.join("sessions");
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for entry in std::fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.ends_with(".jsonl") {
+            continue;
+        }
+        let modified = entry.metadata()?.modified()?;
+        entries.push((modified, entry.path(), name));
+    }
+    entries.sort_by_key(|e| std::cmp::Reverse(e.0));
+    entries
+        .into_iter()
+        .take(MAX_TRANSCRIPTS)
+        .map(|(_, path, name)| {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read session {}", path.display()))?;
+            Ok((name, content))
+        })
+        .collect()
+}
+
+pub async fn cmd_debug_bundle(args: &DebugBundleArgs, ctx: &AppContext) -> Result<()> {
+    let mut files: Vec<(String, String)> = Vec::new();
+
+    files.push((
+        "version.txt".to_string(),
+        format!(
+            "sw {}\nos: {} {}\ncaptured: {}\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            chrono::Utc::now().to_rfc3339(),
+        ),
+    ));
+
+    let config_toml =
+        toml::to_string_pretty(&masked_config(&ctx.config)).context("failed to render config")?;
+    files.push(("config.toml".to_string(), ctx.redact(&config_toml)));
+
+    for (name, content) in recent_transcripts()? {
+        files.push((format!("sessions/{name}"), ctx.redact(&content)));
+    }
+
+    if !args.no_probe {
+        ctx.render.status("probing provider latency");
+        let provider = ctx.provider()?;
+        let started = Instant::now();
+        let result = provider.list_models().await;
+        let probe = Probe {
+            provider: provider.name().to_string(),
+            ok: result.is_ok(),
+            latency_ms: started.elapsed().as_millis() as u64,
+            error: result.err().map(|e| ctx.redact(&format!("{e:#}"))),
+        };
+        files.push((
+            "probes.json".to_string(),
+            serde_json::to_string_pretty(&probe)?,
+        ));
+    }
+
+    let out = args.out.clone().unwrap_or_else(|| {
+        format!(
+            "sw-debug-{}.tar.gz",
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        )
+        .into()
+    });
+    let file = std::fs::File::create(&out)
+        .with_context(|| format!("failed to create {}", out.display()))?;
+    let gz = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut tar = tar::Builder::new(gz);
+    for (name, content) in &files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, format!("sw-debug/{name}"), content.as_bytes())?;
+    }
+    tar.into_inner()?
+        .finish()
+        .context("failed to write bundle")?;
+
+    ctx.render.status(&format!(
+        "wrote {} ({} entries)",
+        out.display(),
+        files.len()
+    ));
+    ctx.render.emit(
+        &BundleOutput {
+            file: out.display().to_string(),
+            entries: files.iter().map(|(n, _)| n.clone()).collect(),
+        },
+        || out.display().to_string(),
+    );
+    Ok(())
+}
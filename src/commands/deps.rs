@@ -0,0 +1,259 @@
+//! `sw deps` — dependency auditing: licenses, advisories, and an
+//! optional LLM read on risky upgrades.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::analysis::{parse_dependencies, Dependency};
+use crate::app::AppContext;
+use crate::cli::DepsAuditArgs;
+use crate::llm::ChatMessage;
+
+#[derive(Debug, Clone, Serialize)]
+struct Advisory {
+    id: String,
+    summary: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AuditEntry {
+    name: String,
+    ecosystem: String,
+    requirement: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    advisories: Vec<Advisory>,
+}
+
+#[derive(Serialize)]
+struct AuditOutput {
+    root: String,
+    entries: Vec<AuditEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+}
+
+/// Registry metadata for one dependency: `(latest, license)`. Failures
+/// degrade to `None` — an unreachable registry should not fail the audit.
+async fn fetch_metadata(
+    client: &reqwest::Client,
+    dep: &Dependency,
+) -> (Option<String>, Option<String>) {
+    let result = match dep.ecosystem {
+        "crates.io" => {
+            let url = format!("https://crates.io/api/v1/crates/{}", dep.name);
+            client.get(&url).send().await.ok().map(|r| (r, "crates.io"))
+        }
+        "npm" => {
+            let url = format!("https://registry.npmjs.org/{}", dep.name);
+            client.get(&url).send().await.ok().map(|r| (r, "npm"))
+        }
+        "PyPI" => {
+            let url = format!("https://pypi.org/pypi/{}/json", dep.name);
+            client.get(&url).send().await.ok().map(|r| (r, "PyPI"))
+        }
+        _ => None,
+    };
+    let Some((resp, ecosystem)) = result else {
+        return (None, None);
+    };
+    let Ok(value) = resp.json::<serde_json::Value>().await else {
+        return (None, None);
+    };
+    match ecosystem {
+        "crates.io" => {
+            let latest = value
+                .pointer("/crate/max_stable_version")
+                .or_else(|| value.pointer("/crate/max_version"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let license = value
+                .pointer("/versions/0/license")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            (latest, license)
+        }
+        "npm" => (
+            value
+                .pointer("/dist-tags/latest")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            value
+                .get("license")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        ),
+        _ => (
+            value
+                .pointer("/info/version")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            value
+                .pointer("/info/license")
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+        ),
+    }
+}
+
+/// A requirement string reduced to a concrete version for OSV queries,
+/// or `None` when the manifest does not pin anything usable.
+fn concrete_version(requirement: &str) -> Option<String> {
+    let v = requirement
+        .trim_start_matches(['^', '~', '=', '>', '<', ' '])
+        .trim();
+    (!v.is_empty() && v != "*" && v.chars().next().is_some_and(|c| c.is_ascii_digit()))
+        .then(|| v.to_string())
+}
+
+/// Known advisories for a dependency via the OSV query API.
+async fn fetch_advisories(client: &reqwest::Client, dep: &Dependency) -> Vec<Advisory> {
+    let Some(version) = concrete_version(&dep.requirement) else {
+        return Vec::new();
+    };
+    let query = serde_json::json!({
+        "package": { "name": dep.name, "ecosystem": dep.ecosystem },
+        "version": version,
+    });
+    let Ok(resp) = client
+        .post("https://api.osv.dev/v1/query")
+        .json(&query)
+        .send()
+        .await
+    else {
+        return Vec::new();
+    };
+    let Ok(value) = resp.json::<serde_json::Value>().await else {
+        return Vec::new();
+    };
+    value
+        .get("vulns")
+        .and_then(|v| v.as_array())
+        .map(|vulns| {
+            vulns
+                .iter()
+                .map(|v| Advisory {
+                    id: v
+                        .get("id")
+                        .and_then(|i| i.as_str())
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    summary: v
+                        .get("summary")
+                        .and_then(|s| s.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub async fn cmd_deps_audit(args: &DepsAuditArgs, ctx: &AppContext) -> Result<()> {
+    let root = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let deps = parse_dependencies(&root);
+    if deps.is_empty() {
+        bail!(
+            "no dependency manifest found under {} (looked for Cargo.toml, \
+             package.json, requirements.txt)",
+            root.display()
+        );
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent(concat!("sw-assist/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let mut entries = Vec::with_capacity(deps.len());
+    for dep in &deps {
+        if ctx.verbose {
+            ctx.render
+                .status(&format!("auditing {} ({})", dep.name, dep.ecosystem));
+        }
+        let (entry_meta, advisories) = tokio::select! {
+            r = async {
+                if args.offline {
+                    ((None, None), Vec::new())
+                } else {
+                    tokio::join!(fetch_metadata(&client, dep), fetch_advisories(&client, dep))
+                }
+            } => r,
+            _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+        };
+        entries.push(AuditEntry {
+            name: dep.name.clone(),
+            ecosystem: dep.ecosystem.to_string(),
+            requirement: dep.requirement.clone(),
+            latest: entry_meta.0,
+            license: entry_meta.1,
+            advisories,
+        });
+    }
+
+    let summary = if args.summarize {
+        let report = serde_json::to_string_pretty(&entries)?;
+        let messages = vec![
+            ChatMessage::system("You assess dependency audit reports. Be concrete and brief."),
+            ChatMessage::user(format!(
+                "Given this dependency audit, summarize the risky upgrades: \
+                 which advisories matter, which version jumps look breaking, \
+                 and any license concerns.\n\n{report}"
+            )),
+        ];
+        Some(ctx.complete(messages).await?.content)
+    } else {
+        None
+    };
+
+    let vulnerable = entries.iter().filter(|e| !e.advisories.is_empty()).count();
+    ctx.render.status(&format!(
+        "{} dependencies audited, {} with advisories",
+        entries.len(),
+        vulnerable
+    ));
+    let output = AuditOutput {
+        root: root.display().to_string(),
+        entries,
+        summary,
+    };
+    ctx.render.emit(&output, || {
+        let mut s = String::new();
+        for e in &output.entries {
+            let latest = e.latest.as_deref().unwrap_or("?");
+            let license = e.license.as_deref().unwrap_or("unknown license");
+            s.push_str(&format!(
+                "{} {} (latest {latest}, {license}) [{}]\n",
+                e.name, e.requirement, e.ecosystem
+            ));
+            for a in &e.advisories {
+                s.push_str(&format!("  {}: {}\n", a.id, a.summary));
+            }
+        }
+        if let Some(summary) = &output.summary {
+            s.push('\n');
+            s.push_str(&ctx.render.markdown(summary));
+        }
+        s.trim_end().to_string()
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concrete_versions_for_osv() {
+        assert_eq!(concrete_version("^1.2.3").as_deref(), Some("1.2.3"));
+        assert_eq!(concrete_version(">=2.0"), Some("2.0".to_string()));
+        assert_eq!(concrete_version("*"), None);
+        assert_eq!(concrete_version(""), None);
+    }
+}
@@ -0,0 +1,195 @@
+//! `sw diagram` — Mermaid diagrams from the repository import graph and
+//! analysis data, optionally refined by the model and rendered to SVG
+//! when `mmdc` (mermaid-cli) is on PATH.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::DiagramArgs;
+use crate::llm::ChatMessage;
+
+#[derive(Serialize)]
+struct DiagramOutput {
+    kind: String,
+    file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    svg: Option<String>,
+    nodes: usize,
+    edges: usize,
+    mermaid: String,
+}
+
+/// A path reduced to a Mermaid-safe node identifier.
+fn node_id(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// The module graph as `graph LR` Mermaid: the highest-ranked files as
+/// nodes, resolved local imports as arrows.
+fn modules_mermaid(nodes: &[String], edges: &[(String, String)]) -> String {
+    let mut out = String::from("graph LR\n");
+    for path in nodes {
+        out.push_str(&format!("    {}[\"{}\"]\n", node_id(path), path));
+    }
+    for (from, to) in edges {
+        out.push_str(&format!("    {} --> {}\n", node_id(from), node_id(to)));
+    }
+    out
+}
+
+/// Ask the model to draft a `sequenceDiagram` grounded in the repo map.
+async fn sequence_mermaid(ctx: &AppContext, root: &Path, focus: &str) -> Result<String> {
+    let outline = crate::repomap::prompt_block(root, 2000)
+        .context("cannot build a repository outline to ground the diagram")?;
+    let messages = vec![
+        ChatMessage::system(
+            "You draw Mermaid sequence diagrams of software flows. Output only \
+             valid Mermaid source starting with `sequenceDiagram` — no \
+             explanation, no markdown fences.",
+        ),
+        ChatMessage::user(format!(
+            "Repository outline:\n\n{outline}\n\nDraw a sequence diagram of: {focus}"
+        )),
+    ];
+    let resp = ctx.complete(messages).await?;
+    let mermaid = crate::commands::generate::strip_code_fence(&resp.content).to_string();
+    anyhow::ensure!(
+        mermaid.starts_with("sequenceDiagram"),
+        "model did not return a sequence diagram; try rephrasing --focus"
+    );
+    Ok(mermaid)
+}
+
+/// One refinement pass over generated Mermaid. Falls back to the input
+/// when the model's answer no longer parses as the same diagram type.
+async fn refine_mermaid(ctx: &AppContext, mermaid: &str) -> Result<String> {
+    let header = mermaid.lines().next().unwrap_or_default().to_string();
+    let messages = vec![
+        ChatMessage::system(
+            "You improve Mermaid diagrams: group related nodes into subgraphs, \
+             shorten labels, and drop noise, without inventing nodes or edges. \
+             Output only the Mermaid source — no explanation, no fences.",
+        ),
+        ChatMessage::user(mermaid.to_string()),
+    ];
+    let resp = ctx.complete(messages).await?;
+    let refined = crate::commands::generate::strip_code_fence(&resp.content).to_string();
+    if refined.starts_with(header.split_whitespace().next().unwrap_or("graph")) {
+        Ok(refined)
+    } else {
+        ctx.render
+            .warn("refinement changed the diagram type; keeping the original");
+        Ok(mermaid.to_string())
+    }
+}
+
+/// Render `source.mmd` to SVG next to it via mermaid-cli.
+async fn render_svg(source: &Path, ctx: &AppContext) -> Result<std::path::PathBuf> {
+    if !crate::platform::has_command("mmdc") {
+        bail!("mmdc not found on PATH; install mermaid-cli or drop --svg");
+    }
+    let svg = source.with_extension("svg");
+    let mut cmd = tokio::process::Command::new("mmdc");
+    cmd.arg("-i").arg(source).arg("-o").arg(&svg);
+    cmd.kill_on_drop(true);
+    let output = tokio::select! {
+        r = cmd.output() => r.context("failed to run mmdc")?,
+        _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+    };
+    if !output.status.success() {
+        bail!(
+            "mmdc failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(svg)
+}
+
+pub async fn cmd_diagram(args: &DiagramArgs, ctx: &AppContext) -> Result<()> {
+    let root = args.path.clone().unwrap_or_else(|| ctx.workspace.clone());
+    if !ctx.config.allow_outside_workspace {
+        crate::fsutil::ensure_within_workspace(&args.out, &ctx.workspace)?;
+    }
+
+    let (mermaid, nodes, edges) = match args.kind.as_str() {
+        "modules" => {
+            let map = crate::repomap::load_or_build(&root, false)?;
+            let nodes: Vec<String> = map
+                .entries
+                .iter()
+                .take(args.max_nodes)
+                .map(|e| e.path.clone())
+                .collect();
+            let edges: Vec<(String, String)> = map
+                .import_edges()
+                .into_iter()
+                .filter(|(from, to)| nodes.contains(from) && nodes.contains(to))
+                .collect();
+            let mut mermaid = modules_mermaid(&nodes, &edges);
+            if args.refine {
+                mermaid = refine_mermaid(ctx, &mermaid).await?;
+            }
+            (mermaid, nodes.len(), edges.len())
+        }
+        "sequence" => {
+            let focus = args
+                .focus
+                .as_deref()
+                .context("--kind sequence needs --focus describing the flow to trace")?;
+            let mermaid = sequence_mermaid(ctx, &root, focus).await?;
+            (mermaid, 0, 0)
+        }
+        other => bail!("unknown diagram kind '{other}' (expected modules or sequence)"),
+    };
+
+    let mut content = mermaid.clone();
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    crate::fsutil::write_file_async(&args.out, &content).await?;
+    ctx.render.status(&format!(
+        "wrote {} ({} nodes, {} edges)",
+        args.out.display(),
+        nodes,
+        edges
+    ));
+
+    let svg = if args.svg {
+        let path = render_svg(&args.out, ctx).await?;
+        ctx.render.status(&format!("rendered {}", path.display()));
+        Some(path.display().to_string())
+    } else {
+        None
+    };
+
+    let output = DiagramOutput {
+        kind: args.kind.clone(),
+        file: args.out.display().to_string(),
+        svg,
+        nodes,
+        edges,
+        mermaid,
+    };
+    ctx.render.emit(&output, || output.mermaid.clone());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_graphs_are_valid_mermaid() {
+        let nodes = vec!["src/main.rs".to_string(), "src/config.rs".to_string()];
+        let edges = vec![("src/main.rs".to_string(), "src/config.rs".to_string())];
+        let mermaid = modules_mermaid(&nodes, &edges);
+        assert!(mermaid.starts_with("graph LR\n"));
+        assert!(mermaid.contains("src_main_rs[\"src/main.rs\"]"));
+        assert!(mermaid.contains("src_main_rs --> src_config_rs"));
+    }
+}
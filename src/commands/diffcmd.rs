@@ -0,0 +1,453 @@
+//! `sw diff` — propose changes as unified diffs and apply them.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::{DiffApplyArgs, DiffProposeArgs};
+use crate::commands::generate::strip_code_fence;
+use crate::diff::{apply_file_diff, parse_diff_target_files, parse_unified_diff};
+use crate::fsutil::{backup_file_async, read_file_to_string_async, write_file_async};
+use crate::llm::ChatMessage;
+
+#[derive(Serialize)]
+struct ProposeOutput {
+    file: String,
+    diff: String,
+    model: String,
+}
+
+pub const DIFF_SYSTEM: &str = "You produce minimal, correct changes as a unified diff (--- a/..., \
+     +++ b/..., @@ hunks with accurate line numbers and context). \
+     Output only the diff.";
+
+/// Keep only the tail of a test log; the failure detail lives at the end.
+const MAX_FAILURE_BYTES: usize = 12 * 1024;
+
+/// Run the named test and assemble a repair prompt from its failure output
+/// and the source files it mentions.
+async fn build_test_prompt(
+    test: &str,
+    instruction: Option<&str>,
+    ctx: &AppContext,
+) -> Result<String> {
+    ctx.render.status(&format!("running test {test}"));
+    let mut cmd = tokio::process::Command::new("cargo");
+    cmd.args(["test", test]);
+    cmd.kill_on_drop(true);
+    let out = tokio::select! {
+        r = cmd.output() => r.context("failed to run cargo test")?,
+        _ = ctx.cancel.cancelled() => anyhow::bail!(crate::cancel::INTERRUPTED),
+    };
+    if out.status.success() {
+        anyhow::bail!("test '{test}' passes — nothing to fix");
+    }
+    let mut failure = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    if failure.len() > MAX_FAILURE_BYTES {
+        let mut start = failure.len() - MAX_FAILURE_BYTES;
+        while !failure.is_char_boundary(start) {
+            start += 1;
+        }
+        failure = failure[start..].to_string();
+    }
+
+    // Pull the source files the failure mentions into the prompt.
+    let file_re = regex::Regex::new(r"[A-Za-z0-9_][A-Za-z0-9_\-./]*\.rs").expect("static regex");
+    let mut seen = Vec::new();
+    for m in file_re.find_iter(&failure) {
+        let path = std::path::PathBuf::from(m.as_str());
+        // Auto-gathered context never overrides the content policy, and
+        // binaries have nothing to quote.
+        if ctx.ensure_sendable(&path).is_err() || crate::fsutil::is_binary(&path) {
+            continue;
+        }
+        if path.exists() && !seen.contains(&path) {
+            seen.push(path);
+        }
+        if seen.len() >= 5 {
+            break;
+        }
+    }
+    let mut blocks = String::new();
+    for path in &seen {
+        let content = ctx.redact(&read_file_to_string_async(path).await?);
+        blocks.push_str(&format!(
+            "Contents of `{}`:\n```rust\n{}\n```\n\n",
+            path.display(),
+            content.trim_end_matches('\n')
+        ));
+    }
+
+    let mut prompt = format!(
+        "The test `{test}` fails with:\n\n```\n{}\n```\n\n{blocks}",
+        failure.trim()
+    );
+    if let Some(instruction) = instruction {
+        prompt.push_str(&format!("Additional guidance: {instruction}\n\n"));
+    }
+    prompt.push_str("Propose a fix that makes the test pass, as a unified diff.");
+    Ok(prompt)
+}
+
+/// Bundle related files (import targets, test files) into prompt blocks
+/// under a token budget: full contents while they fit, then just a symbol
+/// outline so the model still sees which APIs exist.
+fn build_context_pack(file: &std::path::Path, ctx: &AppContext, budget: usize) -> String {
+    use crate::analysis::{extract_symbols, language_for_path, related_files};
+    use crate::context::estimate_tokens;
+
+    let mut blocks = String::new();
+    let mut remaining = budget;
+    for related in related_files(file) {
+        if ctx.ensure_sendable(&related).is_err() {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&related) else {
+            continue;
+        };
+        let content = ctx.redact(&content);
+        let cost = estimate_tokens(&content);
+        if cost <= remaining {
+            blocks.push_str(&format!(
+                "Related file `{}`:\n```\n{}\n```\n\n",
+                related.display(),
+                content.trim_end_matches('\n')
+            ));
+            remaining -= cost;
+            if ctx.verbose {
+                ctx.render
+                    .status(&format!("context: {} (full)", related.display()));
+            }
+            continue;
+        }
+        let symbols = extract_symbols(&content, language_for_path(&related));
+        if symbols.is_empty() {
+            continue;
+        }
+        let outline = symbols
+            .iter()
+            .map(|s| format!("{} {} (line {})", s.kind, s.name, s.line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let cost = estimate_tokens(&outline);
+        if cost <= remaining {
+            blocks.push_str(&format!(
+                "Symbols defined in `{}` (too large to inline):\n{outline}\n\n",
+                related.display()
+            ));
+            remaining -= cost;
+            if ctx.verbose {
+                ctx.render
+                    .status(&format!("context: {} (outline)", related.display()));
+            }
+        }
+    }
+    blocks
+}
+
+/// Design angles requested under `--alternatives`, in presentation order.
+const ALTERNATIVE_ANGLES: &[&str] = &[
+    "the most minimal, surgical change",
+    "a cleaner refactor of the surrounding code",
+    "a defensive implementation with extra validation and error handling",
+    "an approach optimized for performance",
+];
+
+/// `--alternatives N`: request N diffs taking distinct approaches,
+/// present them labelled, and return the picked one plus the model name.
+async fn propose_alternatives(
+    base_prompt: &str,
+    n: usize,
+    ctx: &AppContext,
+) -> Result<(String, String)> {
+    anyhow::ensure!(
+        (2..=ALTERNATIVE_ANGLES.len()).contains(&n),
+        "--alternatives takes 2 to {}",
+        ALTERNATIVE_ANGLES.len()
+    );
+    let provider = ctx.provider()?;
+    ctx.render.status(&format!("requesting {n} alternatives"));
+    let mut reqs = Vec::new();
+    for angle in ALTERNATIVE_ANGLES.iter().take(n) {
+        let messages = vec![
+            ChatMessage::system(DIFF_SYSTEM),
+            ChatMessage::user(format!("{base_prompt}\n\nFor this proposal take {angle}.")),
+        ];
+        reqs.push(ctx.chat_request(messages)?);
+    }
+    let calls = reqs.iter().map(|req| {
+        let provider = provider.as_ref();
+        async move { provider.send(req).await }
+    });
+    let results = tokio::select! {
+        r = futures_util::future::join_all(calls) => r,
+        _ = ctx.cancel.cancelled() => anyhow::bail!(crate::cancel::INTERRUPTED),
+    };
+
+    let mut model = String::new();
+    let mut candidates = Vec::new();
+    for (angle, result) in ALTERNATIVE_ANGLES.iter().zip(results) {
+        match result {
+            Ok(resp) => {
+                ctx.report_meta(&resp);
+                model = resp.model;
+                candidates.push((*angle, strip_code_fence(&resp.content).to_string()));
+            }
+            Err(e) => ctx.render.warn(&format!("alternative failed: {e:#}")),
+        }
+    }
+    anyhow::ensure!(!candidates.is_empty(), "all {n} alternatives failed");
+
+    if ctx.render.is_text() {
+        for (i, (angle, diff)) in candidates.iter().enumerate() {
+            let note = match parse_diff_target_files(diff) {
+                Ok(_) => String::new(),
+                Err(_) => " [does not parse as a unified diff]".to_string(),
+            };
+            ctx.render.data(&format!(
+                "=== alternative {} — {angle}{note} ===\n{}\n\n",
+                i + 1,
+                diff.trim_end()
+            ));
+        }
+    }
+    let picked = if ctx.render.is_text() {
+        crate::commands::ask::pick_choice(candidates.len(), ctx)
+    } else {
+        1
+    };
+    Ok((candidates[picked - 1].1.clone(), model))
+}
+
+pub async fn cmd_diff_propose(args: &DiffProposeArgs, ctx: &AppContext) -> Result<()> {
+    let (label, user_prompt) = if let Some(test) = &args.test {
+        (
+            format!("test {test}"),
+            build_test_prompt(test, args.instruction.as_deref(), ctx).await?,
+        )
+    } else {
+        let file = args
+            .file
+            .as_ref()
+            .expect("clap requires file without --test");
+        let instruction = args
+            .instruction
+            .as_deref()
+            .context("an instruction is required when proposing from a file")?;
+        ctx.ensure_sendable(file)?;
+        crate::fsutil::ensure_text_file(file)?;
+        let content = ctx.redact(&read_file_to_string_async(file).await?);
+        let path = file.display().to_string();
+        // Related definitions keep the model from inventing APIs that do
+        // not exist in this codebase.
+        let budget = (ctx.context_window()? / 4).min(8_000);
+        // The ranked repo map grounds the diff in real paths and symbols;
+        // skipped silently when it cannot be built.
+        let map = crate::repomap::prompt_block(&ctx.workspace, budget / 4)
+            .map(|m| format!("Repository outline (ranked by incoming references):\n{m}\n\n"))
+            .unwrap_or_default();
+        if ctx.verbose && !map.is_empty() {
+            ctx.render.status("context: repository map");
+        }
+        let pack = build_context_pack(file, ctx, budget);
+        let style = ctx
+            .config
+            .style_hint_for(file)
+            .map(|hint| format!("Style guide for this file: {hint}\n\n"))
+            .unwrap_or_default();
+        (
+            path.clone(),
+            format!(
+                "Current contents of `{path}`:\n\n```\n{content}\n```\n\n{map}{pack}{style}\
+                 Change request: {instruction}\n\nRespond with a unified diff."
+            ),
+        )
+    };
+
+    let (mut diff_text, model) = if args.alternatives > 0 {
+        propose_alternatives(&user_prompt, args.alternatives, ctx).await?
+    } else {
+        let messages = vec![
+            ChatMessage::system(DIFF_SYSTEM),
+            ChatMessage::user(user_prompt),
+        ];
+        let resp = ctx.complete(messages).await?;
+        (strip_code_fence(&resp.content).to_string(), resp.model)
+    };
+    if args.self_review > 0 {
+        let instruction = args
+            .instruction
+            .as_deref()
+            .unwrap_or("make the failing test pass");
+        diff_text = crate::commands::generate::self_review(
+            ctx,
+            instruction,
+            "unified diff",
+            diff_text,
+            args.self_review,
+            &|candidate| {
+                parse_diff_target_files(candidate)
+                    .err()
+                    .map(|e| format!("{e:#}"))
+            },
+        )
+        .await?;
+    }
+
+    // Validate the model output parses before handing it to the user.
+    let targets =
+        parse_diff_target_files(&diff_text).context("model did not return a valid unified diff")?;
+    if ctx.verbose {
+        for t in &targets {
+            ctx.render.status(&format!("target: {}", t.display()));
+        }
+    }
+
+    if args.copy {
+        ctx.copy_artifact(&diff_text);
+    }
+    match &args.out {
+        Some(out) => {
+            let mut body = diff_text.clone();
+            if !body.ends_with('\n') {
+                body.push('\n');
+            }
+            write_file_async(out, &body).await?;
+            ctx.render
+                .status(&format!("proposed diff written to {}", out.display()));
+        }
+        None => {
+            ctx.render.emit(
+                &ProposeOutput {
+                    file: label,
+                    diff: diff_text.clone(),
+                    model,
+                },
+                || diff_text.clone(),
+            );
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ApplyOutput {
+    applied: Vec<String>,
+    dry_run: bool,
+}
+
+/// Validate model-chosen diff targets before any write: they must stay
+/// inside the workspace unless `allow_outside_workspace` is set.
+fn ensure_targets_safe(targets: &[std::path::PathBuf], ctx: &AppContext) -> Result<()> {
+    if ctx.config.allow_outside_workspace {
+        return Ok(());
+    }
+    for target in targets {
+        crate::fsutil::ensure_within_workspace(target, &ctx.workspace)?;
+    }
+    Ok(())
+}
+
+/// Apply through `git apply`, which brings its own fuzzy matching and
+/// conflict reporting. The patch is validated with `--check` first.
+async fn git_apply(args: &DiffApplyArgs, ctx: &AppContext, text: &str) -> Result<()> {
+    anyhow::ensure!(
+        crate::gitutil::in_work_tree(),
+        "the git backend requires a git work tree"
+    );
+    let patch = args.patch.to_string_lossy().to_string();
+    crate::gitutil::git(&["apply", "--check", &patch]).context("patch does not apply cleanly")?;
+
+    let targets = parse_diff_target_files(text)?;
+    ensure_targets_safe(&targets, ctx)?;
+    if args.dry_run {
+        ctx.render
+            .status(&format!("would apply to {} file(s)", targets.len()));
+    } else {
+        let mut apply_args = vec!["apply"];
+        if args.cached {
+            apply_args.push("--cached");
+        }
+        apply_args.push(&patch);
+        crate::gitutil::git(&apply_args)?;
+        ctx.render
+            .status(&format!("applied to {} file(s)", targets.len()));
+        for target in &targets {
+            crate::provenance::track(ctx.config.provenance, target, "diff apply", None, text)?;
+        }
+    }
+    let applied: Vec<String> = targets.iter().map(|t| t.display().to_string()).collect();
+    ctx.render.emit(
+        &ApplyOutput {
+            applied: applied.clone(),
+            dry_run: args.dry_run,
+        },
+        || applied.join("\n"),
+    );
+    Ok(())
+}
+
+pub async fn cmd_diff_apply(args: &DiffApplyArgs, ctx: &AppContext) -> Result<()> {
+    let text = read_file_to_string_async(&args.patch).await?;
+    if args.backend == crate::cli::ApplyBackend::Git {
+        return git_apply(args, ctx, &text).await;
+    }
+    let diffs = parse_unified_diff(&text)?;
+
+    let mut applied = Vec::new();
+    for diff in &diffs {
+        let target = diff
+            .target_path()
+            .context("diff section has no target path")?
+            .clone();
+        ensure_targets_safe(std::slice::from_ref(&target), ctx)?;
+
+        if diff.is_deletion() {
+            if !args.dry_run {
+                tokio::fs::remove_file(&target)
+                    .await
+                    .with_context(|| format!("failed to delete {}", target.display()))?;
+            }
+            applied.push(format!("{} (deleted)", target.display()));
+            continue;
+        }
+
+        let current = if diff.is_creation() {
+            String::new()
+        } else {
+            crate::fsutil::ensure_text_file(&target)?;
+            read_file_to_string_async(&target).await?
+        };
+        let updated = apply_file_diff(diff, &current)
+            .with_context(|| format!("failed to apply hunks to {}", target.display()))?;
+
+        if !args.dry_run {
+            if !args.no_backup && !diff.is_creation() {
+                backup_file_async(&target).await?;
+            }
+            write_file_async(&target, &updated).await?;
+            crate::provenance::track(ctx.config.provenance, &target, "diff apply", None, &text)?;
+        }
+        applied.push(target.display().to_string());
+    }
+
+    let verb = if args.dry_run {
+        "would apply to"
+    } else {
+        "applied to"
+    };
+    ctx.render
+        .status(&format!("{verb} {} file(s)", applied.len()));
+    ctx.render.emit(
+        &ApplyOutput {
+            applied: applied.clone(),
+            dry_run: args.dry_run,
+        },
+        || applied.join("\n"),
+    );
+    Ok(())
+}
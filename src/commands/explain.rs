@@ -0,0 +1,314 @@
+//! `sw explain` — explain a file, a line range, or a named symbol.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::analysis::{find_symbol, SymbolLocation};
+use crate::app::AppContext;
+use crate::cli::ExplainArgs;
+use crate::fsutil::read_file_to_string_async;
+use crate::llm::ChatMessage;
+
+/// Call sites quoted in a symbol prompt; enough to show usage patterns.
+const MAX_CALL_SITES: usize = 8;
+
+#[derive(Serialize)]
+struct ExplainOutput {
+    file: String,
+    explanation: String,
+    model: String,
+}
+
+fn parse_line_range(spec: &str) -> Result<(usize, usize)> {
+    let Some((start, end)) = spec.split_once(':') else {
+        bail!("invalid --lines '{spec}', expected start:end");
+    };
+    let start: usize = start.parse()?;
+    let end: usize = end.parse()?;
+    if start == 0 || end < start {
+        bail!("invalid --lines '{spec}', expected 1-based start <= end");
+    }
+    Ok((start, end))
+}
+
+/// The definition block starting at `line` (1-based): leading doc
+/// comments and attributes, then either the balanced-brace body or, for
+/// braceless languages, the indented block. Heuristic, capped.
+fn extract_definition(content: &str, line: usize) -> String {
+    const MAX_DEFINITION_LINES: usize = 120;
+    let lines: Vec<&str> = content.lines().collect();
+    let def = line.saturating_sub(1).min(lines.len().saturating_sub(1));
+
+    let mut start = def;
+    while start > 0 {
+        let prev = lines[start - 1].trim();
+        if prev.starts_with("///") || prev.starts_with("//") || prev.starts_with("#[") {
+            start -= 1;
+        } else {
+            break;
+        }
+    }
+
+    let base_indent = lines[def].len() - lines[def].trim_start().len();
+    let mut depth = 0i32;
+    let mut opened = false;
+    let mut end = def;
+    for (i, text) in lines.iter().enumerate().skip(def) {
+        if i - def >= MAX_DEFINITION_LINES {
+            break;
+        }
+        depth += text.matches('{').count() as i32 - text.matches('}').count() as i32;
+        if depth > 0 {
+            opened = true;
+        }
+        end = i;
+        if opened && depth <= 0 {
+            break;
+        }
+        // Braceless block (Python): stop when indentation falls back.
+        if !opened && i > def {
+            let trimmed = text.trim();
+            if !trimmed.is_empty() && text.len() - text.trim_start().len() <= base_indent {
+                end = i - 1;
+                break;
+            }
+        }
+    }
+    lines[start..=end].join("\n")
+}
+
+/// Lines elsewhere in the tree that mention the symbol, as
+/// `path:line: text`, capped at [`MAX_CALL_SITES`].
+fn collect_call_sites(root: &Path, name: &str, def: &SymbolLocation) -> Vec<String> {
+    let Ok(re) = regex::Regex::new(&format!(r"\b{}\b", regex::escape(name))) else {
+        return Vec::new();
+    };
+    let mut sites = Vec::new();
+    let Ok(files) = crate::commands::files::walk_files(root, &[]) else {
+        return Vec::new();
+    };
+    for path in files {
+        if crate::analysis::language_for_path(&path) == "Other" {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for (i, text) in content.lines().enumerate() {
+            if path == def.path && i + 1 == def.line {
+                continue;
+            }
+            if re.is_match(text) {
+                sites.push(format!("{}:{}: {}", path.display(), i + 1, text.trim()));
+                if sites.len() >= MAX_CALL_SITES {
+                    return sites;
+                }
+            }
+        }
+    }
+    sites
+}
+
+/// Render language-server reference locations in the same `path:line:
+/// text` shape as the regex call-site scan.
+fn reference_lines(refs: &[(std::path::PathBuf, usize)]) -> Vec<String> {
+    let mut sites = Vec::new();
+    for (path, line) in refs.iter().take(MAX_CALL_SITES) {
+        let text = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|c| c.lines().nth(line - 1).map(|t| t.trim().to_string()))
+            .unwrap_or_default();
+        sites.push(format!("{}:{line}: {text}", path.display()));
+    }
+    sites
+}
+
+/// Locate `symbol` and assemble a prompt around its definition and call
+/// sites. A `file` argument narrows the search to that file.
+fn build_symbol_prompt(
+    symbol: &str,
+    file: Option<&Path>,
+    ctx: &AppContext,
+) -> Result<(String, String, String)> {
+    let root = ctx.workspace.as_path();
+    let mut hits = find_symbol(root, symbol)?;
+    if let Some(file) = file {
+        hits.retain(|h| h.path == file);
+    }
+    let Some(def) = hits.first().cloned() else {
+        bail!("symbol '{symbol}' not found under {}", root.display());
+    };
+    if hits.len() > 1 {
+        let others: Vec<String> = hits
+            .iter()
+            .skip(1)
+            .map(|h| format!("{}:{}", h.path.display(), h.line))
+            .collect();
+        ctx.render.warn(&format!(
+            "{} definitions of '{symbol}'; explaining {}:{} (also: {})",
+            hits.len(),
+            def.path.display(),
+            def.line,
+            others.join(", ")
+        ));
+    }
+    ctx.ensure_sendable(&def.path)?;
+    let content = std::fs::read_to_string(&def.path)?;
+    let definition = ctx.redact(&extract_definition(&content, def.line));
+    let bare = symbol.rsplit("::").next().unwrap_or(symbol);
+
+    // A configured language server gives ground-truth hover and reference
+    // facts; any failure falls back quietly to the regex heuristics.
+    let mut facts = None;
+    if ctx.config.lsp.enabled {
+        match crate::lsp::symbol_facts(root, &def.path, def.line, bare, &ctx.config.lsp) {
+            Ok(f) => facts = f,
+            Err(e) => ctx.render.warn(&format!("lsp: {e:#}")),
+        }
+    }
+    let sites = match &facts {
+        Some(f) if !f.references.is_empty() => reference_lines(&f.references),
+        _ => collect_call_sites(root, bare, &def),
+    };
+
+    let mut prompt = format!(
+        "Explain the {} `{symbol}`, defined at {}:{}:\n\n```\n{definition}\n```\n\n",
+        def.kind,
+        def.path.display(),
+        def.line
+    );
+    if let Some(hover) = facts.as_ref().and_then(|f| f.hover.as_deref()) {
+        prompt.push_str(&format!(
+            "The language server reports:\n{}\n\n",
+            ctx.redact(hover)
+        ));
+    }
+    if !sites.is_empty() {
+        prompt.push_str(&format!(
+            "Call sites across the repository:\n{}\n\n",
+            sites.join("\n")
+        ));
+    }
+    prompt.push_str("Explain what it does, how it is used, and anything surprising.");
+    Ok((
+        prompt,
+        format!("symbol `{symbol}`"),
+        def.path.display().to_string(),
+    ))
+}
+
+pub async fn cmd_explain(args: &ExplainArgs, ctx: &AppContext) -> Result<()> {
+    let (user_prompt, scope, path) = if args.staged || args.diff_file.is_some() {
+        // Unlike review, which critiques, this walks the change to teach
+        // what it does and why it was likely made.
+        let (label, diff) = if args.staged {
+            let diff = crate::gitutil::staged_diff()?;
+            if diff.is_empty() {
+                bail!("no staged changes to explain");
+            }
+            ("the staged diff".to_string(), diff)
+        } else {
+            let p = args.diff_file.as_ref().expect("checked above");
+            ctx.ensure_sendable(p)?;
+            (
+                format!("the diff in `{}`", p.display()),
+                read_file_to_string_async(p).await?,
+            )
+        };
+        let diff = ctx.redact(&diff);
+        (
+            format!(
+                "Explain this change hunk by hunk: for each hunk, what it does \
+                 and why it was likely made. Close with a short overview of the \
+                 change as a whole. Describe intent; do not critique.\n\n\
+                 ```diff\n{diff}\n```"
+            ),
+            label.clone(),
+            label,
+        )
+    } else if let Some(symbol) = &args.symbol {
+        build_symbol_prompt(symbol, args.file.as_deref(), ctx)?
+    } else {
+        let file = args.file.as_ref().expect("clap requires a file");
+        ctx.ensure_sendable(file)?;
+        let content = ctx.redact(&read_file_to_string_async(file).await?);
+        let path = file.display().to_string();
+        let (snippet, scope) = match &args.lines {
+            Some(spec) => {
+                let (start, end) = parse_line_range(spec)?;
+                let lines: Vec<&str> = content.lines().collect();
+                if start > lines.len() {
+                    bail!("--lines start {start} is past the end of {path}");
+                }
+                let end = end.min(lines.len());
+                (
+                    lines[start - 1..end].join("\n"),
+                    format!("lines {start}-{end} of `{path}`"),
+                )
+            }
+            None => (content, format!("`{path}`")),
+        };
+        (
+            format!(
+                "Explain {scope}: what it does, how, and anything surprising.\n\n```\n{snippet}\n```"
+            ),
+            scope,
+            path,
+        )
+    };
+
+    let messages = vec![
+        ChatMessage::system(
+            "You explain code clearly to an experienced developer who is new \
+             to this codebase.",
+        ),
+        ChatMessage::user(user_prompt),
+    ];
+    let resp = ctx.complete(messages).await?;
+
+    if let Some(name) = &args.session {
+        crate::session::record_artifact(
+            name,
+            &format!("Explain {scope}."),
+            &resp.content,
+            Some(resp.model.clone()),
+            ctx.config.session_max_record_bytes,
+        )?;
+        ctx.render.status(&format!("recorded in session '{name}'"));
+    }
+
+    ctx.render.emit(
+        &ExplainOutput {
+            file: path,
+            explanation: resp.content.clone(),
+            model: resp.model,
+        },
+        || ctx.render.markdown(&resp.content),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definition_extraction_balances_braces() {
+        let content =
+            "use x;\n\n/// Doc.\nfn foo() {\n    if a {\n        b();\n    }\n}\n\nfn bar() {}\n";
+        let def = extract_definition(content, 4);
+        assert!(def.starts_with("/// Doc."));
+        assert!(def.ends_with('}'));
+        assert!(!def.contains("bar"));
+    }
+
+    #[test]
+    fn parses_line_ranges() {
+        assert_eq!(parse_line_range("3:10").unwrap(), (3, 10));
+        assert!(parse_line_range("0:5").is_err());
+        assert!(parse_line_range("5").is_err());
+        assert!(parse_line_range("9:2").is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! `sw generate` — create a file from an instruction.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::GenerateArgs;
+use crate::fsutil::write_file_async;
+use crate::llm::ChatMessage;
+
+#[derive(Serialize)]
+struct GenerateOutput {
+    file: String,
+    bytes: usize,
+    model: String,
+}
+
+/// Strip a wrapping markdown code fence, which models add despite
+/// instructions not to.
+pub fn strip_code_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let Some(body_start) = rest.find('\n') else {
+        return trimmed;
+    };
+    let body = &rest[body_start + 1..];
+    match body.rfind("```") {
+        Some(end) => body[..end].trim_end_matches('\n'),
+        None => trimmed,
+    }
+}
+
+/// What the model replies when a self-review round finds nothing to fix.
+const SELF_REVIEW_APPROVED: &str = "APPROVED";
+
+/// Let the model critique and revise its own output against the original
+/// instruction, up to `rounds` times. `check` supplies mechanical
+/// feedback on each candidate (e.g. diff parse errors); the loop stops
+/// early when the model approves its output unchanged.
+pub async fn self_review(
+    ctx: &AppContext,
+    instruction: &str,
+    kind: &str,
+    mut candidate: String,
+    rounds: usize,
+    check: &dyn Fn(&str) -> Option<String>,
+) -> Result<String> {
+    for round in 1..=rounds {
+        let feedback = match check(&candidate) {
+            Some(problem) => format!("A mechanical check reports: {problem}\n\n"),
+            None => String::new(),
+        };
+        let messages = vec![
+            ChatMessage::system(
+                "You critically review your own output. If it fully satisfies \
+                 the instruction and any mechanical feedback, reply with exactly \
+                 APPROVED. Otherwise output only the corrected version — no \
+                 explanation, no markdown fences.",
+            ),
+            ChatMessage::user(format!(
+                "Instruction: {instruction}\n\nYour {kind}:\n\n```\n{candidate}\n```\n\n\
+                 {feedback}Review it against the instruction and reply with \
+                 APPROVED or the corrected {kind}."
+            )),
+        ];
+        let resp = ctx.complete(messages).await?;
+        let reply = strip_code_fence(&resp.content);
+        if reply.trim() == SELF_REVIEW_APPROVED {
+            ctx.render
+                .status(&format!("self-review round {round}/{rounds}: approved"));
+            break;
+        }
+        ctx.render
+            .status(&format!("self-review round {round}/{rounds}: revised"));
+        candidate = reply.to_string();
+    }
+    Ok(candidate)
+}
+
+pub async fn cmd_generate(args: &GenerateArgs, ctx: &AppContext) -> Result<()> {
+    if args.out.exists() && !args.force {
+        bail!(
+            "{} already exists; pass --force to overwrite",
+            args.out.display()
+        );
+    }
+    // Never write lossy UTF-8 over a binary, even with --force.
+    crate::fsutil::ensure_text_file(&args.out)?;
+    if !ctx.config.allow_outside_workspace {
+        crate::fsutil::ensure_within_workspace(&args.out, &ctx.workspace)?;
+    }
+
+    let mut prompt = format!(
+        "Generate the contents of `{}`.\n\nInstruction: {}",
+        args.out.display(),
+        args.instruction
+    );
+    if let Some(hint) = ctx.config.style_hint_for(&args.out) {
+        prompt.push_str(&format!("\n\nStyle guide: {hint}"));
+    }
+    let messages = vec![
+        ChatMessage::system(
+            "You generate complete, working file contents. Output only the file \
+             body — no explanation, no markdown fences.",
+        ),
+        ChatMessage::user(prompt),
+    ];
+    let resp = if ctx.render.streams_events() {
+        let req = ctx.chat_request(messages)?;
+        let provider = ctx.provider()?;
+        let render = ctx.render.clone();
+        let mut on_delta = move |delta: crate::llm::Delta| match delta {
+            crate::llm::Delta::Content(delta) => {
+                render.emit_record(&serde_json::json!({"event": "delta", "content": delta}));
+            }
+            crate::llm::Delta::Thinking(delta) => {
+                render.emit_record(&serde_json::json!({"event": "thinking", "content": delta}));
+            }
+        };
+        let resp = tokio::select! {
+            r = provider.send_stream(&req, &mut on_delta) => r,
+            _ = ctx.cancel.cancelled() => {
+                ctx.render
+                    .emit_record(&serde_json::json!({"event": "error", "message": crate::cancel::INTERRUPTED}));
+                anyhow::bail!(crate::cancel::INTERRUPTED);
+            }
+        };
+        match resp {
+            Ok(resp) => resp,
+            Err(e) => {
+                ctx.render.emit_record(
+                    &serde_json::json!({"event": "error", "message": format!("{e:#}")}),
+                );
+                return Err(e);
+            }
+        }
+    } else {
+        ctx.complete(messages).await?
+    };
+    let mut content = strip_code_fence(&resp.content).to_string();
+    if args.self_review > 0 {
+        content = self_review(
+            ctx,
+            &args.instruction,
+            "file body",
+            content,
+            args.self_review,
+            &|_| None,
+        )
+        .await?;
+    }
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    write_file_async(&args.out, &content).await?;
+    crate::provenance::track(
+        ctx.config.provenance,
+        &args.out,
+        "generate",
+        Some(&resp.model),
+        &args.instruction,
+    )?;
+
+    ctx.render.status(&format!(
+        "wrote {} ({} bytes)",
+        args.out.display(),
+        content.len()
+    ));
+    if ctx.render.streams_events() {
+        ctx.render.emit_record(&serde_json::json!({
+            "event": "done",
+            "model": resp.model.as_str(),
+            "file": args.out.display().to_string(),
+            "bytes": content.len(),
+        }));
+    } else {
+        ctx.render.emit(
+            &GenerateOutput {
+                file: args.out.display().to_string(),
+                bytes: content.len(),
+                model: resp.model,
+            },
+            String::new,
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_fences() {
+        assert_eq!(
+            strip_code_fence("```rust\nfn main() {}\n```"),
+            "fn main() {}"
+        );
+        assert_eq!(strip_code_fence("plain text"), "plain text");
+        assert_eq!(strip_code_fence("```\nx\n```\n"), "x");
+    }
+}
@@ -0,0 +1,326 @@
+//! `sw grep` — ripgrep frontend with structured output and a pure-Rust
+//! fallback.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::cli::GrepArgs;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GrepMatch {
+    pub path: String,
+    pub line_number: u64,
+    /// 1-based byte offset of the first match within the line.
+    pub column: u64,
+    pub line: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before_context: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after_context: Vec<String>,
+}
+
+/// rg --json emits one event per line: begin/match/context/end.
+#[derive(Deserialize)]
+struct RgEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    data: serde_json::Value,
+}
+
+fn event_line(data: &serde_json::Value) -> String {
+    data["lines"]["text"]
+        .as_str()
+        .unwrap_or("")
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+/// Parse rg's JSON event stream, folding context events into the
+/// surrounding match records.
+fn parse_rg_json(output: &str, context: usize) -> Vec<GrepMatch> {
+    let mut matches: Vec<GrepMatch> = Vec::new();
+    let mut before_buf: Vec<String> = Vec::new();
+    let mut after_remaining = 0usize;
+
+    for line in output.lines() {
+        let Ok(event) = serde_json::from_str::<RgEvent>(line) else {
+            continue;
+        };
+        match event.kind.as_str() {
+            "begin" => {
+                before_buf.clear();
+                after_remaining = 0;
+            }
+            "context" => {
+                let text = event_line(&event.data);
+                if after_remaining > 0 {
+                    if let Some(last) = matches.last_mut() {
+                        last.after_context.push(text);
+                    }
+                    after_remaining -= 1;
+                } else {
+                    before_buf.push(text);
+                    if before_buf.len() > context {
+                        before_buf.remove(0);
+                    }
+                }
+            }
+            "match" => {
+                let path = event.data["path"]["text"]
+                    .as_str()
+                    .unwrap_or("")
+                    .to_string();
+                let line_number = event.data["line_number"].as_u64().unwrap_or(0);
+                let column = event.data["submatches"][0]["start"]
+                    .as_u64()
+                    .map_or(1, |s| s + 1);
+                matches.push(GrepMatch {
+                    path,
+                    line_number,
+                    column,
+                    line: event_line(&event.data),
+                    before_context: std::mem::take(&mut before_buf),
+                    after_context: Vec::new(),
+                });
+                after_remaining = context;
+            }
+            _ => {}
+        }
+    }
+    matches
+}
+
+fn build_glob_set(globs: &[String]) -> Result<Option<globset::GlobSet>> {
+    if globs.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    for g in globs {
+        builder.add(globset::Glob::new(g).with_context(|| format!("invalid glob '{g}'"))?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+/// Pure-Rust fallback used when ripgrep is unavailable or `--no-rg` is set.
+/// Produces the same match shape as the rg path, honouring .gitignore.
+fn builtin_grep(args: &GrepArgs, search_path: &Path) -> Result<Vec<GrepMatch>> {
+    use grep::regex::RegexMatcherBuilder;
+    use grep::searcher::sinks::UTF8;
+    use grep::searcher::SearcherBuilder;
+
+    let matcher = RegexMatcherBuilder::new()
+        .case_insensitive(args.ignore_case)
+        .build(&args.pattern)
+        .context("invalid search pattern")?;
+    let mut searcher = SearcherBuilder::new().line_number(true).build();
+    let glob_set = build_glob_set(&args.globs)?;
+    // A plain regex over the same pattern recovers column offsets, which
+    // the line-oriented sink does not report.
+    let column_re = regex::RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.ignore_case)
+        .build()
+        .context("invalid search pattern")?;
+    let context = args.context.unwrap_or(0);
+
+    let mut matches = Vec::new();
+    for entry in ignore::WalkBuilder::new(search_path).build() {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+        if !entry.file_type().is_some_and(|t| t.is_file()) {
+            continue;
+        }
+        if let Some(set) = &glob_set {
+            let rel = entry
+                .path()
+                .strip_prefix(search_path)
+                .unwrap_or(entry.path());
+            if !set.is_match(rel) && !set.is_match(entry.path()) {
+                continue;
+            }
+        }
+        let path = crate::platform::to_portable(entry.path());
+        let mut file_hits: Vec<u64> = Vec::new();
+        let result = searcher.search_path(
+            &matcher,
+            entry.path(),
+            UTF8(|line_number, _line| {
+                file_hits.push(line_number);
+                Ok(args.max_count.is_none_or(|m| (file_hits.len() as u64) < m))
+            }),
+        );
+        // Binary or unreadable files are skipped, matching rg's behavior.
+        if result.is_err() || file_hits.is_empty() {
+            continue;
+        }
+        if let Some(m) = args.max_count {
+            file_hits.truncate(m as usize);
+        }
+        let content = match std::fs::read_to_string(entry.path()) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        for line_number in file_hits {
+            let idx = (line_number - 1) as usize;
+            let line = lines.get(idx).copied().unwrap_or("");
+            let column = column_re.find(line).map_or(1, |m| m.start() as u64 + 1);
+            let before_start = idx.saturating_sub(context);
+            matches.push(GrepMatch {
+                path: path.clone(),
+                line_number,
+                column,
+                line: line.to_string(),
+                before_context: lines[before_start..idx]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+                after_context: lines
+                    [(idx + 1).min(lines.len())..(idx + 1 + context).min(lines.len())]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            });
+        }
+    }
+    Ok(matches)
+}
+
+fn rg_grep(args: &GrepArgs, search_path: &Path) -> Result<Vec<GrepMatch>> {
+    let mut cmd = Command::new("rg");
+    cmd.arg("--json");
+    if args.ignore_case {
+        cmd.arg("-i");
+    }
+    if let Some(n) = args.context {
+        cmd.arg("-C").arg(n.to_string());
+    }
+    if let Some(m) = args.max_count {
+        cmd.arg("-m").arg(m.to_string());
+    }
+    for g in &args.globs {
+        cmd.arg("--glob").arg(g);
+    }
+    cmd.arg(&args.pattern).arg(search_path);
+
+    let out = cmd
+        .output()
+        .context("failed to run rg — install ripgrep or add it to PATH")?;
+    // rg exits 1 on "no matches", which is not an error for us.
+    if !out.status.success() && out.status.code() != Some(1) {
+        bail!("rg failed: {}", String::from_utf8_lossy(&out.stderr).trim());
+    }
+
+    Ok(parse_rg_json(
+        &String::from_utf8_lossy(&out.stdout),
+        args.context.unwrap_or(0),
+    ))
+}
+
+#[derive(Serialize)]
+struct CountEntry {
+    path: String,
+    count: usize,
+}
+
+pub async fn cmd_grep(args: &GrepArgs, ctx: &AppContext) -> Result<()> {
+    let search_path = args.path.clone().unwrap_or_else(|| ctx.workspace.clone());
+
+    let use_rg = !args.no_rg && crate::platform::has_command("rg");
+    let matches = if use_rg {
+        rg_grep(args, &search_path)?
+    } else {
+        if ctx.verbose {
+            ctx.render.status("using built-in searcher");
+        }
+        builtin_grep(args, &search_path)?
+    };
+
+    if args.files_with_matches {
+        let mut paths: Vec<String> = matches.iter().map(|m| m.path.clone()).collect();
+        paths.dedup();
+        ctx.render.emit(&paths, || paths.join("\n"));
+        return Ok(());
+    }
+    if args.count {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for m in &matches {
+            *counts.entry(m.path.clone()).or_default() += 1;
+        }
+        let entries: Vec<CountEntry> = counts
+            .into_iter()
+            .map(|(path, count)| CountEntry { path, count })
+            .collect();
+        ctx.render.emit(&entries, || {
+            entries
+                .iter()
+                .map(|e| format!("{}:{}", e.path, e.count))
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        return Ok(());
+    }
+
+    if ctx.render.streams_records() {
+        for m in &matches {
+            ctx.render.emit_record(m);
+        }
+    } else if args.json && ctx.render.is_text() {
+        // --json forces structured output even in the default text format.
+        match serde_json::to_string_pretty(&matches) {
+            Ok(s) => println!("{s}"),
+            Err(e) => ctx
+                .render
+                .warn(&format!("failed to serialize matches: {e}")),
+        }
+    } else {
+        ctx.render.emit(&matches, || render_text(&matches));
+    }
+    ctx.render.status(&format!("{} match(es)", matches.len()));
+    Ok(())
+}
+
+fn render_text(matches: &[GrepMatch]) -> String {
+    matches
+        .iter()
+        .map(|m| format!("{}:{}:{}", m.path, m.line_number, m.line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rg_match_events() {
+        let raw = r#"{"type":"begin","data":{"path":{"text":"src/a.rs"}}}
+{"type":"match","data":{"path":{"text":"src/a.rs"},"lines":{"text":"fn main() {}\n"},"line_number":3,"absolute_offset":10,"submatches":[{"match":{"text":"main"},"start":3,"end":7}]}}
+{"type":"end","data":{}}"#;
+        let matches = parse_rg_json(raw, 0);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "src/a.rs");
+        assert_eq!(matches[0].line_number, 3);
+        assert_eq!(matches[0].column, 4);
+        assert_eq!(matches[0].line, "fn main() {}");
+    }
+
+    #[test]
+    fn folds_context_events_into_matches() {
+        let raw = r#"{"type":"begin","data":{"path":{"text":"a.txt"}}}
+{"type":"context","data":{"path":{"text":"a.txt"},"lines":{"text":"before\n"},"line_number":1}}
+{"type":"match","data":{"path":{"text":"a.txt"},"lines":{"text":"hit\n"},"line_number":2,"submatches":[{"start":0,"end":3}]}}
+{"type":"context","data":{"path":{"text":"a.txt"},"lines":{"text":"after\n"},"line_number":3}}
+{"type":"end","data":{}}"#;
+        let matches = parse_rg_json(raw, 1);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].before_context, vec!["before"]);
+        assert_eq!(matches[0].after_context, vec!["after"]);
+    }
+}
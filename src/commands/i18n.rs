@@ -0,0 +1,301 @@
+//! `sw i18n` — extract user-facing strings into a catalog and
+//! machine-translate it, with the result emitted as a reviewable diff.
+//!
+//! Extraction uses the same line-level heuristics as the rest of the
+//! analysis layer rather than a real parser: a quoted literal counts as
+//! user-facing when it reads like prose, not like an identifier or path.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::cli::{I18nExtractArgs, I18nTranslateArgs};
+use crate::llm::ChatMessage;
+
+/// One extracted string with the location it was first seen at.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub file: String,
+    pub line: usize,
+}
+
+/// Whether a quoted literal looks like text shown to a user: it needs a
+/// space and letters, and must not look like a path, URL, format-only
+/// string, or identifier.
+pub fn is_user_facing(s: &str) -> bool {
+    let trimmed = s.trim();
+    if trimmed.len() < 4 || !trimmed.contains(' ') {
+        return false;
+    }
+    if !trimmed.chars().any(|c| c.is_alphabetic()) {
+        return false;
+    }
+    if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        return false;
+    }
+    // Mostly symbols or placeholders — a format skeleton, not a message.
+    let alpha = trimmed.chars().filter(|c| c.is_alphabetic()).count();
+    alpha * 2 >= trimmed.len()
+}
+
+/// Pull candidate strings out of one file's content.
+pub fn extract_strings(content: &str) -> Vec<(usize, String)> {
+    let literal = regex::Regex::new(r#""((?:[^"\\]|\\.)+)""#).expect("static regex");
+    let mut out = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        // Comments are not user-facing even when they read like prose.
+        if trimmed.starts_with("//") || trimmed.starts_with('#') || trimmed.starts_with('*') {
+            continue;
+        }
+        for cap in literal.captures_iter(line) {
+            let text = cap[1].replace("\\\"", "\"").replace("\\n", "\n");
+            if is_user_facing(&text) {
+                out.push((idx + 1, text));
+            }
+        }
+    }
+    out
+}
+
+/// Render the catalog as gettext PO, one entry per msgid.
+fn to_po(entries: &[CatalogEntry]) -> String {
+    let mut s = String::new();
+    for entry in entries {
+        s.push_str(&format!(
+            "#: {}:{}\nmsgid \"{}\"\nmsgstr \"\"\n\n",
+            entry.file,
+            entry.line,
+            entry.id.replace('"', "\\\"").replace('\n', "\\n")
+        ));
+    }
+    s
+}
+
+/// The msgids of a PO file; enough to drive translation.
+fn po_msgids(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|l| l.strip_prefix("msgid \""))
+        .filter_map(|l| l.strip_suffix('"'))
+        .filter(|l| !l.is_empty())
+        .map(|l| l.replace("\\\"", "\"").replace("\\n", "\n"))
+        .collect()
+}
+
+#[derive(Serialize)]
+struct ExtractOutput {
+    strings: usize,
+    files: usize,
+    catalog: String,
+}
+
+pub async fn cmd_i18n_extract(args: &I18nExtractArgs, ctx: &AppContext) -> Result<()> {
+    let root = if args.path.is_absolute() {
+        args.path.clone()
+    } else {
+        ctx.workspace.join(&args.path)
+    };
+    let mut entries: Vec<CatalogEntry> = Vec::new();
+    let mut seen = std::collections::BTreeSet::new();
+    let mut files = 0;
+    for path in crate::commands::files::walk_files(&root, &[])? {
+        if crate::analysis::language_for_path(&path) == "Other" {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let rel = path.strip_prefix(&ctx.workspace).unwrap_or(&path);
+        let found = extract_strings(&content);
+        if !found.is_empty() {
+            files += 1;
+        }
+        for (line, id) in found {
+            // First location wins; duplicates collapse into one entry.
+            if seen.insert(id.clone()) {
+                entries.push(CatalogEntry {
+                    id,
+                    file: rel.display().to_string(),
+                    line,
+                });
+            }
+        }
+    }
+    if entries.is_empty() {
+        bail!("no user-facing strings found under {}", root.display());
+    }
+
+    let (default_name, body) = match args.format.as_str() {
+        "json" => (
+            "messages.json",
+            format!("{}\n", serde_json::to_string_pretty(&entries)?),
+        ),
+        "po" => ("messages.po", to_po(&entries)),
+        other => bail!("unknown format '{other}' (json or po)"),
+    };
+    let catalog = args
+        .out
+        .clone()
+        .unwrap_or_else(|| ctx.workspace.join("i18n").join(default_name));
+    if let Some(parent) = catalog.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&catalog, &body)
+        .with_context(|| format!("failed to write {}", catalog.display()))?;
+    ctx.render.status(&format!(
+        "{} string(s) from {files} file(s) written to {}",
+        entries.len(),
+        catalog.display()
+    ));
+    let out = ExtractOutput {
+        strings: entries.len(),
+        files,
+        catalog: catalog.display().to_string(),
+    };
+    ctx.render.emit(&out, || out.catalog.clone());
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct TranslateOutput {
+    lang: String,
+    entries: usize,
+    file: String,
+    diff: String,
+}
+
+pub async fn cmd_i18n_translate(args: &I18nTranslateArgs, ctx: &AppContext) -> Result<()> {
+    let raw = std::fs::read_to_string(&args.catalog)
+        .with_context(|| format!("failed to read catalog {}", args.catalog.display()))?;
+    let is_po = args.catalog.extension().is_some_and(|e| e == "po");
+    let ids: Vec<String> = if is_po {
+        po_msgids(&raw)
+    } else {
+        let entries: Vec<CatalogEntry> =
+            serde_json::from_str(&raw).context("catalog is not a valid messages.json")?;
+        entries.into_iter().map(|e| e.id).collect()
+    };
+    if ids.is_empty() {
+        bail!("catalog has no entries; run `sw i18n extract` first");
+    }
+
+    ctx.render.status(&format!(
+        "translating {} string(s) to {}",
+        ids.len(),
+        args.lang
+    ));
+    let listing: String = ids.iter().map(|id| format!("- {id}\n")).collect();
+    let messages = vec![
+        ChatMessage::system(
+            "You translate user-facing software strings. Reply with one JSON \
+             object mapping every source string to its translation. Keep \
+             placeholders ({name}, {}, %s, %d) exactly as they are; match \
+             the source's tone and punctuation.",
+        ),
+        ChatMessage::user(format!(
+            "Translate these strings to {} (language code):\n{listing}",
+            args.lang
+        )),
+    ];
+    let resp = ctx.complete(messages).await?;
+    let body = crate::commands::generate::strip_code_fence(&resp.content);
+    let translations: BTreeMap<String, String> =
+        serde_json::from_str(body.trim()).context("model did not return a JSON translation map")?;
+    let missing = ids
+        .iter()
+        .filter(|id| !translations.contains_key(*id))
+        .count();
+    if missing > 0 {
+        ctx.render
+            .warn(&format!("{missing} string(s) came back untranslated"));
+    }
+
+    // The translation lands as a diff against the per-language file, so
+    // nothing is written until a human has reviewed it.
+    let target = target_path(&args.catalog, &args.lang, is_po);
+    let new = if is_po {
+        let mut s = String::new();
+        for id in &ids {
+            let translated = translations.get(id).cloned().unwrap_or_default();
+            s.push_str(&format!(
+                "msgid \"{}\"\nmsgstr \"{}\"\n\n",
+                id.replace('"', "\\\"").replace('\n', "\\n"),
+                translated.replace('"', "\\\"").replace('\n', "\\n")
+            ));
+        }
+        s
+    } else {
+        let map: BTreeMap<&String, String> = ids
+            .iter()
+            .map(|id| (id, translations.get(id).cloned().unwrap_or_default()))
+            .collect();
+        format!("{}\n", serde_json::to_string_pretty(&map)?)
+    };
+    let current = std::fs::read_to_string(&target).unwrap_or_default();
+    let rel = target
+        .strip_prefix(&ctx.workspace)
+        .unwrap_or(&target)
+        .display()
+        .to_string();
+    let diff = crate::commands::template::unified_diff(&rel, &current, &new);
+    ctx.render
+        .status("review the diff and apply it with `sw diff apply`");
+    let out = TranslateOutput {
+        lang: args.lang.clone(),
+        entries: ids.len(),
+        file: rel,
+        diff,
+    };
+    ctx.render.emit(&out, || out.diff.trim_end().to_string());
+    Ok(())
+}
+
+/// `messages.json` + `de` → `messages.de.json`; `messages.po` → `de.po`.
+fn target_path(catalog: &Path, lang: &str, is_po: bool) -> PathBuf {
+    let dir = catalog.parent().unwrap_or(Path::new("."));
+    if is_po {
+        return dir.join(format!("{lang}.po"));
+    }
+    let stem = catalog
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "messages".to_string());
+    dir.join(format!("{stem}.{lang}.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_prose_literals_are_extracted() {
+        let src = r#"
+            // "a comment, not a message"
+            let path = "src/main.rs";
+            let url = "https://example.com/a b";
+            let fmt = "{} -> {}";
+            println!("failed to open the file");
+            eprintln!("see --help for usage");
+        "#;
+        let found = extract_strings(src);
+        let ids: Vec<&str> = found.iter().map(|(_, s)| s.as_str()).collect();
+        assert_eq!(ids, ["failed to open the file", "see --help for usage"]);
+    }
+
+    #[test]
+    fn po_entries_round_trip() {
+        let entries = vec![CatalogEntry {
+            id: "say \"hi\"".to_string(),
+            file: "src/a.rs".to_string(),
+            line: 3,
+        }];
+        let po = to_po(&entries);
+        assert!(po.contains("#: src/a.rs:3"));
+        assert_eq!(po_msgids(&po), vec!["say \"hi\"".to_string()]);
+    }
+}
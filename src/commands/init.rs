@@ -0,0 +1,226 @@
+//! `sw init` — interactive first-run configuration.
+
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::app::AppContext;
+use crate::config::{Config, Profile, DEFAULT_PROFILE};
+use crate::llm::{ModelInfo, ProviderRegistry};
+
+/// Menu entries shown when the provider lists more models than fit.
+const MENU_LIMIT: usize = 20;
+
+/// A local OpenAI-compatible server worth suggesting during setup.
+struct LocalServer {
+    /// Name offered at the provider prompt.
+    choice: &'static str,
+    label: &'static str,
+    api_base: &'static str,
+}
+
+const LOCAL_SERVERS: &[LocalServer] = &[
+    LocalServer {
+        choice: "ollama",
+        label: "Ollama",
+        api_base: "http://localhost:11434/v1",
+    },
+    LocalServer {
+        choice: "lmstudio",
+        label: "LM Studio",
+        api_base: "http://localhost:1234/v1",
+    },
+];
+
+fn prompt(question: &str, default: &str) -> Result<String> {
+    eprint!("{question} [{default}]: ");
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    let answer = line.trim();
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer.to_string()
+    })
+}
+
+/// Probe the known local servers; a fast `/models` response means one is
+/// running and worth suggesting as the default provider.
+async fn detect_local_servers() -> Vec<&'static LocalServer> {
+    let Ok(client) = reqwest::Client::builder()
+        .timeout(Duration::from_millis(600))
+        .user_agent(concat!("sw-assist/", env!("CARGO_PKG_VERSION")))
+        .build()
+    else {
+        return Vec::new();
+    };
+    let mut running = Vec::new();
+    for server in LOCAL_SERVERS {
+        let url = format!("{}/models", server.api_base);
+        if matches!(client.get(&url).send().await, Ok(r) if r.status().is_success()) {
+            running.push(server);
+        }
+    }
+    running
+}
+
+/// Validate the profile by listing models through it, timed so the user
+/// sees both that the key works and roughly how far away the API is.
+async fn validate_profile(config: &Config, profile: &Profile, ctx: &AppContext) -> Vec<ModelInfo> {
+    let provider = match ProviderRegistry::create(config, profile) {
+        Ok(p) => p,
+        Err(e) => {
+            ctx.render
+                .warn(&format!("cannot validate credentials: {e:#}"));
+            return Vec::new();
+        }
+    };
+    let started = std::time::Instant::now();
+    match provider.list_models().await {
+        Ok(models) => {
+            ctx.render.status(&format!(
+                "credentials ok: {} models in {} ms",
+                models.len(),
+                started.elapsed().as_millis()
+            ));
+            models
+        }
+        Err(e) => {
+            ctx.render
+                .warn(&format!("credential check failed ({e:#}); saving anyway"));
+            Vec::new()
+        }
+    }
+}
+
+/// Pick the default model from a numbered menu of what the provider
+/// actually serves; free-form input still wins for unlisted models.
+fn pick_model(models: &[ModelInfo], default: &str) -> Result<String> {
+    if models.is_empty() {
+        return prompt("default model", default);
+    }
+    let shown = models.len().min(MENU_LIMIT);
+    for (i, model) in models.iter().take(shown).enumerate() {
+        eprintln!("  {:>2}. {}", i + 1, model.id);
+    }
+    if models.len() > shown {
+        eprintln!("  … and {} more", models.len() - shown);
+    }
+    let answer = prompt(&format!("default model (1-{shown} or a name)"), default)?;
+    Ok(match answer.parse::<usize>() {
+        Ok(i) if (1..=shown).contains(&i) => models[i - 1].id.clone(),
+        _ => answer,
+    })
+}
+
+/// Optionally pin the chosen provider and model in a `.sw.toml` at the
+/// workspace root, which is merged over the user config for everyone
+/// working in this repository.
+fn offer_project_config(profile: &Profile, ctx: &AppContext) -> Result<()> {
+    let path = ctx.workspace.join(".sw.toml");
+    if path.exists() {
+        return Ok(());
+    }
+    let answer = prompt("pin these settings in a project .sw.toml?", "n")?;
+    if !matches!(answer.as_str(), "y" | "Y" | "yes") {
+        return Ok(());
+    }
+    let mut raw = format!(
+        "[profiles.{DEFAULT_PROFILE}]\nprovider = {}\nmodel = {}\n",
+        toml::Value::String(profile.provider.clone()),
+        toml::Value::String(profile.model.clone()),
+    );
+    if let Some(base) = &profile.api_base {
+        raw.push_str(&format!(
+            "api_base = {}\n",
+            toml::Value::String(base.clone())
+        ));
+    }
+    if let Some(var) = &profile.api_key_env {
+        raw.push_str(&format!(
+            "api_key_env = {}\n",
+            toml::Value::String(var.clone())
+        ));
+    }
+    std::fs::write(&path, raw).with_context(|| format!("failed to write {}", path.display()))?;
+    ctx.render
+        .status(&format!("project config written to {}", path.display()));
+    Ok(())
+}
+
+pub async fn cmd_init(ctx: &AppContext) -> Result<()> {
+    let mut config = Config::load().unwrap_or_default();
+
+    let detected = detect_local_servers().await;
+    for server in &detected {
+        ctx.render.status(&format!(
+            "detected {} running at {}",
+            server.label, server.api_base
+        ));
+    }
+    let provider_default = detected.first().map_or("openai", |s| s.choice);
+    let provider = prompt(
+        "provider (openai/anthropic/ollama/lmstudio)",
+        provider_default,
+    )?;
+
+    // LM Studio is an OpenAI-compatible local endpoint, not a provider of
+    // its own; it becomes the openai adapter pointed at localhost.
+    let local = LOCAL_SERVERS.iter().find(|s| s.choice == provider);
+    let (provider, api_base) = match local {
+        Some(s) if s.choice == "lmstudio" => ("openai".to_string(), Some(s.api_base.to_string())),
+        _ => (provider, None),
+    };
+
+    let key_env_default = match provider.as_str() {
+        "anthropic" => "ANTHROPIC_API_KEY",
+        "ollama" => "",
+        _ if api_base.is_some() => "",
+        _ => "OPENAI_API_KEY",
+    };
+    let api_key_env = if key_env_default.is_empty() {
+        String::new()
+    } else {
+        prompt("API key environment variable", key_env_default)?
+    };
+
+    let mut profile = Profile {
+        provider: provider.clone(),
+        api_base,
+        api_key: None,
+        api_key_env: if api_key_env.is_empty() {
+            None
+        } else {
+            Some(api_key_env)
+        },
+        ..Profile::default()
+    };
+    let models = validate_profile(&config, &profile, ctx).await;
+
+    let default_model = match provider.as_str() {
+        "anthropic" => "claude-3-5-sonnet-latest",
+        "ollama" => "llama3.1",
+        _ => "gpt-4o-mini",
+    };
+    let default_model = models
+        .iter()
+        .any(|m| m.id == default_model)
+        .then_some(default_model)
+        .or_else(|| models.first().map(|m| m.id.as_str()))
+        .unwrap_or(default_model);
+    profile.model = pick_model(&models, default_model)?;
+
+    config
+        .profiles
+        .insert(DEFAULT_PROFILE.to_string(), profile.clone());
+    config.save()?;
+    ctx.render.status(&format!(
+        "config written to {}",
+        Config::config_path()?.display()
+    ));
+
+    offer_project_config(&profile, ctx)?;
+    Ok(())
+}
@@ -0,0 +1,288 @@
+//! `sw issue` — start working from a tracker issue: fetch it, draft a
+//! plan, branch, and seed a session carrying both.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::IssueStartArgs;
+use crate::llm::{ChatMessage, Role};
+use crate::session::{SessionRecord, SessionStore};
+
+/// Which tracker API an issue reference points at.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Tracker {
+    Github,
+    Gitlab,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct IssueRef {
+    pub tracker: Tracker,
+    /// `owner/repo` (GitHub) or the full project path (GitLab).
+    pub project: String,
+    pub number: u64,
+}
+
+/// Parse an issue URL, or a bare number against the `origin` remote URL.
+pub fn parse_issue_ref(input: &str, remote_url: Option<&str>) -> Result<IssueRef> {
+    if let Ok(number) = input.parse::<u64>() {
+        let remote =
+            remote_url.context("bare issue numbers need a git remote to infer the repo")?;
+        let (tracker, project) = project_from_remote(remote)?;
+        return Ok(IssueRef {
+            tracker,
+            project,
+            number,
+        });
+    }
+    let trimmed = input
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    if let Some(rest) = trimmed.strip_prefix("github.com/") {
+        let (project, number) = rest
+            .split_once("/issues/")
+            .context("expected .../issues/<number> in the URL")?;
+        return Ok(IssueRef {
+            tracker: Tracker::Github,
+            project: project.to_string(),
+            number: number.trim_matches('/').parse()?,
+        });
+    }
+    if let Some(rest) = trimmed.strip_prefix("gitlab.com/") {
+        let (project, number) = rest
+            .split_once("/-/issues/")
+            .context("expected .../-/issues/<number> in the URL")?;
+        return Ok(IssueRef {
+            tracker: Tracker::Gitlab,
+            project: project.to_string(),
+            number: number.trim_matches('/').parse()?,
+        });
+    }
+    bail!("unrecognized issue reference '{input}' (github.com and gitlab.com URLs, or a number)")
+}
+
+/// The tracker and project path behind a git remote URL, covering the
+/// https and ssh forms.
+fn project_from_remote(remote: &str) -> Result<(Tracker, String)> {
+    let normalized = remote
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_start_matches("git@")
+        .replacen(':', "/", 1);
+    let normalized = normalized.trim_end_matches(".git");
+    if let Some(project) = normalized.strip_prefix("github.com/") {
+        return Ok((Tracker::Github, project.to_string()));
+    }
+    if let Some(project) = normalized.strip_prefix("gitlab.com/") {
+        return Ok((Tracker::Gitlab, project.to_string()));
+    }
+    bail!("cannot infer a tracker from remote '{remote}'; pass the issue URL instead")
+}
+
+pub struct Issue {
+    pub title: String,
+    pub body: String,
+    pub url: String,
+}
+
+/// Fetch the issue over the tracker's REST API; tokens come from the
+/// conventional environment variables and are optional for public repos.
+async fn fetch_issue(issue: &IssueRef, ctx: &AppContext) -> Result<Issue> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent(concat!("sw-assist/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let rb = match issue.tracker {
+        Tracker::Github => {
+            let url = format!(
+                "https://api.github.com/repos/{}/issues/{}",
+                issue.project, issue.number
+            );
+            let mut rb = client.get(&url);
+            if let Ok(token) = std::env::var("GITHUB_TOKEN") {
+                rb = rb.bearer_auth(token);
+            }
+            rb
+        }
+        Tracker::Gitlab => {
+            let encoded = issue.project.replace('/', "%2F");
+            let url = format!(
+                "https://gitlab.com/api/v4/projects/{encoded}/issues/{}",
+                issue.number
+            );
+            let mut rb = client.get(&url);
+            if let Ok(token) = std::env::var("GITLAB_TOKEN") {
+                rb = rb.header("PRIVATE-TOKEN", token);
+            }
+            rb
+        }
+    };
+    let resp = tokio::select! {
+        r = rb.send() => r.context("issue fetch failed")?,
+        _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+    };
+    let status = resp.status();
+    if !status.is_success() {
+        bail!(
+            "issue fetch failed ({status}); private repos need GITHUB_TOKEN \
+             or GITLAB_TOKEN set"
+        );
+    }
+    let value: serde_json::Value = resp.json().await.context("invalid tracker response")?;
+    let title = value
+        .get("title")
+        .and_then(|t| t.as_str())
+        .context("issue has no title")?
+        .to_string();
+    let body = value
+        .get("body")
+        .or_else(|| value.get("description"))
+        .and_then(|b| b.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let url = value
+        .get("html_url")
+        .or_else(|| value.get("web_url"))
+        .and_then(|u| u.as_str())
+        .unwrap_or_default()
+        .to_string();
+    Ok(Issue { title, body, url })
+}
+
+/// `issue-123-short-title-slug`, capped so branch names stay readable.
+pub fn branch_name(number: u64, title: &str) -> String {
+    let slug: String = title
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let mut slug = slug.trim_matches('-').to_string();
+    while slug.contains("--") {
+        slug = slug.replace("--", "-");
+    }
+    slug.truncate(40);
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        format!("issue-{number}")
+    } else {
+        format!("issue-{number}-{slug}")
+    }
+}
+
+#[derive(Serialize)]
+struct IssueStartOutput {
+    number: u64,
+    title: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    url: String,
+    branch: String,
+    session: String,
+    plan: String,
+}
+
+pub async fn cmd_issue_start(args: &IssueStartArgs, ctx: &AppContext) -> Result<()> {
+    let remote = crate::gitutil::git(&["remote", "get-url", "origin"]).ok();
+    let issue_ref = parse_issue_ref(&args.issue, remote.as_deref())?;
+    ctx.render.status(&format!(
+        "fetching {} issue #{}",
+        issue_ref.project, issue_ref.number
+    ));
+    let issue = fetch_issue(&issue_ref, ctx).await?;
+
+    let mut prompt = format!(
+        "Issue #{}: {}\n\n{}\n\nDraft a short implementation plan: numbered \
+         steps, each naming the files or areas it touches, plus how to verify.",
+        issue_ref.number, issue.title, issue.body
+    );
+    if let Some(outline) = crate::repomap::prompt_block(&ctx.workspace, 2000) {
+        prompt = format!("Repository outline:\n\n{outline}\n\n{prompt}");
+    }
+    let messages = vec![
+        ChatMessage::system("You plan software changes. Be concrete and brief."),
+        ChatMessage::user(prompt),
+    ];
+    let plan = ctx.complete(messages).await?;
+
+    let branch = args
+        .branch
+        .clone()
+        .unwrap_or_else(|| branch_name(issue_ref.number, &issue.title));
+    if crate::gitutil::in_work_tree() {
+        crate::gitutil::git(&["checkout", "-b", &branch])
+            .with_context(|| format!("failed to create branch {branch}"))?;
+        ctx.render.status(&format!("on new branch {branch}"));
+    } else {
+        ctx.render
+            .warn("not in a git work tree; skipping branch creation");
+    }
+
+    // Seed a session carrying the issue and the plan, so follow-up work
+    // (`sw chat --session`, `sw agent`) starts with full context.
+    let store = SessionStore::open()?;
+    let session = branch.clone();
+    store.append_capped(
+        &session,
+        &SessionRecord::now(
+            Role::User,
+            format!(
+                "Issue #{}: {}\n\n{}",
+                issue_ref.number, issue.title, issue.body
+            ),
+            None,
+        ),
+        ctx.config.session_max_record_bytes,
+    )?;
+    store.append_capped(
+        &session,
+        &SessionRecord::now(Role::Assistant, &plan.content, Some(plan.model.clone())),
+        ctx.config.session_max_record_bytes,
+    )?;
+    ctx.render
+        .status(&format!("continue with `sw chat --session {session}`"));
+
+    let output = IssueStartOutput {
+        number: issue_ref.number,
+        title: issue.title,
+        url: issue.url,
+        branch,
+        session,
+        plan: plan.content,
+    };
+    ctx.render
+        .emit(&output, || ctx.render.markdown(&output.plan));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_urls_and_bare_numbers() {
+        let from_url = parse_issue_ref("https://github.com/acme/widget/issues/42", None).unwrap();
+        assert_eq!(from_url.tracker, Tracker::Github);
+        assert_eq!(from_url.project, "acme/widget");
+        assert_eq!(from_url.number, 42);
+
+        let gitlab = parse_issue_ref("https://gitlab.com/group/sub/proj/-/issues/7", None).unwrap();
+        assert_eq!(gitlab.tracker, Tracker::Gitlab);
+        assert_eq!(gitlab.project, "group/sub/proj");
+
+        let bare = parse_issue_ref("42", Some("git@github.com:acme/widget.git")).unwrap();
+        assert_eq!(bare.project, "acme/widget");
+        assert!(parse_issue_ref("42", None).is_err());
+    }
+
+    #[test]
+    fn branch_names_are_slugged_and_capped() {
+        assert_eq!(
+            branch_name(12, "Fix the  thing! (again)"),
+            "issue-12-fix-the-thing-again"
+        );
+        assert_eq!(branch_name(3, "!!!"), "issue-3");
+        assert!(branch_name(1, &"long word ".repeat(20)).len() <= "issue-1-".len() + 40);
+    }
+}
@@ -0,0 +1,18 @@
+//! `sw map` — ranked repository outline (paths, key symbols, sizes).
+
+use anyhow::Result;
+
+use crate::app::AppContext;
+use crate::cli::MapArgs;
+
+pub async fn cmd_map(args: &MapArgs, ctx: &AppContext) -> Result<()> {
+    let root = args.path.clone().unwrap_or_else(|| ctx.workspace.clone());
+    let map = crate::repomap::load_or_build(&root, args.refresh)?;
+    ctx.render.status(&format!(
+        "{} file(s) mapped under {}",
+        map.entries.len(),
+        map.root
+    ));
+    ctx.render.emit(&map, || map.render(args.budget));
+    Ok(())
+}
@@ -0,0 +1,318 @@
+//! `sw migrate` — a migration playbook on top of the batch machinery:
+//! pre-scan for affected files, per-file rewrite with validation, a
+//! progress manifest for resumable runs, and a closing report of the
+//! manual work left.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::checkpoint::create_checkpoint;
+use crate::cli::MigrateArgs;
+use crate::commands::generate::strip_code_fence;
+use crate::fsutil::{backup_file_async, read_file_to_string_async, write_file_async};
+use crate::llm::ChatMessage;
+
+/// Progress manifest at `.sw/migrate.json`; a re-run skips files already
+/// migrated under the same from/to pair whose content has not moved.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MigrateManifest {
+    from: String,
+    to: String,
+    entries: BTreeMap<String, MigrateEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MigrateEntry {
+    status: String,
+    output_hash: String,
+}
+
+fn manifest_path(workspace: &Path) -> PathBuf {
+    workspace.join(".sw").join("migrate.json")
+}
+
+fn load_manifest(workspace: &Path, from: &str, to: &str) -> MigrateManifest {
+    std::fs::read_to_string(manifest_path(workspace))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<MigrateManifest>(&raw).ok())
+        // A different migration starts from a clean slate.
+        .filter(|m| m.from == from && m.to == to)
+        .unwrap_or_else(|| MigrateManifest {
+            from: from.to_string(),
+            to: to.to_string(),
+            entries: BTreeMap::new(),
+        })
+}
+
+fn save_manifest(workspace: &Path, manifest: &MigrateManifest) -> Result<()> {
+    let path = manifest_path(workspace);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(manifest)?)
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn content_hash(content: &str) -> String {
+    use sha2::Digest;
+    format!("{:x}", sha2::Sha256::digest(content.as_bytes()))
+}
+
+/// Regexes that mark a file as still using the old pattern, drafted by
+/// the model once per run.
+async fn indicator_patterns(from: &str, ctx: &AppContext) -> Result<Vec<regex::Regex>> {
+    let messages = vec![
+        ChatMessage::system(
+            "You write search patterns. Output up to 8 regular expressions, \
+             one per line, nothing else.",
+        ),
+        ChatMessage::user(format!(
+            "Regular expressions that match source lines still using: {from}"
+        )),
+    ];
+    let resp = ctx.complete(messages).await?;
+    let mut patterns: Vec<regex::Regex> = resp
+        .content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('`'))
+        .filter_map(|l| regex::Regex::new(l).ok())
+        .collect();
+    // A model that produced nothing compilable still leaves the literal
+    // words of the description as a crude scan.
+    if patterns.is_empty() {
+        patterns.extend(
+            from.split_whitespace()
+                .filter(|w| w.len() > 3)
+                .filter_map(|w| regex::Regex::new(&regex::escape(w)).ok()),
+        );
+    }
+    Ok(patterns)
+}
+
+/// Files under `root` whose content matches any indicator.
+pub fn scan_affected(paths: &[PathBuf], patterns: &[regex::Regex]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .filter(|p| {
+            std::fs::read_to_string(p)
+                .map(|content| patterns.iter().any(|re| re.is_match(&content)))
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect()
+}
+
+#[derive(Serialize)]
+struct MigrateOutput {
+    from: String,
+    to: String,
+    affected: usize,
+    migrated: Vec<String>,
+    skipped: Vec<String>,
+    manual: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<String>,
+    dry_run: bool,
+}
+
+pub async fn cmd_migrate(args: &MigrateArgs, ctx: &AppContext) -> Result<()> {
+    let root = if args.path.is_absolute() {
+        args.path.clone()
+    } else {
+        ctx.workspace.join(&args.path)
+    };
+    let mut candidates = crate::commands::files::walk_files(&root, &[])?;
+    candidates.retain(|p| !crate::fsutil::is_binary(p));
+
+    ctx.render
+        .status(&format!("scanning {} file(s)", candidates.len()));
+    let patterns = indicator_patterns(&args.from, ctx).await?;
+    let affected = scan_affected(&candidates, &patterns);
+    ctx.render.status(&format!(
+        "{} file(s) still use {}",
+        affected.len(),
+        args.from
+    ));
+    if args.dry_run || affected.is_empty() {
+        let listed: Vec<String> = affected.iter().map(|p| p.display().to_string()).collect();
+        let out = MigrateOutput {
+            from: args.from.clone(),
+            to: args.to.clone(),
+            affected: listed.len(),
+            migrated: Vec::new(),
+            skipped: Vec::new(),
+            manual: Vec::new(),
+            report: None,
+            dry_run: args.dry_run,
+        };
+        ctx.render.emit(&out, || listed.join("\n"));
+        return Ok(());
+    }
+    for path in &affected {
+        ctx.ensure_sendable(path)?;
+    }
+
+    // Same safety net as batch transform: checkpoint before any write.
+    let auto = create_checkpoint(
+        &ctx.workspace,
+        Some(format!("auto: before migrate to {}", args.to)),
+        ctx.config.checkpoints_in_repo,
+    )?;
+    ctx.render
+        .status(&format!("auto-checkpoint {} created", auto.id));
+
+    let mut manifest = load_manifest(&ctx.workspace, &args.from, &args.to);
+    let mut migrated = Vec::new();
+    let mut skipped = Vec::new();
+    let mut manual = Vec::new();
+    for path in &affected {
+        let rel = path.strip_prefix(&ctx.workspace).unwrap_or(path);
+        let key = rel.display().to_string();
+        let content = read_file_to_string_async(path).await?;
+        if manifest
+            .entries
+            .get(&key)
+            .is_some_and(|e| e.status == "migrated" && e.output_hash == content_hash(&content))
+        {
+            skipped.push(key);
+            continue;
+        }
+        ctx.render.status(&format!("migrating {key}"));
+        let style = ctx
+            .config
+            .style_hint_for(path)
+            .map(|hint| format!("\nStyle guide: {hint}"))
+            .unwrap_or_default();
+        let messages = vec![
+            ChatMessage::system(
+                "You migrate code between idioms. Output only the complete \
+                 new file body — no fences, no commentary. If part of the \
+                 file cannot be migrated mechanically, keep it unchanged \
+                 and mark it with a TODO(migrate) comment.",
+            ),
+            ChatMessage::user(format!(
+                "Migrate from {} to {}.{style}\n\nFile `{key}`:\n\n{}",
+                args.from,
+                args.to,
+                ctx.redact(&content)
+            )),
+        ];
+        let resp = ctx.complete(messages).await?;
+        let mut body = strip_code_fence(&resp.content).to_string();
+        if !body.ends_with('\n') {
+            body.push('\n');
+        }
+        backup_file_async(path).await?;
+        write_file_async(path, &body).await?;
+
+        // Validation gates each file; a failing file is rolled back and
+        // left for a human rather than breaking the tree mid-run.
+        if let Some(cmd) = &args.validate {
+            let output = crate::platform::shell_exec(cmd)?;
+            if !output.status.success() {
+                write_file_async(path, &content).await?;
+                ctx.render
+                    .warn(&format!("{key}: `{cmd}` failed after rewrite; rolled back"));
+                manifest.entries.insert(
+                    key.clone(),
+                    MigrateEntry {
+                        status: "manual".to_string(),
+                        output_hash: content_hash(&content),
+                    },
+                );
+                manual.push(key);
+                continue;
+            }
+        }
+        crate::provenance::track(ctx.config.provenance, path, "migrate", None, &body)?;
+        let needs_followup = body.contains("TODO(migrate)");
+        manifest.entries.insert(
+            key.clone(),
+            MigrateEntry {
+                status: if needs_followup {
+                    "manual".to_string()
+                } else {
+                    "migrated".to_string()
+                },
+                output_hash: content_hash(&body),
+            },
+        );
+        if needs_followup {
+            manual.push(key);
+        } else {
+            migrated.push(key);
+        }
+        save_manifest(&ctx.workspace, &manifest)?;
+    }
+    save_manifest(&ctx.workspace, &manifest)?;
+
+    ctx.render.status(&format!(
+        "{} migrated, {} already done, {} need manual work",
+        migrated.len(),
+        skipped.len(),
+        manual.len()
+    ));
+    let report = if manual.is_empty() {
+        None
+    } else {
+        let messages = vec![
+            ChatMessage::system(
+                "You summarize migration progress. List the remaining manual \
+                 work per file as actionable bullet points. Markdown only.",
+            ),
+            ChatMessage::user(format!(
+                "Migration from {} to {}: {} file(s) migrated cleanly; these \
+                 need manual attention (rolled back after failed validation, \
+                 or carrying TODO(migrate) markers):\n{}",
+                args.from,
+                args.to,
+                migrated.len(),
+                manual
+                    .iter()
+                    .map(|f| format!("- {f}\n"))
+                    .collect::<String>()
+            )),
+        ];
+        Some(ctx.complete(messages).await?.content)
+    };
+
+    let out = MigrateOutput {
+        from: args.from.clone(),
+        to: args.to.clone(),
+        affected: affected.len(),
+        migrated,
+        skipped,
+        manual,
+        report,
+        dry_run: false,
+    };
+    ctx.render.emit(&out, || match &out.report {
+        Some(r) => ctx.render.markdown(r),
+        None => out.migrated.join("\n"),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_matches_only_files_using_the_old_idiom() {
+        let dir = std::env::temp_dir().join("sw-migrate-scan-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let old = dir.join("old.js");
+        let new = dir.join("new.js");
+        std::fs::write(&old, "class Widget extends React.Component {}\n").unwrap();
+        std::fs::write(&new, "const Widget = () => null;\n").unwrap();
+
+        let patterns = vec![regex::Regex::new(r"extends React\.Component").unwrap()];
+        let affected = scan_affected(&[old.clone(), new], &patterns);
+        assert_eq!(affected, vec![old]);
+    }
+}
@@ -0,0 +1,39 @@
+//! Command handlers, one module per top-level subcommand.
+
+pub mod agent;
+pub mod ask;
+pub mod backups;
+pub mod batch;
+pub mod chat;
+pub mod checkpoint;
+pub mod ci;
+pub mod commitlint;
+pub mod commitmsg;
+pub mod compare;
+pub mod debug;
+pub mod deps;
+pub mod diagram;
+pub mod diffcmd;
+pub mod explain;
+pub mod files;
+pub mod generate;
+pub mod grep;
+pub mod i18n;
+pub mod init;
+pub mod issue;
+pub mod map;
+pub mod migrate;
+pub mod models;
+pub mod provenance;
+pub mod release;
+pub mod report;
+pub mod rerun;
+pub mod review;
+pub mod run;
+pub mod script;
+pub mod serve;
+pub mod sessioncmd;
+pub mod stats;
+pub mod summarize;
+pub mod template;
+pub mod testscmd;
@@ -0,0 +1,156 @@
+//! `sw models` — list available models, with a local cache.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::cli::{ModelsListArgs, ModelsRefreshArgs};
+use crate::config::Config;
+use crate::llm::ModelInfo;
+
+/// Cache entries older than this are refreshed after serving.
+const CACHE_MAX_AGE_HOURS: i64 = 24;
+
+#[derive(Serialize, Deserialize)]
+struct ModelCache {
+    provider: String,
+    fetched_at: chrono::DateTime<chrono::Utc>,
+    models: Vec<ModelInfo>,
+}
+
+impl ModelCache {
+    fn is_stale(&self) -> bool {
+        chrono::Utc::now() - self.fetched_at > chrono::Duration::hours(CACHE_MAX_AGE_HOURS)
+    }
+}
+
+fn cache_path(provider: &str) -> Result<std::path::PathBuf> {
+    Ok(Config::data_dir()?
+        .join("models")
+        .join(format!("{provider}.json")))
+}
+
+fn load_cache(provider: &str) -> Option<ModelCache> {
+    let raw = std::fs::read_to_string(cache_path(provider).ok()?).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_cache(provider: &str, models: &[ModelInfo]) -> Result<()> {
+    let path = cache_path(provider)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let cache = ModelCache {
+        provider: provider.to_string(),
+        fetched_at: chrono::Utc::now(),
+        models: models.to_vec(),
+    };
+    std::fs::write(&path, serde_json::to_string_pretty(&cache)?)?;
+    Ok(())
+}
+
+pub async fn cmd_models_list(args: &ModelsListArgs, ctx: &AppContext) -> Result<()> {
+    let profile = ctx.profile()?;
+    let cached = load_cache(&profile.provider);
+
+    if args.cached {
+        let cache = cached.context("no cached model list; run `models refresh` first")?;
+        return emit_models(ctx, &cache.models);
+    }
+
+    // Serve from cache instantly when we have one; a stale cache is still
+    // shown first and refreshed afterwards, so the next run is current.
+    if let Some(cache) = cached {
+        let stale = cache.is_stale();
+        emit_models(ctx, &cache.models)?;
+        if stale {
+            ctx.render.status("model cache is stale; refreshing");
+            let provider = ctx.provider()?;
+            match provider.list_models().await {
+                Ok(models) => write_cache(&profile.provider, &models)?,
+                Err(e) => ctx.render.warn(&format!("refresh failed: {e:#}")),
+            }
+        }
+        return Ok(());
+    }
+
+    let provider = ctx.provider()?;
+    let models = provider.list_models().await?;
+    write_cache(&profile.provider, &models)?;
+    emit_models(ctx, &models)
+}
+
+fn emit_models(ctx: &AppContext, models: &[ModelInfo]) -> Result<()> {
+    if models.is_empty() {
+        bail!("provider returned no models");
+    }
+    ctx.render.emit(&models, || {
+        models
+            .iter()
+            .map(|m| m.id.clone())
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct RefreshEntry {
+    provider: String,
+    models: usize,
+}
+
+pub async fn cmd_models_refresh(args: &ModelsRefreshArgs, ctx: &AppContext) -> Result<()> {
+    // One provider can back several profiles; refresh each cache once.
+    let mut profiles: Vec<crate::config::Profile> = Vec::new();
+    if args.all {
+        for profile in ctx.config.profiles.values() {
+            if !profiles.iter().any(|p| p.provider == profile.provider) {
+                profiles.push(profile.clone());
+            }
+        }
+    } else {
+        profiles.push(ctx.profile()?);
+    }
+
+    let calls = profiles.iter().map(|profile| {
+        let name = profile.provider.clone();
+        let provider = crate::llm::ProviderRegistry::create(&ctx.config, profile);
+        async move {
+            match provider {
+                Ok(p) => (name.clone(), p.list_models().await),
+                Err(e) => (name, Err(e)),
+            }
+        }
+    });
+    let results = tokio::select! {
+        r = futures_util::future::join_all(calls) => r,
+        _ = ctx.cancel.cancelled() => anyhow::bail!(crate::cancel::INTERRUPTED),
+    };
+
+    let mut refreshed = Vec::new();
+    for (provider, result) in results {
+        match result {
+            Ok(models) => {
+                write_cache(&provider, &models)?;
+                refreshed.push(RefreshEntry {
+                    models: models.len(),
+                    provider,
+                });
+            }
+            Err(e) => ctx.render.warn(&format!("{provider}: {e:#}")),
+        }
+    }
+    anyhow::ensure!(!refreshed.is_empty(), "no provider could be refreshed");
+
+    ctx.render
+        .status(&format!("{} provider cache(s) refreshed", refreshed.len()));
+    ctx.render.emit(&refreshed, || {
+        refreshed
+            .iter()
+            .map(|r| format!("{}: {} models", r.provider, r.models))
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Ok(())
+}
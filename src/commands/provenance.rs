@@ -0,0 +1,46 @@
+//! `sw provenance` — inspect the generated-code audit trail.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::ProvenanceShowArgs;
+use crate::provenance::ProvenanceRecord;
+
+#[derive(Serialize)]
+struct ShowOutput {
+    file: String,
+    records: Vec<ProvenanceRecord>,
+}
+
+pub async fn cmd_provenance_show(args: &ProvenanceShowArgs, ctx: &AppContext) -> Result<()> {
+    let records = crate::provenance::records_for(&args.file)?;
+    if records.is_empty() {
+        bail!(
+            "no provenance recorded for {} (enable with provenance = true in config)",
+            args.file.display()
+        );
+    }
+    let output = ShowOutput {
+        file: args.file.display().to_string(),
+        records,
+    };
+    ctx.render.emit(&output, || {
+        output
+            .records
+            .iter()
+            .map(|r| {
+                format!(
+                    "{} {} model={} prompt={}",
+                    r.timestamp
+                        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+                    r.command,
+                    r.model.as_deref().unwrap_or("none"),
+                    r.prompt_hash
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Ok(())
+}
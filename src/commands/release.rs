@@ -0,0 +1,205 @@
+//! `sw release prepare` — changelog, version bump, and tag message in
+//! one pass, written as reviewable files rather than applied directly.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::ReleasePrepareArgs;
+use crate::llm::ChatMessage;
+
+/// `x.y.z` bumped at the requested position.
+pub fn bump_version(current: &str, bump: &str) -> Result<String> {
+    let parts: Vec<u64> = current
+        .trim()
+        .splitn(3, '.')
+        .map(|p| p.parse::<u64>())
+        .collect::<Result<_, _>>()
+        .with_context(|| format!("cannot parse version '{current}'"))?;
+    let [major, minor, patch] = parts[..] else {
+        bail!("expected major.minor.patch, got '{current}'");
+    };
+    Ok(match bump {
+        "major" => format!("{}.0.0", major + 1),
+        "minor" => format!("{major}.{}.0", minor + 1),
+        "patch" => format!("{major}.{minor}.{}", patch + 1),
+        other => bail!("unknown bump '{other}' (expected major, minor, or patch)"),
+    })
+}
+
+/// The version declared in a manifest, plus the content with it replaced.
+/// Only the first declaration is touched — dependency tables repeat the
+/// key, the package header does not.
+pub fn bump_manifest(content: &str, file: &str, next: &str) -> Option<(String, String)> {
+    let re = if file.ends_with(".json") {
+        regex::Regex::new(r#""version"\s*:\s*"([^"]+)""#)
+    } else {
+        regex::Regex::new(r#"(?m)^version\s*=\s*"([^"]+)""#)
+    }
+    .expect("static regex");
+    let caps = re.captures(content)?;
+    let current = caps[1].to_string();
+    let replaced = re
+        .replace(content, |c: &regex::Captures| c[0].replace(&current, next))
+        .into_owned();
+    Some((current, replaced))
+}
+
+#[derive(Serialize)]
+struct ReleasePrepareOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_tag: Option<String>,
+    version: String,
+    commits: usize,
+    files: Vec<String>,
+}
+
+pub async fn cmd_release_prepare(args: &ReleasePrepareArgs, ctx: &AppContext) -> Result<()> {
+    if !crate::gitutil::in_work_tree() {
+        bail!("release prepare needs a git work tree");
+    }
+    let previous_tag = crate::gitutil::git(&["describe", "--tags", "--abbrev=0"]).ok();
+    let range = previous_tag
+        .as_ref()
+        .map(|t| format!("{t}..HEAD"))
+        .unwrap_or_else(|| "HEAD".to_string());
+    let commits = crate::gitutil::commits_in_range(&range)?;
+    if commits.is_empty() {
+        bail!(
+            "no commits since {}; nothing to release",
+            previous_tag.as_deref().unwrap_or("the beginning")
+        );
+    }
+
+    // Version bump diffs for whichever manifests the workspace carries.
+    let mut current_version = None;
+    let mut patch = String::new();
+    for manifest in ["Cargo.toml", "package.json"] {
+        let path = ctx.workspace.join(manifest);
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        // The bump target comes from the first manifest found; the same
+        // next version is then written into every manifest.
+        if current_version.is_none() {
+            if let Some((version, _)) = bump_manifest(&content, manifest, "0.0.0") {
+                current_version = Some(version);
+            }
+        }
+        let next = match &args.version {
+            Some(v) => v.clone(),
+            None => bump_version(
+                current_version.as_deref().context("no version found")?,
+                &args.bump,
+            )?,
+        };
+        if let Some((_, replaced)) = bump_manifest(&content, manifest, &next) {
+            patch.push_str(&crate::commands::template::unified_diff(
+                manifest, &content, &replaced,
+            ));
+        }
+    }
+    let current = current_version
+        .context("no Cargo.toml or package.json with a version found in the workspace")?;
+    let next = match &args.version {
+        Some(v) => v.clone(),
+        None => bump_version(&current, &args.bump)?,
+    };
+
+    // Draft the notes from the commit subjects; the summary paragraph
+    // doubles as the annotated tag message.
+    let subjects: String = commits
+        .iter()
+        .map(|(hash, subject)| format!("{hash} {subject}\n"))
+        .collect();
+    ctx.render
+        .status(&format!("drafting notes for {} commit(s)", commits.len()));
+    let messages = vec![
+        ChatMessage::system(
+            "You write release notes from commit subjects. Start with one \
+             summary paragraph, then group changes under `## Added`, \
+             `## Changed`, and `## Fixed` headings, dropping noise commits. \
+             Output markdown only.",
+        ),
+        ChatMessage::user(format!(
+            "Draft release notes for v{next} (previous release: {}).\n\nCommits:\n{subjects}",
+            previous_tag.as_deref().unwrap_or("none")
+        )),
+    ];
+    let notes = ctx.complete(messages).await?.content;
+    let summary = notes
+        .split("\n\n")
+        .find(|p| !p.trim().is_empty() && !p.trim_start().starts_with('#'))
+        .unwrap_or("")
+        .trim();
+
+    let dir = args
+        .out
+        .clone()
+        .unwrap_or_else(|| ctx.workspace.join(".sw").join("release"));
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let mut files = Vec::new();
+    let mut write = |name: String, content: &str| -> Result<PathBuf> {
+        let path = dir.join(&name);
+        std::fs::write(&path, content)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        files.push(path.display().to_string());
+        Ok(path)
+    };
+    write(
+        format!("release-notes-v{next}.md"),
+        &format!("{}\n", notes.trim_end()),
+    )?;
+    let patch_path = write("0001-bump-version.patch".to_string(), &patch)?;
+    let tag_path = write(
+        "tag-message.txt".to_string(),
+        &format!("Release v{next}\n\n{summary}\n"),
+    )?;
+
+    ctx.render.status(&format!(
+        "review the files under {}, apply the bump with `sw diff apply {}`, \
+         then `git tag -a v{next} -F {}`",
+        dir.display(),
+        patch_path.display(),
+        tag_path.display()
+    ));
+    let output = ReleasePrepareOutput {
+        previous_tag,
+        version: next,
+        commits: commits.len(),
+        files,
+    };
+    ctx.render.emit(&output, || output.files.join("\n"));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumps_each_position() {
+        assert_eq!(bump_version("1.2.3", "patch").unwrap(), "1.2.4");
+        assert_eq!(bump_version("1.2.3", "minor").unwrap(), "1.3.0");
+        assert_eq!(bump_version("1.2.3", "major").unwrap(), "2.0.0");
+        assert!(bump_version("1.2", "patch").is_err());
+        assert!(bump_version("1.2.3", "mega").is_err());
+    }
+
+    #[test]
+    fn bumps_only_the_package_version() {
+        let cargo =
+            "[package]\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1\" }\n";
+        let (current, replaced) = bump_manifest(cargo, "Cargo.toml", "0.2.0").unwrap();
+        assert_eq!(current, "0.1.0");
+        assert!(replaced.contains("version = \"0.2.0\""));
+        assert!(replaced.contains("serde = { version = \"1\" }"));
+
+        let pkg = "{\n  \"name\": \"x\",\n  \"version\": \"1.0.0\"\n}\n";
+        let (current, replaced) = bump_manifest(pkg, "package.json", "1.0.1").unwrap();
+        assert_eq!(current, "1.0.0");
+        assert!(replaced.contains("\"version\": \"1.0.1\""));
+    }
+}
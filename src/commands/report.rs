@@ -0,0 +1,304 @@
+//! `sw report` — unattended batch reports for cron and CI.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::cli::ReportNightlyArgs;
+use crate::commands::files::walk_files;
+
+/// Findings and TODO items stored per report; enough for a diff, not a dump.
+const MAX_ITEMS: usize = 500;
+
+#[derive(Serialize, Deserialize)]
+struct SecuritySection {
+    counts: BTreeMap<String, usize>,
+    findings: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TodoSection {
+    total: usize,
+    /// New since the previous report in this directory.
+    added: Vec<String>,
+    /// Resolved since the previous report.
+    removed: Vec<String>,
+    items: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DepsSection {
+    total: usize,
+    by_ecosystem: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OverviewSection {
+    files: usize,
+    total_lines: usize,
+    by_language: BTreeMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NightlyReport {
+    date: String,
+    generated_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    security: Option<SecuritySection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    todos: Option<TodoSection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deps: Option<DepsSection>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    overview: Option<OverviewSection>,
+}
+
+fn security_section(root: &Path) -> Result<SecuritySection> {
+    let rules = crate::commands::files::security_rules();
+    let mut findings = Vec::new();
+    for path in walk_files(root, &[])? {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        findings.extend(crate::commands::files::scan_content(
+            &path.display().to_string(),
+            &content,
+            &rules,
+        ));
+    }
+    Ok(SecuritySection {
+        counts: crate::commands::review::severity_counts(
+            findings.iter().map(|f| f.severity.as_str()),
+        ),
+        findings: findings
+            .iter()
+            .take(MAX_ITEMS)
+            .map(|f| format!("[{}] {}:{} {}", f.severity, f.path, f.line, f.rule))
+            .collect(),
+    })
+}
+
+/// `path:line: text` for every TODO/FIXME/HACK marker in the tree.
+fn collect_todos(root: &Path) -> Result<Vec<String>> {
+    let re = regex::Regex::new(r"\b(TODO|FIXME|HACK)\b").expect("static regex");
+    let mut items = Vec::new();
+    for path in walk_files(root, &[])? {
+        if crate::analysis::language_for_path(&path) == "Other" {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for (i, line) in content.lines().enumerate() {
+            if re.is_match(line) {
+                items.push(format!("{}:{}: {}", path.display(), i + 1, line.trim()));
+                if items.len() >= MAX_ITEMS {
+                    return Ok(items);
+                }
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// The most recent previous report in `dir`, used for the TODO diff.
+fn previous_report(dir: &Path, today: &str) -> Option<NightlyReport> {
+    let mut dates: Vec<String> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            name.strip_suffix(".json").map(|s| s.to_string())
+        })
+        .filter(|d| d.as_str() < today)
+        .collect();
+    dates.sort();
+    let latest = dates.pop()?;
+    let raw = std::fs::read_to_string(dir.join(format!("{latest}.json"))).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn overview_section(root: &Path) -> Result<OverviewSection> {
+    let mut by_language: BTreeMap<String, usize> = BTreeMap::new();
+    let mut files = 0usize;
+    let mut total_lines = 0usize;
+    for path in walk_files(root, &[])? {
+        let Ok(a) = crate::analysis::analyze_file(&path) else {
+            continue;
+        };
+        files += 1;
+        total_lines += a.total_lines;
+        *by_language.entry(a.language).or_default() += a.total_lines;
+    }
+    Ok(OverviewSection {
+        files,
+        total_lines,
+        by_language,
+    })
+}
+
+fn render_markdown(report: &NightlyReport) -> String {
+    let mut s = format!("# Nightly report — {}\n\n", report.date);
+    if let Some(sec) = &report.security {
+        s.push_str("## Security scan\n\n");
+        if sec.findings.is_empty() {
+            s.push_str("No findings.\n\n");
+        } else {
+            for (severity, n) in &sec.counts {
+                s.push_str(&format!("- {severity}: {n}\n"));
+            }
+            s.push('\n');
+            for f in &sec.findings {
+                s.push_str(&format!("- {f}\n"));
+            }
+            s.push('\n');
+        }
+    }
+    if let Some(todos) = &report.todos {
+        s.push_str(&format!("## TODOs ({})\n\n", todos.total));
+        for item in &todos.added {
+            s.push_str(&format!("- added: {item}\n"));
+        }
+        for item in &todos.removed {
+            s.push_str(&format!("- resolved: {item}\n"));
+        }
+        if todos.added.is_empty() && todos.removed.is_empty() {
+            s.push_str("No change since the previous report.\n");
+        }
+        s.push('\n');
+    }
+    if let Some(deps) = &report.deps {
+        s.push_str(&format!("## Dependencies ({})\n\n", deps.total));
+        for (eco, n) in &deps.by_ecosystem {
+            s.push_str(&format!("- {eco}: {n}\n"));
+        }
+        s.push('\n');
+    }
+    if let Some(overview) = &report.overview {
+        s.push_str(&format!(
+            "## Overview\n\n{} files, {} lines\n\n",
+            overview.files, overview.total_lines
+        ));
+        for (lang, lines) in &overview.by_language {
+            s.push_str(&format!("- {lang}: {lines} lines\n"));
+        }
+        s.push('\n');
+    }
+    s
+}
+
+#[derive(Serialize)]
+struct NightlySummary {
+    date: String,
+    markdown: String,
+    json: String,
+    sections: Vec<String>,
+    security_findings: usize,
+    todos_total: usize,
+    todos_added: usize,
+    todos_removed: usize,
+}
+
+pub async fn cmd_report_nightly(args: &ReportNightlyArgs, ctx: &AppContext) -> Result<()> {
+    let root = ctx.workspace.clone();
+    let dir = args
+        .dir
+        .clone()
+        .unwrap_or_else(|| root.join(&ctx.config.report.dir));
+    let sections: Vec<String> = match &args.sections {
+        Some(raw) => raw
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        None => ctx.config.report.sections.clone(),
+    };
+    for section in &sections {
+        if !matches!(section.as_str(), "security" | "todos" | "deps" | "overview") {
+            bail!("unknown report section '{section}' (security, todos, deps, overview)");
+        }
+    }
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create report dir {}", dir.display()))?;
+
+    let now = Utc::now();
+    let date = now.format("%Y-%m-%d").to_string();
+    let previous = previous_report(&dir, &date);
+
+    let mut report = NightlyReport {
+        date: date.clone(),
+        generated_at: now,
+        security: None,
+        todos: None,
+        deps: None,
+        overview: None,
+    };
+    for section in &sections {
+        ctx.render.status(&format!("section: {section}"));
+        match section.as_str() {
+            "security" => report.security = Some(security_section(&root)?),
+            "todos" => {
+                let items = collect_todos(&root)?;
+                let old: Vec<String> = previous
+                    .as_ref()
+                    .and_then(|p| p.todos.as_ref())
+                    .map(|t| t.items.clone())
+                    .unwrap_or_default();
+                report.todos = Some(TodoSection {
+                    total: items.len(),
+                    added: items.iter().filter(|i| !old.contains(i)).cloned().collect(),
+                    removed: old.iter().filter(|i| !items.contains(i)).cloned().collect(),
+                    items,
+                });
+            }
+            "deps" => {
+                let deps = crate::analysis::parse_dependencies(&root);
+                let mut by_ecosystem: BTreeMap<String, usize> = BTreeMap::new();
+                for dep in &deps {
+                    *by_ecosystem.entry(dep.ecosystem.to_string()).or_default() += 1;
+                }
+                report.deps = Some(DepsSection {
+                    total: deps.len(),
+                    by_ecosystem,
+                });
+            }
+            "overview" => report.overview = Some(overview_section(&root)?),
+            _ => unreachable!("validated above"),
+        }
+    }
+
+    let md_path: PathBuf = dir.join(format!("{date}.md"));
+    let json_path = dir.join(format!("{date}.json"));
+    std::fs::write(&md_path, render_markdown(&report))
+        .with_context(|| format!("failed to write {}", md_path.display()))?;
+    std::fs::write(&json_path, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("failed to write {}", json_path.display()))?;
+
+    let summary = NightlySummary {
+        date,
+        markdown: md_path.display().to_string(),
+        json: json_path.display().to_string(),
+        sections,
+        security_findings: report.security.as_ref().map_or(0, |s| s.findings.len()),
+        todos_total: report.todos.as_ref().map_or(0, |t| t.total),
+        todos_added: report.todos.as_ref().map_or(0, |t| t.added.len()),
+        todos_removed: report.todos.as_ref().map_or(0, |t| t.removed.len()),
+    };
+    ctx.render
+        .status(&format!("report written to {}", md_path.display()));
+    ctx.render.emit(&summary, || {
+        format!(
+            "{}\n{} security finding(s), {} TODO(s) (+{} / -{})",
+            summary.markdown,
+            summary.security_findings,
+            summary.todos_total,
+            summary.todos_added,
+            summary.todos_removed
+        )
+    });
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! `sw rerun` and `sw history` — replay recorded invocations.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::RerunArgs;
+use crate::history::{self, HistoryRecord};
+
+#[derive(Serialize)]
+struct HistoryEntry<'a> {
+    id: u64,
+    timestamp: String,
+    command: &'a str,
+    args: &'a [String],
+    success: bool,
+}
+
+pub async fn cmd_history_list(ctx: &AppContext) -> Result<()> {
+    let records = history::load()?;
+    let entries: Vec<HistoryEntry<'_>> = records
+        .iter()
+        .map(|r| HistoryEntry {
+            id: r.id,
+            timestamp: r.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            command: &r.command,
+            args: &r.args,
+            success: r.success,
+        })
+        .collect();
+    ctx.render.emit(&entries, || {
+        entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "#{} {} {} sw {}",
+                    e.id,
+                    e.timestamp,
+                    if e.success { "ok " } else { "err" },
+                    e.args.join(" ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Ok(())
+}
+
+/// Strip a recorded `--model` so a rerun override does not collide with
+/// clap's duplicate-argument check.
+fn without_model_flag(args: &[String]) -> Vec<String> {
+    let mut out = Vec::with_capacity(args.len());
+    let mut skip_value = false;
+    for arg in args {
+        if skip_value {
+            skip_value = false;
+            continue;
+        }
+        if arg == "--model" {
+            skip_value = true;
+            continue;
+        }
+        if arg.starts_with("--model=") {
+            continue;
+        }
+        out.push(arg.clone());
+    }
+    out
+}
+
+pub async fn cmd_rerun(args: &RerunArgs, ctx: &AppContext) -> Result<()> {
+    let records = history::load()?;
+    let rec: &HistoryRecord = match args.id {
+        Some(id) => records
+            .iter()
+            .find(|r| r.id == id)
+            .with_context(|| format!("no history entry #{id}"))?,
+        None => records.last().context("history is empty")?,
+    };
+
+    let mut argv = if ctx.model_override.is_some() {
+        without_model_flag(&rec.args)
+    } else {
+        rec.args.clone()
+    };
+    if let Some(model) = &ctx.model_override {
+        argv.push(format!("--model={model}"));
+    }
+
+    ctx.render
+        .status(&format!("rerunning #{}: sw {}", rec.id, argv.join(" ")));
+    let exe = std::env::current_exe().context("cannot locate the sw binary")?;
+    let status = tokio::process::Command::new(exe)
+        .args(&argv)
+        .status()
+        .await
+        .context("failed to spawn rerun")?;
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
@@ -0,0 +1,493 @@
+//! `sw review` — LLM code review of a file or diff.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::cli::ReviewArgs;
+use crate::fsutil::read_file_to_string_async;
+use crate::gitutil;
+use crate::llm::ChatMessage;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub severity: String,
+    pub location: String,
+    pub message: String,
+    /// Focus-specific fields (e.g. `cwe` under `--focus security`) carried
+    /// through to JSON output as-is.
+    #[serde(flatten, default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize)]
+struct ReviewOutput {
+    target: String,
+    /// Owner of the reviewed file, from CODEOWNERS or git shortlog.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    findings: Vec<Finding>,
+    counts: std::collections::BTreeMap<String, usize>,
+    summary: String,
+    model: String,
+}
+
+/// Rank a finding's severity string; unknown values rank lowest.
+pub fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "critical" => 3,
+        "high" => 2,
+        "medium" => 1,
+        _ => 0,
+    }
+}
+
+/// Findings-per-severity counts for JSON output and CI gating.
+pub fn severity_counts<'a>(
+    severities: impl Iterator<Item = &'a str>,
+) -> std::collections::BTreeMap<String, usize> {
+    let mut counts = std::collections::BTreeMap::new();
+    for s in severities {
+        *counts.entry(s.to_string()).or_default() += 1;
+    }
+    counts
+}
+
+const REVIEW_SYSTEM: &str = "You are a rigorous code reviewer. Respond with JSON: \
+{\"summary\": string, \"findings\": [{\"severity\": \"critical|high|medium|low\", \
+\"location\": \"file:line or area\", \"message\": string}]}. No prose outside the JSON.";
+
+/// Built-in `--focus` presets: the rubric that replaces the general one,
+/// and an extra per-finding JSON field the schema gains under that focus.
+fn focus_preset(name: &str) -> Option<(&'static str, Option<(&'static str, &'static str)>)> {
+    match name {
+        "security" => Some((
+            "Review exclusively for security: injection, unsafe deserialization, \
+             path traversal, secrets handling, authn/authz gaps, unsafe defaults. \
+             Ignore style and performance.",
+            Some(("cwe", "the closest CWE identifier, or null")),
+        )),
+        "performance" => Some((
+            "Review exclusively for performance: algorithmic complexity, \
+             unnecessary allocation or copying, blocking calls on hot paths, \
+             missing batching or caching. Ignore style.",
+            Some((
+                "impact",
+                "estimated impact: hot-path, incidental, or negligible",
+            )),
+        )),
+        "style" => Some((
+            "Review exclusively for style and readability: naming, structure, \
+             idiomatic use of the language, dead code, comment quality. \
+             Do not report functional bugs.",
+            None,
+        )),
+        "tests" => Some((
+            "Review exclusively for test quality and coverage: untested \
+             branches, missing edge cases, brittle assertions, test smells.",
+            Some((
+                "missing_test",
+                "a one-line description of the test to add, or null",
+            )),
+        )),
+        "api" => Some((
+            "Review exclusively the public API surface: naming consistency, \
+             breaking changes, error contracts, documentation of exported items.",
+            Some(("breaking", "true when the issue is a breaking change")),
+        )),
+        _ => None,
+    }
+}
+
+/// The system prompt for a review: the general rubric, or a focus preset
+/// or `[review_focus]` config entry when `--focus` was given.
+fn review_system(focus: Option<&str>, ctx: &AppContext) -> Result<String> {
+    let Some(name) = focus else {
+        return Ok(REVIEW_SYSTEM.to_string());
+    };
+    let (rubric, extra) = match focus_preset(name) {
+        Some((rubric, extra)) => (rubric.to_string(), extra),
+        None => match ctx.config.review_focus.get(name) {
+            Some(rubric) => (rubric.clone(), None),
+            None => bail!(
+                "unknown --focus '{name}' (built-in: security, performance, style, \
+                 tests, api; custom rubrics go under [review_focus] in config)"
+            ),
+        },
+    };
+    let field = match extra {
+        Some((key, desc)) => format!(" Each finding additionally has \"{key}\": {desc}."),
+        None => String::new(),
+    };
+    Ok(format!(
+        "{rubric} Respond with JSON: {{\"summary\": string, \"findings\": \
+         [{{\"severity\": \"critical|high|medium|low\", \"location\": \
+         \"file:line or area\", \"message\": string}}]}}.{field} No prose \
+         outside the JSON."
+    ))
+}
+
+#[derive(Deserialize)]
+struct ModelReview {
+    summary: String,
+    #[serde(default)]
+    findings: Vec<Finding>,
+}
+
+/// Pull the first JSON object out of a model reply that may wrap it in fences.
+pub fn extract_json_object(text: &str) -> Option<&str> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end > start {
+        Some(&text[start..=end])
+    } else {
+        None
+    }
+}
+
+/// Split a unified diff into `(path, section)` pairs on `diff --git`
+/// boundaries. A diff without such headers comes back as one section.
+fn split_diff_by_file(diff: &str) -> Vec<(String, String)> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            let path = rest.split(" b/").nth(1).unwrap_or(rest).to_string();
+            sections.push((path, String::new()));
+        }
+        if let Some((_, body)) = sections.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    if sections.is_empty() && !diff.trim().is_empty() {
+        sections.push(("(diff)".to_string(), diff.to_string()));
+    }
+    sections
+}
+
+#[derive(Serialize)]
+struct RouteSection {
+    area: String,
+    files: Vec<String>,
+    findings: Vec<Finding>,
+    counts: BTreeMap<String, usize>,
+    summary: String,
+}
+
+#[derive(Serialize)]
+struct RoutedOutput {
+    target: String,
+    sections: Vec<RouteSection>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    skipped: Vec<String>,
+    counts: BTreeMap<String, usize>,
+    model: String,
+}
+
+/// `review --staged` under `[[review_routes]]`: changed files are grouped
+/// by the first rule matching their path, each group is reviewed under
+/// that rule's focus and persona, and the output is sectioned per area.
+async fn routed_staged_review(args: &ReviewArgs, ctx: &AppContext, diff: String) -> Result<()> {
+    let mut compiled = Vec::new();
+    for route in &ctx.config.review_routes {
+        let glob = globset::Glob::new(&route.paths)
+            .with_context(|| format!("invalid [[review_routes]] glob '{}'", route.paths))?;
+        compiled.push((glob.compile_matcher(), route));
+    }
+
+    // Group sections by area label; files no rule covers get the general
+    // rubric under "general".
+    let mut areas: Vec<String> = Vec::new();
+    let mut groups: BTreeMap<String, (Option<&crate::config::ReviewRoute>, String, Vec<String>)> =
+        BTreeMap::new();
+    let mut skipped = Vec::new();
+    for (path, section) in split_diff_by_file(&ctx.redact(&diff)) {
+        let route = compiled
+            .iter()
+            .find(|(m, _)| m.is_match(&path))
+            .map(|(_, r)| *r);
+        if route.is_some_and(|r| r.skip) {
+            skipped.push(path);
+            continue;
+        }
+        let area = route
+            .map(|r| {
+                r.focus
+                    .clone()
+                    .or_else(|| r.persona.clone())
+                    .unwrap_or_else(|| r.paths.clone())
+            })
+            .unwrap_or_else(|| "general".to_string());
+        if !areas.contains(&area) {
+            areas.push(area.clone());
+        }
+        let entry = groups
+            .entry(area)
+            .or_insert((route, String::new(), Vec::new()));
+        entry.1.push_str(&section);
+        entry.2.push(path);
+    }
+    if !skipped.is_empty() {
+        ctx.render.status(&format!(
+            "skipping {} file(s) per review routes",
+            skipped.len()
+        ));
+    }
+    if groups.is_empty() {
+        bail!("all staged changes are routed to skip; nothing to review");
+    }
+
+    let mut sections = Vec::new();
+    let mut model = String::new();
+    // Areas in first-seen (diff) order, not BTreeMap order.
+    for area in &areas {
+        let (route, body, files) = &groups[area];
+        let mut system = review_system(route.and_then(|r| r.focus.as_deref()), ctx)?;
+        if let Some(name) = route.and_then(|r| r.persona.as_ref()) {
+            let persona = ctx.config.personas.get(name).with_context(|| {
+                format!("persona '{name}' not found; add [persona] entries to config")
+            })?;
+            system = format!("{persona}\n\n{system}");
+        }
+        ctx.render
+            .status(&format!("reviewing {} ({} file(s))", area, files.len()));
+        let messages = vec![
+            ChatMessage::system(system),
+            ChatMessage::user(format!(
+                "Review this diff (staged changes, {} area):\n\n```\n{body}\n```",
+                area
+            )),
+        ];
+        let resp = ctx.complete(messages).await?;
+        let parsed: ModelReview =
+            match extract_json_object(&resp.content).and_then(|j| serde_json::from_str(j).ok()) {
+                Some(p) => p,
+                None => ModelReview {
+                    summary: resp.content.clone(),
+                    findings: Vec::new(),
+                },
+            };
+        model = resp.model;
+        sections.push(RouteSection {
+            area: area.clone(),
+            files: files.clone(),
+            counts: severity_counts(parsed.findings.iter().map(|f| f.severity.as_str())),
+            findings: parsed.findings,
+            summary: parsed.summary,
+        });
+    }
+
+    let output = RoutedOutput {
+        target: "staged changes".to_string(),
+        counts: severity_counts(
+            sections
+                .iter()
+                .flat_map(|s| s.findings.iter().map(|f| f.severity.as_str())),
+        ),
+        sections,
+        skipped,
+        model,
+    };
+    let rendered = {
+        let mut s = String::new();
+        for section in &output.sections {
+            s.push_str(&format!(
+                "## {} ({})\n",
+                section.area,
+                section.files.join(", ")
+            ));
+            for f in &section.findings {
+                s.push_str(&format!(
+                    "[{}] {} — {}\n",
+                    f.severity, f.location, f.message
+                ));
+            }
+            s.push_str(&section.summary);
+            s.push_str("\n\n");
+        }
+        if !output.skipped.is_empty() {
+            s.push_str(&format!("skipped: {}\n", output.skipped.join(", ")));
+        }
+        s.trim_end().to_string()
+    };
+
+    if let Some(name) = &args.session {
+        crate::session::record_artifact(
+            name,
+            "Review the staged changes per review routes.",
+            &rendered,
+            Some(output.model.clone()),
+            ctx.config.session_max_record_bytes,
+        )?;
+        ctx.render.status(&format!("recorded in session '{name}'"));
+    }
+
+    ctx.render.emit(&output, || ctx.render.markdown(&rendered));
+
+    if let Some(threshold) = args.fail_on {
+        let over = output
+            .sections
+            .iter()
+            .flat_map(|s| s.findings.iter())
+            .filter(|f| severity_rank(&f.severity) >= threshold.rank())
+            .count();
+        if over > 0 {
+            bail!(
+                "{over} finding(s) at or above {} severity",
+                threshold.as_str()
+            );
+        }
+    }
+    Ok(())
+}
+
+pub async fn cmd_review(args: &ReviewArgs, ctx: &AppContext) -> Result<()> {
+    let (target, body, kind) = if args.staged {
+        let diff = gitutil::staged_diff()?;
+        if diff.is_empty() {
+            bail!("no staged changes to review");
+        }
+        // Path-based routing takes over when configured; an explicit
+        // --focus keeps the single-call behaviour.
+        if args.focus.is_none() && !ctx.config.review_routes.is_empty() {
+            return routed_staged_review(args, ctx, diff).await;
+        }
+        ("staged changes".to_string(), diff, "diff")
+    } else if let Some(path) = &args.diff_file {
+        ctx.ensure_sendable(path)?;
+        (
+            path.display().to_string(),
+            read_file_to_string_async(path).await?,
+            "diff",
+        )
+    } else if let Some(path) = &args.file {
+        ctx.ensure_sendable(path)?;
+        (
+            path.display().to_string(),
+            read_file_to_string_async(path).await?,
+            "file",
+        )
+    } else {
+        bail!("nothing to review: pass a file, --staged, or --diff-file");
+    };
+    let body = ctx.redact(&body);
+
+    let system = review_system(args.focus.as_deref(), ctx)?;
+    let messages = vec![
+        ChatMessage::system(system),
+        ChatMessage::user(format!(
+            "Review this {kind} ({target}):\n\n```\n{body}\n```"
+        )),
+    ];
+    let resp = ctx.complete(messages).await?;
+
+    let parsed: ModelReview =
+        match extract_json_object(&resp.content).and_then(|j| serde_json::from_str(j).ok()) {
+            Some(p) => p,
+            None => ModelReview {
+                summary: resp.content.clone(),
+                findings: Vec::new(),
+            },
+        };
+
+    // Surface who owns the reviewed file, so findings can be routed.
+    let owner = args.file.as_deref().and_then(|path| {
+        let owners = crate::owners::Owners::load(&ctx.workspace).ok().flatten()?;
+        crate::owners::owner_of(&owners, &ctx.workspace, path)
+    });
+
+    let output = ReviewOutput {
+        target,
+        owner,
+        counts: severity_counts(parsed.findings.iter().map(|f| f.severity.as_str())),
+        findings: parsed.findings,
+        summary: parsed.summary,
+        model: resp.model,
+    };
+    let rendered = {
+        let mut s = String::new();
+        for f in &output.findings {
+            let extra: String = f
+                .extra
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| format!(" ({k}: {v})"))
+                .collect();
+            s.push_str(&format!(
+                "[{}] {} — {}{extra}\n",
+                f.severity, f.location, f.message
+            ));
+        }
+        if let Some(owner) = &output.owner {
+            s.push_str(&format!("owned by {owner}\n"));
+        }
+        s.push_str(&output.summary);
+        s
+    };
+
+    if let Some(name) = &args.session {
+        crate::session::record_artifact(
+            name,
+            &format!("Review this {kind} ({}).", output.target),
+            &rendered,
+            Some(output.model.clone()),
+            ctx.config.session_max_record_bytes,
+        )?;
+        ctx.render.status(&format!("recorded in session '{name}'"));
+    }
+
+    ctx.render.emit(&output, || ctx.render.markdown(&rendered));
+
+    if let Some(threshold) = args.fail_on {
+        let over = output
+            .findings
+            .iter()
+            .filter(|f| severity_rank(&f.severity) >= threshold.rank())
+            .count();
+        if over > 0 {
+            bail!(
+                "{over} finding(s) at or above {} severity",
+                threshold.as_str()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn focus_presets_cover_the_documented_names() {
+        for name in ["security", "performance", "style", "tests", "api"] {
+            assert!(focus_preset(name).is_some(), "missing preset {name}");
+        }
+        assert!(focus_preset("vibes").is_none());
+        let (_, extra) = focus_preset("security").unwrap();
+        assert_eq!(extra.map(|(k, _)| k), Some("cwe"));
+    }
+
+    #[test]
+    fn splits_diffs_on_file_boundaries() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-x\n+y\n\
+                    diff --git a/docs/b.md b/docs/b.md\n--- a/docs/b.md\n+++ b/docs/b.md\n@@ -1 +1 @@\n-p\n+q\n";
+        let sections = split_diff_by_file(diff);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].0, "src/a.rs");
+        assert_eq!(sections[1].0, "docs/b.md");
+        assert!(sections[0].1.contains("+y"));
+        assert!(!sections[0].1.contains("+q"));
+        // Headerless diffs survive as a single unnamed section.
+        assert_eq!(split_diff_by_file("@@ -1 +1 @@\n-x\n+y\n").len(), 1);
+    }
+
+    #[test]
+    fn extracts_fenced_json() {
+        let text = "Here you go:\n```json\n{\"summary\": \"ok\"}\n```";
+        assert_eq!(extract_json_object(text), Some("{\"summary\": \"ok\"}"));
+        assert_eq!(extract_json_object("no json here"), None);
+    }
+}
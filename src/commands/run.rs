@@ -0,0 +1,221 @@
+//! `sw run` — execute a command and explain its failure.
+
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tokio::io::AsyncBufReadExt;
+
+use crate::app::AppContext;
+use crate::cli::RunArgs;
+use crate::llm::ChatMessage;
+
+/// Tail of the captured output kept for the prompt; failures explain
+/// themselves at the end, not the start.
+const MAX_OUTPUT_BYTES: usize = 12 * 1024;
+
+/// Source files quoted alongside the failure output.
+const MAX_QUOTED_FILES: usize = 5;
+
+#[derive(Serialize)]
+struct RunOutput {
+    command: String,
+    exit_code: Option<i32>,
+    summary: String,
+    model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix_diff: Option<String>,
+}
+
+/// Run the command with both output streams teed: lines pass through to
+/// the terminal as they arrive and are kept for the failure prompt.
+async fn run_teed(
+    command: &[String],
+    ctx: &AppContext,
+) -> Result<(std::process::ExitStatus, String)> {
+    let mut child = tokio::process::Command::new(&command[0])
+        .args(&command[1..])
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("failed to run `{}`", command[0]))?;
+    let mut out = tokio::io::BufReader::new(child.stdout.take().expect("piped stdout")).lines();
+    let mut err = tokio::io::BufReader::new(child.stderr.take().expect("piped stderr")).lines();
+
+    let mut captured = String::new();
+    let (mut out_done, mut err_done) = (false, false);
+    while !(out_done && err_done) {
+        tokio::select! {
+            line = out.next_line(), if !out_done => match line? {
+                Some(line) => {
+                    ctx.render.data(&format!("{line}\n"));
+                    captured.push_str(&line);
+                    captured.push('\n');
+                }
+                None => out_done = true,
+            },
+            line = err.next_line(), if !err_done => match line? {
+                Some(line) => {
+                    eprintln!("{line}");
+                    captured.push_str(&line);
+                    captured.push('\n');
+                }
+                None => err_done = true,
+            },
+            _ = ctx.cancel.cancelled() => {
+                let _ = child.start_kill();
+                let _ = child.wait().await;
+                bail!(crate::cancel::INTERRUPTED);
+            }
+        }
+    }
+    let status = tokio::select! {
+        s = child.wait() => s.context("failed to wait for command")?,
+        _ = ctx.cancel.cancelled() => {
+            let _ = child.start_kill();
+            let _ = child.wait().await;
+            bail!(crate::cancel::INTERRUPTED);
+        }
+    };
+    if captured.len() > MAX_OUTPUT_BYTES {
+        let mut start = captured.len() - MAX_OUTPUT_BYTES;
+        while !captured.is_char_boundary(start) {
+            start += 1;
+        }
+        captured = captured[start..].to_string();
+    }
+    Ok((status, captured))
+}
+
+/// Existing source files named in the output, quoted so the model can
+/// point at real lines rather than guess.
+fn quoted_files(output: &str, ctx: &AppContext) -> String {
+    let file_re =
+        regex::Regex::new(r"[A-Za-z0-9_][A-Za-z0-9_\-./]*\.[a-z]{1,4}\b").expect("static regex");
+    let mut seen = Vec::new();
+    for m in file_re.find_iter(output) {
+        let path = std::path::PathBuf::from(m.as_str());
+        if crate::analysis::language_for_path(&path) == "Other"
+            || ctx.ensure_sendable(&path).is_err()
+            || !path.is_file()
+        {
+            continue;
+        }
+        if !seen.contains(&path) {
+            seen.push(path);
+        }
+        if seen.len() >= MAX_QUOTED_FILES {
+            break;
+        }
+    }
+    let mut blocks = String::new();
+    for path in &seen {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        blocks.push_str(&format!(
+            "Contents of `{}`:\n```\n{}\n```\n\n",
+            path.display(),
+            ctx.redact(&content).trim_end_matches('\n')
+        ));
+    }
+    blocks
+}
+
+/// Whether to continue into the fix flow: forced by `--fix`, offered
+/// interactively on a terminal, and skipped in scripted runs.
+fn wants_fix(args: &RunArgs, ctx: &AppContext) -> bool {
+    use std::io::IsTerminal;
+    if args.fix {
+        return true;
+    }
+    if !ctx.render.is_text() || !std::io::stdin().is_terminal() {
+        return false;
+    }
+    ctx.render.status("propose a fix as a unified diff? [y/N]");
+    let mut line = String::new();
+    if std::io::stdin().read_line(&mut line).is_err() {
+        return false;
+    }
+    matches!(line.trim(), "y" | "Y" | "yes")
+}
+
+pub async fn cmd_run(args: &RunArgs, ctx: &AppContext) -> Result<()> {
+    anyhow::ensure!(
+        !args.command.is_empty(),
+        "no command given; use `sw run -- CMD ARGS`"
+    );
+    let display = args.command.join(" ");
+    ctx.render.status(&format!("running `{display}`"));
+    let (status, captured) = run_teed(&args.command, ctx).await?;
+    if status.success() {
+        return Ok(());
+    }
+    let code = status.code();
+    let code_str = code.map_or("unknown".to_string(), |c| c.to_string());
+    if args.no_summary {
+        bail!("`{display}` exited with status {code_str}");
+    }
+
+    ctx.render
+        .status(&format!("exited with status {code_str}; summarizing"));
+    let output = ctx.redact(&captured);
+    let blocks = quoted_files(&output, ctx);
+    let prompt = format!(
+        "The command `{display}` exited with status {code_str}. Its output \
+         (tail) was:\n\n```\n{}\n```\n\n{blocks}\
+         Summarize what failed and identify the probable cause, citing \
+         file and line references from the output where possible. Close \
+         with the single most likely next step.",
+        output.trim()
+    );
+    let messages = vec![
+        ChatMessage::system(
+            "You diagnose failed commands from their output. Be concrete \
+             and brief; never invent files or line numbers.",
+        ),
+        ChatMessage::user(prompt.clone()),
+    ];
+    let resp = ctx.complete(messages).await?;
+    let summary = resp.content.clone();
+    let mut model = resp.model;
+    if ctx.render.is_text() {
+        ctx.render
+            .data(&format!("\n{}\n", ctx.render.markdown(&summary)));
+    }
+
+    let mut fix_diff = None;
+    if wants_fix(args, ctx) {
+        let messages = vec![
+            ChatMessage::system(crate::commands::diffcmd::DIFF_SYSTEM),
+            ChatMessage::user(format!(
+                "{prompt}\n\nNow propose a fix for the failure as a unified diff."
+            )),
+        ];
+        let resp = ctx.complete(messages).await?;
+        let diff = crate::commands::generate::strip_code_fence(&resp.content).to_string();
+        model = resp.model;
+        if ctx.render.is_text() {
+            ctx.render.data(&format!("\n{diff}\n"));
+            ctx.render
+                .status("review the diff and apply it with `sw diff apply`");
+        }
+        fix_diff = Some(diff);
+    }
+
+    if !ctx.render.is_text() {
+        ctx.render.emit(
+            &RunOutput {
+                command: display.clone(),
+                exit_code: code,
+                summary,
+                model,
+                fix_diff,
+            },
+            String::new,
+        );
+    }
+    bail!("`{display}` exited with status {code_str}")
+}
@@ -0,0 +1,43 @@
+//! `sw script` — run project scripts with streamed output.
+
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+
+use crate::app::AppContext;
+use crate::cli::ScriptRunArgs;
+
+pub async fn cmd_script_run(args: &ScriptRunArgs, ctx: &AppContext) -> Result<()> {
+    if !args.file.exists() {
+        bail!("script {} not found", args.file.display());
+    }
+    ctx.render
+        .status(&format!("running {}", args.file.display()));
+
+    let mut child = crate::platform::script_invocation(&args.file)
+        .args(&args.args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .context("failed to spawn the script interpreter")?;
+
+    let status = tokio::select! {
+        s = child.wait() => s.context("failed to wait for script")?,
+        _ = ctx.cancel.cancelled() => {
+            let _ = child.start_kill();
+            let _ = child.wait().await;
+            anyhow::bail!(crate::cancel::INTERRUPTED);
+        }
+    };
+
+    if !status.success() {
+        bail!(
+            "script exited with status {}",
+            status
+                .code()
+                .map_or("unknown".to_string(), |c| c.to_string())
+        );
+    }
+    Ok(())
+}
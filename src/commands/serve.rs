@@ -0,0 +1,271 @@
+//! `sw serve` — expose the configured provider stack over an
+//! OpenAI-compatible HTTP endpoint.
+//!
+//! Other tools pointed at `http://127.0.0.1:PORT/v1` inherit sw's
+//! routing: key rotation, rate limits, redaction, and local stats all
+//! apply before a request leaves the machine. Requests are handled one
+//! at a time — this is a personal proxy, not a deployment server.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpListener;
+
+use crate::app::AppContext;
+use crate::cli::ServeOpenaiArgs;
+use crate::llm::{ChatMessage, ChatRequest, Role};
+
+#[derive(Debug, Deserialize)]
+struct IncomingRequest {
+    #[serde(default)]
+    model: Option<String>,
+    messages: Vec<IncomingMessage>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    max_tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IncomingMessage {
+    role: String,
+    content: String,
+}
+
+pub async fn cmd_serve_openai(args: &ServeOpenaiArgs, ctx: &AppContext) -> Result<()> {
+    let listener = TcpListener::bind((args.bind.as_str(), args.port))
+        .await
+        .with_context(|| format!("failed to bind {}:{}", args.bind, args.port))?;
+    ctx.render.status(&format!(
+        "serving OpenAI-compatible API on http://{}:{}/v1 (Ctrl-C to stop)",
+        args.bind, args.port
+    ));
+
+    loop {
+        tokio::select! {
+            _ = ctx.cancel.cancelled() => break,
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted.context("accept failed")?;
+                if let Err(e) = handle_connection(stream, ctx).await {
+                    ctx.render.warn(&format!("{peer}: {e:#}"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn handle_connection(stream: tokio::net::TcpStream, ctx: &AppContext) -> Result<()> {
+    let (read_half, mut write_half) = stream.into_split();
+    let mut reader = BufReader::new(read_half);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0usize;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line
+            .to_ascii_lowercase()
+            .strip_prefix("content-length:")
+            .map(str::trim)
+        {
+            content_length = value.parse().unwrap_or(0);
+        }
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await?;
+
+    if method != "POST" || path != "/v1/chat/completions" {
+        return write_error(
+            &mut write_half,
+            404,
+            "only POST /v1/chat/completions is served",
+        )
+        .await;
+    }
+    let incoming: IncomingRequest = match serde_json::from_slice(&body) {
+        Ok(req) => req,
+        Err(e) => return write_error(&mut write_half, 400, &format!("invalid request: {e}")).await,
+    };
+    if incoming.messages.is_empty() {
+        return write_error(&mut write_half, 400, "messages must not be empty").await;
+    }
+
+    // The same redaction that protects prompts built locally applies to
+    // proxied content before it reaches the upstream provider.
+    let messages: Vec<ChatMessage> = incoming
+        .messages
+        .iter()
+        .map(|m| ChatMessage {
+            role: match m.role.as_str() {
+                "system" => Role::System,
+                "assistant" => Role::Assistant,
+                _ => Role::User,
+            },
+            content: ctx.redact(&m.content),
+        })
+        .collect();
+
+    let profile = ctx.profile()?;
+    let model = incoming
+        .model
+        .filter(|m| !m.is_empty())
+        .unwrap_or(profile.model);
+    // Same per-model presets as chat_request; the caller still wins.
+    let caps = ctx.config.model_caps.get(&model);
+    let req = ChatRequest {
+        temperature: incoming
+            .temperature
+            .or(profile.temperature)
+            .or_else(|| caps.and_then(|c| c.temperature)),
+        max_tokens: incoming
+            .max_tokens
+            .or(profile.max_tokens)
+            .or_else(|| caps.and_then(|c| c.max_tokens)),
+        stop: caps.map(|c| c.stop.clone()).unwrap_or_default(),
+        reasoning_effort: profile.reasoning_effort,
+        model,
+        messages,
+    };
+    let provider = ctx.provider()?;
+    let started = std::time::Instant::now();
+
+    if incoming.stream {
+        serve_stream(&mut write_half, ctx, provider.as_ref(), &req).await?;
+    } else {
+        let resp = tokio::select! {
+            r = provider.send(&req) => r,
+            _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+        };
+        let resp = match resp {
+            Ok(resp) => resp,
+            Err(e) => return write_error(&mut write_half, 502, &format!("{e:#}")).await,
+        };
+        ctx.report_meta(&resp);
+        let payload = json!({
+            "id": format!("sw-{}", chrono::Utc::now().timestamp_millis()),
+            "object": "chat.completion",
+            "model": resp.model,
+            "choices": [{
+                "index": 0,
+                "message": {"role": "assistant", "content": resp.content},
+                "finish_reason": "stop",
+            }],
+            "usage": {
+                "prompt_tokens": resp.meta.prompt_tokens.unwrap_or(0),
+                "completion_tokens": resp.meta.completion_tokens.unwrap_or(0),
+                "total_tokens": resp.meta.prompt_tokens.unwrap_or(0)
+                    + resp.meta.completion_tokens.unwrap_or(0),
+            },
+        });
+        write_json(&mut write_half, 200, &payload).await?;
+    }
+
+    ctx.render.status(&format!(
+        "{} {} {}ms",
+        req.model,
+        if incoming.stream {
+            "stream"
+        } else {
+            "complete"
+        },
+        started.elapsed().as_millis()
+    ));
+    Ok(())
+}
+
+/// Proxy a streaming completion as server-sent `chat.completion.chunk`
+/// events, the framing OpenAI clients expect.
+async fn serve_stream(
+    write_half: &mut OwnedWriteHalf,
+    ctx: &AppContext,
+    provider: &dyn crate::llm::Provider,
+    req: &ChatRequest,
+) -> Result<()> {
+    write_half
+        .write_all(
+            b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\n\
+              cache-control: no-cache\r\nconnection: close\r\n\r\n",
+        )
+        .await?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let send_task = async {
+        // `tx` is owned here so the channel closes when the call ends.
+        // Thinking deltas are dropped: compat clients expect answer text.
+        let mut on_delta = move |delta: crate::llm::Delta| {
+            if let crate::llm::Delta::Content(delta) = delta {
+                let _ = tx.send(delta.to_string());
+            }
+        };
+        provider.send_stream(req, &mut on_delta).await
+    };
+    let writer_task = async {
+        let mut out = Vec::new();
+        while let Some(delta) = rx.recv().await {
+            let chunk = json!({
+                "object": "chat.completion.chunk",
+                "model": req.model,
+                "choices": [{"index": 0, "delta": {"content": delta}}],
+            });
+            out.extend_from_slice(format!("data: {chunk}\n\n").as_bytes());
+            // Flush per delta so clients see tokens as they arrive.
+            if write_half.write_all(&out).await.is_err() {
+                break;
+            }
+            out.clear();
+        }
+        write_half.write_all(b"data: [DONE]\n\n").await.ok();
+    };
+    let (result, ()) = tokio::join!(send_task, writer_task);
+    let resp = result?;
+    ctx.report_meta(&resp);
+    Ok(())
+}
+
+async fn write_json(
+    write_half: &mut OwnedWriteHalf,
+    status: u16,
+    payload: &serde_json::Value,
+) -> Result<()> {
+    let body = serde_json::to_string(payload)?;
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Bad Gateway",
+    };
+    write_half
+        .write_all(
+            format!(
+                "HTTP/1.1 {status} {reason}\r\ncontent-type: application/json\r\n\
+                 content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .as_bytes(),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn write_error(write_half: &mut OwnedWriteHalf, status: u16, message: &str) -> Result<()> {
+    write_json(
+        write_half,
+        status,
+        &json!({"error": {"message": message, "type": "invalid_request_error"}}),
+    )
+    .await
+}
@@ -0,0 +1,340 @@
+//! `sw session` — inspect and manage stored conversations.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppContext;
+use crate::cli::{SessionExportArgs, SessionImportArgs, SessionPublishArgs, SessionShowArgs};
+use crate::session::{SessionRecord, SessionStore};
+
+pub async fn cmd_session_list(ctx: &AppContext) -> Result<()> {
+    let store = SessionStore::open()?;
+    let names = store.list()?;
+    ctx.render.emit(&names, || names.join("\n"));
+    Ok(())
+}
+
+pub async fn cmd_session_show(args: &SessionShowArgs, ctx: &AppContext) -> Result<()> {
+    let store = SessionStore::open()?;
+    if !store.exists(&args.name) {
+        bail!("session '{}' not found", args.name);
+    }
+    let records = store.load(&args.name)?;
+    ctx.render.emit(&records, || {
+        records
+            .iter()
+            .map(|r| match &r.tool {
+                Some(t) => format!("[Tool {}({})] {}", t.name, t.arguments, r.content),
+                None => format!("[{:?}] {}", r.role, r.content),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    });
+    Ok(())
+}
+
+/// Render a transcript as shareable markdown: one section per record,
+/// headed by the role (or tool call) and timestamp.
+fn transcript_markdown(name: &str, records: &[SessionRecord]) -> String {
+    let mut s = format!("# Session {name}\n\n");
+    for r in records {
+        let heading = match &r.tool {
+            Some(t) => format!("Tool {}({})", t.name, t.arguments),
+            None => format!("{:?}", r.role),
+        };
+        s.push_str(&format!(
+            "## {heading} ({})\n\n{}\n\n",
+            r.timestamp.format("%Y-%m-%d %H:%M UTC"),
+            r.content.trim_end()
+        ));
+    }
+    s
+}
+
+#[derive(Serialize)]
+struct PublishOutput {
+    url: String,
+    service: String,
+    bytes: usize,
+}
+
+async fn publish_gist(
+    client: &reqwest::Client,
+    cfg: &crate::config::PublishConfig,
+    name: &str,
+    markdown: &str,
+) -> Result<String> {
+    let token = std::env::var(&cfg.token_env)
+        .with_context(|| format!("gist publishing needs a token in ${}", cfg.token_env))?;
+    let body = serde_json::json!({
+        "description": format!("sw session '{name}'"),
+        "public": !cfg.secret,
+        "files": { format!("{name}.md"): { "content": markdown } },
+    });
+    let resp = client
+        .post("https://api.github.com/gists")
+        .bearer_auth(token)
+        .header("Accept", "application/vnd.github+json")
+        .json(&body)
+        .send()
+        .await
+        .context("gist upload failed")?
+        .error_for_status()
+        .context("gist upload rejected")?;
+    let json: serde_json::Value = resp.json().await?;
+    json["html_url"]
+        .as_str()
+        .map(|s| s.to_string())
+        .context("gist response had no html_url")
+}
+
+async fn publish_paste(
+    client: &reqwest::Client,
+    cfg: &crate::config::PublishConfig,
+    markdown: &str,
+) -> Result<String> {
+    let url = cfg
+        .paste_url
+        .as_deref()
+        .context("publish.service = \"paste\" needs publish.paste_url in config")?;
+    let resp = client
+        .post(url)
+        .header("Content-Type", "text/markdown")
+        .body(markdown.to_string())
+        .send()
+        .await
+        .context("paste upload failed")?
+        .error_for_status()
+        .context("paste upload rejected")?;
+    Ok(resp.text().await?.trim().to_string())
+}
+
+/// Ask before anything leaves the machine; off a terminal the explicit
+/// `--yes` flag is the only way through.
+fn confirm_publish(prompt: &str, yes: bool, ctx: &AppContext) -> Result<()> {
+    if yes {
+        return Ok(());
+    }
+    use std::io::IsTerminal;
+    if !std::io::stdin().is_terminal() {
+        bail!("refusing to publish without --yes in a non-interactive run");
+    }
+    ctx.render.status(&format!("{prompt} [y/N]"));
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    if !matches!(line.trim(), "y" | "Y" | "yes") {
+        bail!("publish aborted");
+    }
+    Ok(())
+}
+
+pub async fn cmd_session_publish(args: &SessionPublishArgs, ctx: &AppContext) -> Result<()> {
+    let store = SessionStore::open()?;
+    if !store.exists(&args.name) {
+        bail!("session '{}' not found", args.name);
+    }
+    let records = store.load(&args.name)?;
+    let markdown = ctx.redact(&transcript_markdown(&args.name, &records));
+
+    // Secret scan before anything is uploaded; redaction catches known
+    // patterns, this catches what redaction is not configured for.
+    let rules = crate::commands::files::security_rules();
+    let findings = crate::commands::files::scan_content("transcript", &markdown, &rules);
+    if !findings.is_empty() {
+        for f in &findings {
+            ctx.render.warn(&format!(
+                "[{}] transcript line {}: {} — {}",
+                f.severity, f.line, f.rule, f.excerpt
+            ));
+        }
+        if !args.allow_findings {
+            bail!(
+                "{} potential secret(s) in the transcript; rerun with \
+                 --allow-findings to publish anyway",
+                findings.len()
+            );
+        }
+    }
+
+    let cfg = &ctx.config.publish;
+    confirm_publish(
+        &format!(
+            "publish session '{}' ({} bytes) via {}?",
+            args.name,
+            markdown.len(),
+            cfg.service
+        ),
+        args.yes,
+        ctx,
+    )?;
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent(concat!("sw-assist/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let url = match cfg.service.as_str() {
+        "gist" => publish_gist(&client, cfg, &args.name, &markdown).await?,
+        "paste" => publish_paste(&client, cfg, &markdown).await?,
+        other => bail!("unknown publish.service '{other}' (gist or paste)"),
+    };
+
+    ctx.render.status("published");
+    let out = PublishOutput {
+        url: url.clone(),
+        service: cfg.service.clone(),
+        bytes: markdown.len(),
+    };
+    ctx.render.emit(&out, || out.url.clone());
+    Ok(())
+}
+
+pub async fn cmd_session_clear(args: &SessionShowArgs, ctx: &AppContext) -> Result<()> {
+    let store = SessionStore::open()?;
+    if !store.exists(&args.name) {
+        bail!("session '{}' not found", args.name);
+    }
+    store.clear(&args.name)?;
+    ctx.render
+        .status(&format!("session '{}' cleared", args.name));
+    Ok(())
+}
+
+/// Portable session archive: everything the store keeps per record, plus
+/// enough envelope to validate on import.
+#[derive(Serialize, Deserialize)]
+pub struct SessionExport {
+    pub version: u32,
+    pub name: String,
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub records: Vec<SessionRecord>,
+}
+
+/// The version importers of this build understand.
+const EXPORT_VERSION: u32 = 1;
+
+/// Parse and validate an exported session file.
+pub fn parse_export(raw: &str) -> Result<SessionExport> {
+    let export: SessionExport =
+        serde_json::from_str(raw).context("not a valid session export file")?;
+    if export.version != EXPORT_VERSION {
+        bail!(
+            "unsupported export version {} (this build reads version {EXPORT_VERSION})",
+            export.version
+        );
+    }
+    if export.records.is_empty() {
+        bail!("export contains no records");
+    }
+    Ok(export)
+}
+
+#[derive(Serialize)]
+struct ExportOutput {
+    name: String,
+    records: usize,
+    file: String,
+}
+
+pub async fn cmd_session_export(args: &SessionExportArgs, ctx: &AppContext) -> Result<()> {
+    let store = SessionStore::open()?;
+    if !store.exists(&args.name) {
+        bail!("session '{}' not found", args.name);
+    }
+    let records = store.load(&args.name)?;
+    let body = match args.format.as_str() {
+        "json" => {
+            let export = SessionExport {
+                version: EXPORT_VERSION,
+                name: args.name.clone(),
+                exported_at: chrono::Utc::now(),
+                records,
+            };
+            let mut body = serde_json::to_string_pretty(&export)?;
+            body.push('\n');
+            body
+        }
+        "markdown" => transcript_markdown(&args.name, &records),
+        other => bail!("unknown format '{other}' (json or markdown)"),
+    };
+    match &args.out {
+        Some(path) => {
+            std::fs::write(path, &body)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            ctx.render.status(&format!(
+                "session '{}' exported to {}",
+                args.name,
+                path.display()
+            ));
+            let out = ExportOutput {
+                name: args.name.clone(),
+                records: store.load(&args.name)?.len(),
+                file: path.display().to_string(),
+            };
+            ctx.render.emit(&out, || out.file.clone());
+        }
+        None => ctx.render.data(&body),
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ImportOutput {
+    name: String,
+    records: usize,
+}
+
+pub async fn cmd_session_import(args: &SessionImportArgs, ctx: &AppContext) -> Result<()> {
+    let raw = std::fs::read_to_string(&args.file)
+        .with_context(|| format!("failed to read {}", args.file.display()))?;
+    let export = parse_export(&raw)?;
+    let name = args.name.clone().unwrap_or(export.name);
+
+    let store = SessionStore::open()?;
+    if store.exists(&name) {
+        if !args.force {
+            bail!("session '{name}' already exists; use --force to replace it");
+        }
+        store.clear(&name)?;
+    }
+    // Records were capped when first written; re-capping an archive
+    // would mangle it, so they go back verbatim.
+    for record in &export.records {
+        store.append(&name, record)?;
+    }
+    ctx.render.status(&format!(
+        "imported {} record(s) into session '{name}'",
+        export.records.len()
+    ));
+    let out = ImportOutput {
+        name,
+        records: export.records.len(),
+    };
+    ctx.render.emit(&out, || out.name.clone());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::Role;
+
+    #[test]
+    fn exports_validate_on_import() {
+        let export = SessionExport {
+            version: 1,
+            name: "demo".to_string(),
+            exported_at: chrono::Utc::now(),
+            records: vec![SessionRecord::now(Role::User, "hi", None)],
+        };
+        let raw = serde_json::to_string(&export).unwrap();
+        let back = parse_export(&raw).unwrap();
+        assert_eq!(back.name, "demo");
+        assert_eq!(back.records.len(), 1);
+
+        assert!(parse_export("not json").is_err());
+        let future = raw.replace("\"version\":1", "\"version\":99");
+        assert!(parse_export(&future).is_err());
+        let empty = raw.replace(&serde_json::to_string(&export.records).unwrap(), "[]");
+        assert!(parse_export(&empty).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! `sw stats` — breakdowns of locally recorded usage.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::StatsArgs;
+use crate::stats::{load_since, parse_since, StatsRecord};
+
+#[derive(Debug, Default, Clone, Serialize)]
+struct Aggregate {
+    invocations: u64,
+    failures: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+    total_duration_ms: u64,
+}
+
+impl Aggregate {
+    fn add(&mut self, rec: &StatsRecord) {
+        self.invocations += 1;
+        if !rec.success {
+            self.failures += 1;
+        }
+        self.prompt_tokens += rec.prompt_tokens;
+        self.completion_tokens += rec.completion_tokens;
+        if let Some(cost) = rec.cost_usd {
+            *self.cost_usd.get_or_insert(0.0) += cost;
+        }
+        self.total_duration_ms += rec.duration_ms;
+    }
+}
+
+#[derive(Serialize)]
+struct StatsOutput {
+    since: String,
+    total: Aggregate,
+    by_command: BTreeMap<String, Aggregate>,
+    by_model: BTreeMap<String, Aggregate>,
+    by_project: BTreeMap<String, Aggregate>,
+}
+
+fn render_group(out: &mut String, title: &str, group: &BTreeMap<String, Aggregate>) {
+    out.push_str(&format!("\n{title}:\n"));
+    for (name, agg) in group {
+        out.push_str(&format!("  {name}: {}\n", render_line(agg)));
+    }
+}
+
+fn render_line(agg: &Aggregate) -> String {
+    let mut s = format!("{} run(s)", agg.invocations);
+    if agg.failures > 0 {
+        s.push_str(&format!(", {} failed", agg.failures));
+    }
+    if agg.prompt_tokens + agg.completion_tokens > 0 {
+        s.push_str(&format!(
+            ", tokens={}+{}",
+            agg.prompt_tokens, agg.completion_tokens
+        ));
+    }
+    if let Some(cost) = agg.cost_usd {
+        s.push_str(&format!(", cost=${cost:.4}"));
+    }
+    s
+}
+
+pub async fn cmd_stats(args: &StatsArgs, ctx: &AppContext) -> Result<()> {
+    let cutoff = chrono::Utc::now() - parse_since(&args.since)?;
+    let records = load_since(cutoff)?;
+
+    let mut total = Aggregate::default();
+    let mut by_command: BTreeMap<String, Aggregate> = BTreeMap::new();
+    let mut by_model: BTreeMap<String, Aggregate> = BTreeMap::new();
+    let mut by_project: BTreeMap<String, Aggregate> = BTreeMap::new();
+    for rec in &records {
+        total.add(rec);
+        by_command.entry(rec.command.clone()).or_default().add(rec);
+        if let Some(model) = &rec.model {
+            by_model.entry(model.clone()).or_default().add(rec);
+        }
+        if let Some(project) = &rec.project {
+            by_project.entry(project.clone()).or_default().add(rec);
+        }
+    }
+
+    let output = StatsOutput {
+        since: args.since.clone(),
+        total,
+        by_command,
+        by_model,
+        by_project,
+    };
+    ctx.render.emit(&output, || {
+        if records.is_empty() {
+            return format!("no recorded invocations in the last {}", args.since);
+        }
+        let mut s = format!("last {}: {}", args.since, render_line(&output.total));
+        render_group(&mut s, "by command", &output.by_command);
+        render_group(&mut s, "by model", &output.by_model);
+        render_group(&mut s, "by project", &output.by_project);
+        s.trim_end().to_string()
+    });
+    Ok(())
+}
@@ -0,0 +1,289 @@
+//! `sw summarize` — summarize a file, chunking large inputs.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::{SummarizeArgs, SummaryAudience, SummaryLength, SummaryStyle};
+use crate::context::chunk_by_tokens;
+use crate::fsutil::read_file_to_string_async;
+use crate::llm::ChatMessage;
+
+#[derive(Serialize)]
+struct SummarizeOutput {
+    file: String,
+    summary: String,
+    chunks: usize,
+    model: String,
+}
+
+fn chunk_prompt(path: &str, part: usize, total: usize, content: &str) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::system("You summarize source files precisely and concisely for developers."),
+        ChatMessage::user(format!(
+            "Summarize part {part}/{total} of `{path}`. Focus on purpose, key \
+             structures, and notable behavior.\n\n```\n{content}\n```"
+        )),
+    ]
+}
+
+/// Prompt directives for the style flags; empty when none are set so the
+/// baseline prompt is unchanged.
+fn style_directives(args: &SummarizeArgs) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(style) = args.style {
+        parts.push(match style {
+            SummaryStyle::Bullets => "Format the summary as a bullet list.".into(),
+            SummaryStyle::Abstract => "Write the summary as a single abstract paragraph.".into(),
+            SummaryStyle::TlDr => "Write a terse TL;DR of one or two sentences.".into(),
+            SummaryStyle::Changelog => {
+                "Format the summary as changelog entries: one line per notable \
+                 item, most significant first."
+                    .into()
+            }
+        });
+    }
+    if let Some(audience) = args.audience {
+        parts.push(match audience {
+            SummaryAudience::Dev => "Write for developers; technical terms are fine and structure \
+                 details matter."
+                .into(),
+            SummaryAudience::Manager => {
+                "Write for a non-technical manager: plain language, purpose and \
+                 impact over mechanics."
+                    .into()
+            }
+        });
+    }
+    if let Some(length) = args.length {
+        parts.push(match length {
+            SummaryLength::Short => "Keep it to a few sentences.".into(),
+            SummaryLength::Medium => "Aim for one or two paragraphs.".into(),
+            SummaryLength::Long => "Be thorough; cover every significant section.".into(),
+        });
+    }
+    if let Some(lang) = &args.language {
+        parts.push(format!("Respond in {lang}."));
+    }
+    parts.join(" ")
+}
+
+/// Append the directives to the final user message. Chunk partials keep
+/// the default developer style; formatting is applied where the result
+/// the user sees is produced.
+fn with_directives(mut messages: Vec<ChatMessage>, directives: &str) -> Vec<ChatMessage> {
+    if !directives.is_empty() {
+        if let Some(last) = messages.last_mut() {
+            last.content.push_str("\n\n");
+            last.content.push_str(directives);
+        }
+    }
+    messages
+}
+
+fn merge_prompt(path: &str, group: &[String]) -> Vec<ChatMessage> {
+    vec![
+        ChatMessage::system(
+            "You merge partial summaries into one coherent summary without \
+             losing important detail.",
+        ),
+        ChatMessage::user(format!(
+            "Merge these {} partial summaries of `{}` into a single \
+             summary:\n\n{}",
+            group.len(),
+            path,
+            group.join("\n\n---\n\n")
+        )),
+    ]
+}
+
+/// Merge partials level by level, `fan_in` at a time in document order,
+/// until one summary remains. A single flat merge over hundreds of
+/// partials can itself exceed the context window; bounding each call to
+/// `fan_in` inputs keeps every merge small, and groups are formed in
+/// order so the result is deterministic for a given input.
+async fn tree_reduce(
+    ctx: &AppContext,
+    path: &str,
+    mut partials: Vec<String>,
+    fan_in: usize,
+    directives: &str,
+) -> Result<(String, String)> {
+    let mut model = String::new();
+    while partials.len() > 1 {
+        if partials.len() > fan_in {
+            ctx.render.status(&format!(
+                "merging {} partial summaries, {fan_in} at a time",
+                partials.len()
+            ));
+        }
+        // The last level produces what the user sees; style directives
+        // apply there, intermediate merges keep the default register.
+        let last_level = partials.len() <= fan_in;
+        let mut next = Vec::with_capacity(partials.len().div_ceil(fan_in));
+        for group in partials.chunks(fan_in) {
+            if group.len() == 1 {
+                next.push(group[0].clone());
+                continue;
+            }
+            let mut messages = merge_prompt(path, group);
+            if last_level {
+                messages = with_directives(messages, directives);
+            }
+            let resp = ctx.complete(messages).await?;
+            model = resp.model;
+            next.push(resp.content);
+        }
+        partials = next;
+    }
+    Ok((partials.pop().unwrap_or_default(), model))
+}
+
+pub async fn cmd_summarize(args: &SummarizeArgs, ctx: &AppContext) -> Result<()> {
+    ctx.ensure_sendable(&args.file)?;
+    let mut content = ctx.redact(&read_file_to_string_async(&args.file).await?);
+    let path = args.file.display().to_string();
+    // Tabular data files are profiled instead of sent raw: the model sees
+    // the schema and a handful of rows, not the megabytes.
+    if let Some(p) =
+        crate::datafile::profile(&args.file, &content, args.rows, args.columns.as_deref())?
+    {
+        ctx.render.status(&format!(
+            "{path}: sending schema and {} sampled row(s) instead of the raw data",
+            p.sample.len()
+        ));
+        content = p.prompt_block(&path);
+    }
+    let chunks = chunk_by_tokens(&content, args.chunk_tokens);
+    let total = chunks.len();
+
+    let directives = style_directives(args);
+
+    let (summary, model) = if total <= 1 {
+        let resp = ctx
+            .complete(with_directives(
+                chunk_prompt(&path, 1, 1, &content),
+                &directives,
+            ))
+            .await?;
+        (resp.content, resp.model)
+    } else {
+        ctx.render
+            .status(&format!("summarizing {path} in {total} chunks"));
+        // One task per chunk, gated by a semaphore so a huge file does not
+        // fire hundreds of simultaneous API calls. Handles are joined in
+        // spawn order, which keeps partials in document order.
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(args.concurrency.max(1)));
+        let mut handles = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let messages = chunk_prompt(&path, i + 1, total, chunk);
+            let req = ctx.chat_request(messages)?;
+            let provider = ctx.provider()?;
+            let semaphore = semaphore.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .context("summarize semaphore closed")?;
+                provider.send(&req).await
+            }));
+        }
+        let mut partials = Vec::with_capacity(total);
+        let mut failed = 0usize;
+        let mut interrupted = false;
+        for (i, handle) in handles.iter_mut().enumerate() {
+            tokio::select! {
+                r = &mut *handle => {
+                    match r.context("summarize task panicked")? {
+                        Ok(resp) => partials.push(resp.content),
+                        // One bad chunk should not sink the run; leave a
+                        // marker so the merged summary admits the gap.
+                        Err(e) => {
+                            ctx.render.warn(&format!("chunk {}/{total} failed: {e:#}", i + 1));
+                            partials.push(format!("(part {}/{total} could not be summarized)", i + 1));
+                            failed += 1;
+                        }
+                    }
+                }
+                _ = ctx.cancel.cancelled() => {
+                    interrupted = true;
+                    break;
+                }
+            }
+        }
+        if interrupted {
+            for handle in &handles {
+                handle.abort();
+            }
+            anyhow::bail!(crate::cancel::INTERRUPTED);
+        }
+        if failed == total {
+            anyhow::bail!("all {total} chunks failed");
+        }
+        if failed > 0 {
+            ctx.render.warn(&format!(
+                "{failed}/{total} chunk(s) missing from the summary"
+            ));
+        }
+        tree_reduce(ctx, &path, partials, args.fan_in.max(2), &directives).await?
+    };
+
+    if let Some(name) = &args.session {
+        crate::session::record_artifact(
+            name,
+            &format!("Summarize `{path}`."),
+            &summary,
+            Some(model.clone()),
+            ctx.config.session_max_record_bytes,
+        )?;
+        ctx.render.status(&format!("recorded in session '{name}'"));
+    }
+
+    ctx.render.emit(
+        &SummarizeOutput {
+            file: path,
+            summary: summary.clone(),
+            chunks: total,
+            model,
+        },
+        || summary.clone(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> SummarizeArgs {
+        SummarizeArgs {
+            file: "lib.rs".into(),
+            chunk_tokens: 4000,
+            fan_in: 8,
+            concurrency: 4,
+            style: None,
+            audience: None,
+            length: None,
+            language: None,
+            rows: 20,
+            columns: None,
+            session: None,
+        }
+    }
+
+    #[test]
+    fn directives_reflect_the_flags() {
+        assert_eq!(style_directives(&args()), "");
+        let mut a = args();
+        a.style = Some(SummaryStyle::TlDr);
+        a.audience = Some(SummaryAudience::Manager);
+        a.language = Some("French".into());
+        let d = style_directives(&a);
+        assert!(d.contains("TL;DR"));
+        assert!(d.contains("manager"));
+        assert!(d.contains("Respond in French."));
+        // Directives land on the user message, not the system prompt.
+        let msgs = with_directives(chunk_prompt("lib.rs", 1, 1, "fn x() {}"), &d);
+        assert!(msgs.last().unwrap().content.ends_with(&d));
+    }
+}
@@ -0,0 +1,305 @@
+//! `sw template` — render built-in project templates.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::{TemplateGenerateArgs, TemplateUpgradeArgs};
+
+/// A built-in template: (relative path, content with `{{var}}` placeholders).
+type TemplateFiles = &'static [(&'static str, &'static str)];
+
+const RUST_CLI: TemplateFiles = &[
+    (
+        "Cargo.toml",
+        "[package]\nname = \"{{name}}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\nclap = { version = \"4\", features = [\"derive\"] }\n",
+    ),
+    (
+        "src/main.rs",
+        "fn main() {\n    println!(\"hello from {{name}}\");\n}\n",
+    ),
+    (".gitignore", "/target\n"),
+];
+
+const PYTHON_SCRIPT: TemplateFiles = &[
+    (
+        "{{name}}.py",
+        "#!/usr/bin/env python3\n\"\"\"{{description}}\"\"\"\n\n\ndef main() -> None:\n    pass\n\n\nif __name__ == \"__main__\":\n    main()\n",
+    ),
+];
+
+pub fn builtin_templates() -> BTreeMap<&'static str, TemplateFiles> {
+    BTreeMap::from([("rust-cli", RUST_CLI), ("python-script", PYTHON_SCRIPT)])
+}
+
+/// Replace `{{key}}` placeholders; unknown placeholders are left as-is.
+pub fn render_template(content: &str, vars: &BTreeMap<String, String>) -> String {
+    let mut out = content.to_string();
+    for (key, value) in vars {
+        out = out.replace(&format!("{{{{{key}}}}}"), value);
+    }
+    out
+}
+
+/// Expand a variable value: `@path` reads the file at `path` (so long
+/// license headers need not be shell-quoted), `env:NAME` reads the
+/// environment, anything else is literal.
+fn resolve_value(value: &str) -> Result<String> {
+    if let Some(path) = value.strip_prefix('@') {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read variable file '{path}'"))?;
+        return Ok(content.trim_end_matches('\n').to_string());
+    }
+    if let Some(name) = value.strip_prefix("env:") {
+        return std::env::var(name)
+            .with_context(|| format!("environment variable '{name}' is not set"));
+    }
+    Ok(value.to_string())
+}
+
+pub fn parse_vars(raw: &[String]) -> Result<BTreeMap<String, String>> {
+    let mut vars = BTreeMap::new();
+    for item in raw {
+        let Some((key, value)) = item.split_once('=') else {
+            bail!("invalid --var '{item}', expected key=value");
+        };
+        vars.insert(key.trim().to_string(), resolve_value(value)?);
+    }
+    Ok(vars)
+}
+
+/// Load a `--vars-file`: a flat TOML table of string values, each run
+/// through the same `@path`/`env:` expansion as `--var`.
+fn load_vars_file(path: &std::path::Path) -> Result<BTreeMap<String, String>> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let table: toml::Table = raw
+        .parse()
+        .with_context(|| format!("invalid vars file at {}", path.display()))?;
+    let mut vars = BTreeMap::new();
+    for (key, value) in table {
+        let toml::Value::String(value) = value else {
+            bail!("vars file entry '{key}' must be a string");
+        };
+        vars.insert(key, resolve_value(&value)?);
+    }
+    Ok(vars)
+}
+
+pub async fn cmd_template_list(ctx: &AppContext) -> Result<()> {
+    let names: Vec<&str> = builtin_templates().keys().copied().collect();
+    ctx.render.emit(&names, || names.join("\n"));
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct GenerateOutput {
+    template: String,
+    written: Vec<String>,
+}
+
+/// Record of what was generated, used later by template upgrades.
+#[derive(Serialize, serde::Deserialize)]
+struct TemplateRecord {
+    template: String,
+    variables: BTreeMap<String, String>,
+}
+
+pub async fn cmd_template_generate(args: &TemplateGenerateArgs, ctx: &AppContext) -> Result<()> {
+    let templates = builtin_templates();
+    let Some(files) = templates.get(args.template.as_str()) else {
+        bail!(
+            "unknown template '{}'; available: {}",
+            args.template,
+            templates.keys().copied().collect::<Vec<_>>().join(", ")
+        );
+    };
+    let mut vars = match &args.vars_file {
+        Some(path) => load_vars_file(path)?,
+        None => BTreeMap::new(),
+    };
+    vars.extend(parse_vars(&args.vars)?);
+    vars.entry("name".to_string())
+        .or_insert_with(|| "project".to_string());
+
+    let mut written = Vec::new();
+    for (rel, content) in files.iter() {
+        let rel_rendered = render_template(rel, &vars);
+        let dest = args.out.join(&rel_rendered);
+        if dest.exists() {
+            bail!("{} already exists; refusing to overwrite", dest.display());
+        }
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&dest, render_template(content, &vars))?;
+        written.push(rel_rendered);
+    }
+
+    // Record template + variables so the project can be upgraded later.
+    let record_dir = args.out.join(".sw");
+    std::fs::create_dir_all(&record_dir)?;
+    std::fs::write(
+        record_dir.join("template.json"),
+        serde_json::to_string_pretty(&TemplateRecord {
+            template: args.template.clone(),
+            variables: vars,
+        })?,
+    )?;
+
+    ctx.render
+        .status(&format!("rendered {} file(s)", written.len()));
+    let out = GenerateOutput {
+        template: args.template.clone(),
+        written,
+    };
+    ctx.render.emit(&out, || out.written.join("\n"));
+    Ok(())
+}
+
+/// Minimal unified diff with the whole file as one hunk. The inputs are
+/// short (template boilerplate, manifests), so context trimming buys
+/// nothing, and the output is accepted by `sw diff apply`. Shared with
+/// `release prepare`.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    // Longest-common-subsequence table, walked forward to interleave
+    // context, removals, and additions.
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut body = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            body.push_str(&format!(" {}\n", a[i]));
+            i += 1;
+            j += 1;
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            body.push_str(&format!("+{}\n", b[j]));
+            j += 1;
+        } else {
+            body.push_str(&format!("-{}\n", a[i]));
+            i += 1;
+        }
+    }
+    format!(
+        "--- a/{path}\n+++ b/{path}\n@@ -1,{} +1,{} @@\n{body}",
+        a.len(),
+        b.len()
+    )
+}
+
+#[derive(Serialize)]
+struct UpgradeOutput {
+    template: String,
+    drifted: Vec<String>,
+    diff: String,
+}
+
+pub async fn cmd_template_upgrade(args: &TemplateUpgradeArgs, ctx: &AppContext) -> Result<()> {
+    let record_path = args.out.join(".sw").join("template.json");
+    let record: TemplateRecord = serde_json::from_str(
+        &std::fs::read_to_string(&record_path)
+            .context("no template record found; this project was not generated by `sw template`")?,
+    )
+    .with_context(|| format!("corrupt template record at {}", record_path.display()))?;
+    let name = args.template.clone().unwrap_or(record.template);
+
+    let templates = builtin_templates();
+    let Some(files) = templates.get(name.as_str()) else {
+        bail!(
+            "unknown template '{name}'; available: {}",
+            templates.keys().copied().collect::<Vec<_>>().join(", ")
+        );
+    };
+
+    let mut drifted = Vec::new();
+    let mut diff = String::new();
+    for (rel, content) in files.iter() {
+        let rel_rendered = render_template(rel, &record.variables);
+        let rendered = render_template(content, &record.variables);
+        let current = std::fs::read_to_string(args.out.join(&rel_rendered)).unwrap_or_default();
+        if current == rendered {
+            continue;
+        }
+        diff.push_str(&unified_diff(&rel_rendered, &current, &rendered));
+        drifted.push(rel_rendered);
+    }
+
+    ctx.render.status(&format!(
+        "{} of {} file(s) drifted from template '{name}'",
+        drifted.len(),
+        files.len()
+    ));
+    if !drifted.is_empty() {
+        ctx.render
+            .status("review the diff and apply it with `sw diff apply`");
+    }
+    let out = UpgradeOutput {
+        template: name,
+        drifted,
+        diff,
+    };
+    ctx.render.emit(&out, || out.diff.trim_end().to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders() {
+        let vars = BTreeMap::from([("name".to_string(), "demo".to_string())]);
+        assert_eq!(render_template("hi {{name}}!", &vars), "hi demo!");
+        assert_eq!(render_template("{{unknown}}", &vars), "{{unknown}}");
+    }
+
+    #[test]
+    fn parses_vars() {
+        let vars = parse_vars(&["a=1".to_string(), "b=x=y".to_string()]).unwrap();
+        assert_eq!(vars["a"], "1");
+        assert_eq!(vars["b"], "x=y");
+        assert!(parse_vars(&["bad".to_string()]).is_err());
+    }
+
+    #[test]
+    fn unified_diff_marks_changed_lines() {
+        let diff = unified_diff("ci.yml", "a\nb\nc\n", "a\nB\nc\nd\n");
+        assert!(diff.starts_with("--- a/ci.yml\n+++ b/ci.yml\n@@ -1,3 +1,4 @@\n"));
+        assert!(diff.contains(" a\n"));
+        assert!(diff.contains("-b\n"));
+        assert!(diff.contains("+B\n"));
+        assert!(diff.contains("+d\n"));
+    }
+
+    #[test]
+    fn expands_file_and_env_values() {
+        let dir = std::env::temp_dir().join("sw-template-vars-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let header = dir.join("header.txt");
+        std::fs::write(&header, "Copyright Example Corp\n").unwrap();
+        std::env::set_var("SW_TEMPLATE_TEST_AUTHOR", "ada");
+
+        let vars = parse_vars(&[
+            format!("license=@{}", header.display()),
+            "author=env:SW_TEMPLATE_TEST_AUTHOR".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(vars["license"], "Copyright Example Corp");
+        assert_eq!(vars["author"], "ada");
+        assert!(parse_vars(&["x=@/no/such/file".to_string()]).is_err());
+        assert!(parse_vars(&["x=env:SW_TEMPLATE_TEST_UNSET".to_string()]).is_err());
+    }
+}
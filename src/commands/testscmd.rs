@@ -0,0 +1,263 @@
+//! `sw tests flaky` — find intermittently failing tests across a pile of
+//! JUnit reports and ask the model why they flake.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use crate::app::AppContext;
+use crate::cli::TestsFlakyArgs;
+use crate::llm::ChatMessage;
+
+/// Lines of test source quoted around each flaky test's definition.
+const SOURCE_CONTEXT_LINES: usize = 40;
+
+/// One `<testcase>` outcome from a JUnit report.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CaseOutcome {
+    /// `classname::name`, or just the name when no classname is set.
+    pub id: String,
+    pub failed: bool,
+    pub skipped: bool,
+}
+
+/// The value of `key="..."` inside an opening tag's attribute list. The
+/// leading space keeps `name` from matching inside `classname`.
+fn attr<'a>(attrs: &'a str, key: &str) -> Option<&'a str> {
+    let needle = format!(" {key}=\"");
+    let start = attrs.find(&needle)? + needle.len();
+    let end = attrs[start..].find('"')?;
+    Some(&attrs[start..start + end])
+}
+
+/// Pull the testcase outcomes out of one JUnit XML document. JUnit is
+/// flat enough that scanning the tags directly beats pulling in an XML
+/// parser for three attributes.
+pub fn parse_junit(xml: &str) -> Vec<CaseOutcome> {
+    let mut cases = Vec::new();
+    let mut rest = xml;
+    while let Some(open) = rest.find("<testcase") {
+        rest = &rest[open + "<testcase".len()..];
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let attrs = &rest[..tag_end];
+        let self_closing = attrs.trim_end().ends_with('/');
+        let body = if self_closing {
+            ""
+        } else {
+            let body = &rest[tag_end + 1..];
+            let end = body
+                .find("</testcase>")
+                .or_else(|| body.find("<testcase"))
+                .unwrap_or(body.len());
+            &body[..end]
+        };
+        let Some(name) = attr(attrs, "name") else {
+            continue;
+        };
+        let id = match attr(attrs, "classname").filter(|c| !c.is_empty()) {
+            Some(class) => format!("{class}::{name}"),
+            None => name.to_string(),
+        };
+        cases.push(CaseOutcome {
+            id,
+            failed: body.contains("<failure") || body.contains("<error"),
+            skipped: body.contains("<skipped"),
+        });
+    }
+    cases
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Tally {
+    pub runs: u64,
+    pub failures: u64,
+}
+
+/// Fold outcomes into per-test run/failure counts; skips don't count as
+/// runs.
+pub fn tally(outcomes: impl IntoIterator<Item = CaseOutcome>) -> BTreeMap<String, Tally> {
+    let mut tallies: BTreeMap<String, Tally> = BTreeMap::new();
+    for case in outcomes {
+        if case.skipped {
+            continue;
+        }
+        let entry = tallies.entry(case.id).or_default();
+        entry.runs += 1;
+        if case.failed {
+            entry.failures += 1;
+        }
+    }
+    tallies
+}
+
+#[derive(Serialize)]
+struct FlakyTest {
+    id: String,
+    runs: u64,
+    failures: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TestsFlakyOutput {
+    reports: usize,
+    tests: usize,
+    flaky: Vec<FlakyTest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    analysis: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+}
+
+/// The defining file and a source window for a test, via the symbol
+/// index the files analysis machinery already maintains.
+fn test_source(id: &str, ctx: &AppContext) -> Option<(String, String)> {
+    let bare = id.rsplit("::").next().unwrap_or(id);
+    let hit = crate::analysis::find_symbol(&ctx.workspace, bare)
+        .ok()?
+        .into_iter()
+        .next()?;
+    let content = std::fs::read_to_string(&hit.path).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let start = hit.line.saturating_sub(1);
+    let end = (start + SOURCE_CONTEXT_LINES).min(lines.len());
+    Some((hit.path.display().to_string(), lines[start..end].join("\n")))
+}
+
+pub async fn cmd_tests_flaky(args: &TestsFlakyArgs, ctx: &AppContext) -> Result<()> {
+    let mut reports = Vec::new();
+    for pattern in &args.junit {
+        let paths = glob::glob(pattern).with_context(|| format!("invalid glob '{pattern}'"))?;
+        reports.extend(paths.filter_map(|p| p.ok()).filter(|p| p.is_file()));
+    }
+    reports.sort();
+    reports.dedup();
+    if reports.is_empty() {
+        bail!("no JUnit reports matched --junit");
+    }
+    ctx.render
+        .status(&format!("aggregating {} report(s)", reports.len()));
+
+    let mut outcomes = Vec::new();
+    for path in &reports {
+        let xml = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        outcomes.extend(parse_junit(&xml));
+    }
+    let tallies = tally(outcomes);
+
+    let mut flaky: Vec<FlakyTest> = tallies
+        .iter()
+        .filter(|(_, t)| t.runs >= args.min_runs && t.failures > 0 && t.failures < t.runs)
+        .map(|(id, t)| FlakyTest {
+            id: id.clone(),
+            runs: t.runs,
+            failures: t.failures,
+            file: None,
+        })
+        .collect();
+    flaky.sort_by(|a, b| b.failures.cmp(&a.failures).then(a.id.cmp(&b.id)));
+    flaky.truncate(args.top);
+
+    let mut analysis = None;
+    let mut model = None;
+    if flaky.is_empty() {
+        ctx.render.status("no intermittently failing tests found");
+    } else if !args.no_analysis {
+        let mut listing = String::new();
+        for test in &mut flaky {
+            listing.push_str(&format!(
+                "- `{}`: failed {} of {} runs\n",
+                test.id, test.failures, test.runs
+            ));
+            if let Some((file, source)) = test_source(&test.id, ctx) {
+                listing.push_str(&format!(
+                    "  Defined in `{file}`:\n```\n{}\n```\n",
+                    ctx.redact(&source)
+                ));
+                test.file = Some(file);
+            }
+        }
+        let messages = vec![
+            ChatMessage::system(
+                "You analyze flaky tests. From each test's failure rate and \
+                 source, name the most likely cause — timing, ordering, \
+                 shared state, network, randomness — and how to make it \
+                 deterministic. Be concrete and brief.",
+            ),
+            ChatMessage::user(format!(
+                "These tests fail intermittently across CI runs:\n\n{listing}"
+            )),
+        ];
+        let resp = ctx.complete(messages).await?;
+        analysis = Some(resp.content);
+        model = Some(resp.model);
+    }
+
+    let output = TestsFlakyOutput {
+        reports: reports.len(),
+        tests: tallies.len(),
+        flaky,
+        analysis,
+        model,
+    };
+    ctx.render.emit(&output, || match &output.analysis {
+        Some(a) => ctx.render.markdown(a),
+        None => output
+            .flaky
+            .iter()
+            .map(|t| format!("{}: {}/{} failed", t.id, t.failures, t.runs))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_junit_outcomes() {
+        let xml = r#"<testsuite>
+            <testcase classname="pkg.Suite" name="ok" time="0.1"/>
+            <testcase classname="pkg.Suite" name="bad">
+                <failure message="boom">trace</failure>
+            </testcase>
+            <testcase name="ignored"><skipped/></testcase>
+        </testsuite>"#;
+        let cases = parse_junit(xml);
+        assert_eq!(cases.len(), 3);
+        assert_eq!(cases[0].id, "pkg.Suite::ok");
+        assert!(!cases[0].failed);
+        assert!(cases[1].failed);
+        assert_eq!(cases[2].id, "ignored");
+        assert!(cases[2].skipped);
+    }
+
+    #[test]
+    fn intermittent_failures_are_flaky_constant_ones_are_not() {
+        let runs = |id: &str, failed: &[bool]| -> Vec<CaseOutcome> {
+            failed
+                .iter()
+                .map(|f| CaseOutcome {
+                    id: id.to_string(),
+                    failed: *f,
+                    skipped: false,
+                })
+                .collect()
+        };
+        let mut outcomes = runs("sometimes", &[true, false, true]);
+        outcomes.extend(runs("always", &[true, true, true]));
+        outcomes.extend(runs("never", &[false, false, false]));
+        let tallies = tally(outcomes);
+        let flaky = |t: &Tally| t.failures > 0 && t.failures < t.runs;
+        assert!(flaky(&tallies["sometimes"]));
+        assert!(!flaky(&tallies["always"]));
+        assert!(!flaky(&tallies["never"]));
+    }
+}
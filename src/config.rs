@@ -0,0 +1,720 @@
+//! Configuration loading and persistence.
+//!
+//! Config lives at `~/.config/sw-assist/config.toml` and is organised as
+//! named profiles, each selecting a provider, model, and credentials.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Schema version written by `save`. Files carrying an older version (or
+/// none, which reads as 1) are upgraded by [`apply_migrations`] on load,
+/// with a backup of the prior file kept next to it.
+pub const CONFIG_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Config schema version; see [`CONFIG_VERSION`].
+    pub version: u32,
+    pub default_profile: String,
+    pub profiles: BTreeMap<String, Profile>,
+    /// Per-model capability overrides (context window etc.) keyed by model id.
+    pub model_caps: BTreeMap<String, ModelCapsOverride>,
+    /// Per-provider rate limits enforced by the shared token-bucket limiter.
+    pub limits: BTreeMap<String, RateLimit>,
+    /// Self-hosted OpenAI-compatible endpoints keyed by provider name
+    /// (`[providers.vllm]`), usable anywhere a built-in provider is.
+    pub providers: BTreeMap<String, CustomProvider>,
+    /// Named system prompts selectable with `--persona` (e.g. `persona.reviewer`).
+    #[serde(rename = "persona")]
+    pub personas: BTreeMap<String, String>,
+    /// Secret-redaction settings for prompt context.
+    pub redact: RedactConfig,
+    /// Commit message conventions enforced by `commit-msg`.
+    pub commit: CommitStyle,
+    /// Custom review rubrics selectable with `review --focus`
+    /// (`[review_focus]` entries, keyed by focus name).
+    pub review_focus: BTreeMap<String, String>,
+    /// Path-based review routing (`[[review_routes]]` entries), applied
+    /// by `review --staged`: changed files are grouped by the first
+    /// matching rule and each group is reviewed under that rule's focus
+    /// and persona. An explicit `--focus` bypasses routing.
+    pub review_routes: Vec<ReviewRoute>,
+    /// Style-guide snippets appended to code-writing prompts
+    /// (`[style_hints]` entries, keyed by language name or extension,
+    /// e.g. `rust = "use anyhow for errors"` or `tsx = "prefer
+    /// functional components"`).
+    pub style_hints: BTreeMap<String, String>,
+    /// Default flags per subcommand (`[defaults.ask] stream = true`),
+    /// merged into the command line before parsing; explicit flags win.
+    pub defaults: BTreeMap<String, toml::Value>,
+    /// Allow `--copy` to place artifacts on the system clipboard.
+    pub clipboard: bool,
+    /// Record local invocation stats for `sw stats` (never uploaded).
+    pub stats: bool,
+    /// Annotate files written by generate/diff-apply with provenance
+    /// trailers and log them to `.sw/provenance.jsonl`.
+    pub provenance: bool,
+    /// Session records larger than this many bytes are externalized: the
+    /// full body moves to a side file and the session keeps a head stub,
+    /// so one huge output cannot blow up every future context. 0 disables.
+    pub session_max_record_bytes: usize,
+    /// Store checkpoints under `.sw-checkpoints/` in the workspace
+    /// instead of the per-project directory under the OS data dir.
+    pub checkpoints_in_repo: bool,
+    /// Let diff apply and generate write outside the workspace root.
+    /// Off by default: hallucinated or malicious diffs can target
+    /// absolute paths and `../` escapes.
+    pub allow_outside_workspace: bool,
+    /// Where `session publish` uploads rendered transcripts.
+    pub publish: PublishConfig,
+    /// Optional language-server integration (`[lsp]`): commands that
+    /// gather symbol context query the configured server for hover and
+    /// reference facts instead of relying only on regex heuristics.
+    pub lsp: LspConfig,
+    /// What `report nightly` runs and where it writes (`[report]`).
+    pub report: ReportConfig,
+    /// What to do when a prompt exceeds the model's context window.
+    pub context_overflow: ContextOverflowPolicy,
+    /// Large-context model used by the `fallback` overflow policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback_model: Option<String>,
+}
+
+/// Preflight policy for prompts that exceed the model's context window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextOverflowPolicy {
+    /// Fail with a `context_length_exceeded` code before sending.
+    #[default]
+    Error,
+    /// Switch to `fallback_model` when it has a larger window.
+    Fallback,
+    /// Let chunking commands split the input; others still error.
+    Chunk,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let mut profiles = BTreeMap::new();
+        profiles.insert(DEFAULT_PROFILE.to_string(), Profile::default());
+        Self {
+            version: CONFIG_VERSION,
+            default_profile: DEFAULT_PROFILE.to_string(),
+            profiles,
+            model_caps: BTreeMap::new(),
+            limits: BTreeMap::new(),
+            providers: BTreeMap::new(),
+            personas: BTreeMap::new(),
+            redact: RedactConfig::default(),
+            commit: CommitStyle::default(),
+            review_focus: BTreeMap::new(),
+            review_routes: Vec::new(),
+            style_hints: BTreeMap::new(),
+            defaults: BTreeMap::new(),
+            clipboard: true,
+            stats: true,
+            provenance: false,
+            session_max_record_bytes: 16 * 1024,
+            checkpoints_in_repo: false,
+            allow_outside_workspace: false,
+            publish: PublishConfig::default(),
+            lsp: LspConfig::default(),
+            report: ReportConfig::default(),
+            context_overflow: ContextOverflowPolicy::default(),
+            fallback_model: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactConfig {
+    /// Additional regex patterns masked alongside the built-in rules.
+    pub patterns: Vec<String>,
+    /// Additional path globs (gitignore-style) whose files are blocked
+    /// from prompts entirely, alongside the built-in denylist.
+    pub deny_paths: Vec<String>,
+}
+
+/// Settings for the unattended `report nightly` run (`[report]`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportConfig {
+    /// Sections to generate: `security`, `todos`, `deps`, `overview`.
+    pub sections: Vec<String>,
+    /// Directory dated reports are written into, workspace-relative.
+    pub dir: String,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            sections: ["security", "todos", "deps", "overview"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            dir: ".sw/reports".to_string(),
+        }
+    }
+}
+
+/// Destination for `session publish` (`[publish]` in config).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublishConfig {
+    /// `gist` (GitHub, default) or `paste`.
+    pub service: String,
+    /// Environment variable holding the gist API token.
+    pub token_env: String,
+    /// Create secret gists rather than public ones.
+    pub secret: bool,
+    /// Endpoint of a paste service that accepts the transcript as a raw
+    /// POST body and answers with the paste URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paste_url: Option<String>,
+}
+
+impl Default for PublishConfig {
+    fn default() -> Self {
+        Self {
+            service: "gist".to_string(),
+            token_env: "GITHUB_TOKEN".to_string(),
+            secret: true,
+            paste_url: None,
+        }
+    }
+}
+
+/// Language-server settings (`[lsp]` in config).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LspConfig {
+    /// Master switch; off by default because it spawns external servers.
+    pub enabled: bool,
+    /// Server command per language name as reported by the analyzer
+    /// (e.g. `Rust = "rust-analyzer"`), split on whitespace.
+    pub servers: BTreeMap<String, String>,
+    /// Seconds to wait for any single server response.
+    pub timeout_secs: u64,
+}
+
+impl Default for LspConfig {
+    fn default() -> Self {
+        let mut servers = BTreeMap::new();
+        servers.insert("Rust".to_string(), "rust-analyzer".to_string());
+        servers.insert(
+            "Python".to_string(),
+            "pyright-langserver --stdio".to_string(),
+        );
+        servers.insert(
+            "TypeScript".to_string(),
+            "typescript-language-server --stdio".to_string(),
+        );
+        Self {
+            enabled: false,
+            servers,
+            timeout_secs: 10,
+        }
+    }
+}
+
+/// One `[[review_routes]]` rule: which changed paths it covers and how
+/// they should be reviewed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReviewRoute {
+    /// Glob matched against paths in the staged diff (e.g. `crypto/**`).
+    pub paths: String,
+    /// `--focus` preset or `[review_focus]` rubric for matching files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<String>,
+    /// Persona whose system prompt fronts the rubric.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persona: Option<String>,
+    /// Exclude matching files from review entirely (e.g. `docs/**`).
+    pub skip: bool,
+}
+
+/// Conventional-commit style rules for generated and linted messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitStyle {
+    /// Allowed commit types.
+    pub types: Vec<String>,
+    /// Require a `(scope)` in the subject.
+    pub require_scope: bool,
+    /// Maximum subject line length in characters.
+    pub subject_limit: usize,
+    /// Prefix the subject with a gitmoji shortcode.
+    pub gitmoji: bool,
+    /// Language the message should be written in (default English).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+impl Default for CommitStyle {
+    fn default() -> Self {
+        Self {
+            types: [
+                "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore",
+                "revert",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            require_scope: false,
+            subject_limit: 72,
+            gitmoji: false,
+            language: None,
+        }
+    }
+}
+
+/// A config-defined OpenAI-compatible endpoint (vLLM, TGI, llama.cpp…).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomProvider {
+    pub api_base: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+}
+
+impl CustomProvider {
+    pub fn resolve_api_key(&self) -> Option<String> {
+        if let Some(k) = &self.api_key {
+            return Some(k.clone());
+        }
+        self.api_key_env
+            .as_ref()
+            .and_then(|v| std::env::var(v).ok())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub provider: String,
+    pub model: String,
+    /// Inline API key; prefer `api_key_env` so keys stay out of config files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// A pool of keys rotated round-robin, failing over on HTTP 429.
+    /// Takes precedence over `api_key` when non-empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub api_keys: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_base: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Reasoning effort for o-series / extended-thinking models
+    /// (`low`, `medium`, `high`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<String>,
+    /// OpenAI organization id, sent as the `OpenAI-Organization` header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    /// OpenAI project id, sent as the `OpenAI-Project` header.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// Anthropic beta flags, joined into one `anthropic-beta` header.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub betas: Vec<String>,
+    /// Arbitrary extra headers sent with every request
+    /// (`[profiles.<name>.headers]`), for gateways and proxies the
+    /// well-known fields above do not cover.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            provider: "openai".to_string(),
+            model: "gpt-4o-mini".to_string(),
+            api_key: None,
+            api_keys: Vec::new(),
+            api_key_env: None,
+            api_base: None,
+            temperature: None,
+            max_tokens: None,
+            reasoning_effort: None,
+            organization: None,
+            project: None,
+            betas: Vec::new(),
+            headers: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelCapsOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<usize>,
+    /// USD per million prompt tokens, used by the local stats recorder.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_mtok_prompt: Option<f64>,
+    /// USD per million completion tokens; defaults to the prompt price.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_per_mtok_completion: Option<f64>,
+    /// Default sampling temperature whenever this model is used; a
+    /// profile temperature still wins. Lets local models that need
+    /// special settings skip the flag on every call.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Default completion cap whenever this model is used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Stop sequences sent with every request to this model.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+}
+
+impl Config {
+    pub fn config_path() -> Result<PathBuf> {
+        let base = dirs::config_dir().context("cannot determine config directory")?;
+        Ok(base.join("sw-assist").join("config.toml"))
+    }
+
+    /// Directory for mutable data (sessions, model cache, stats).
+    pub fn data_dir() -> Result<PathBuf> {
+        let base = dirs::data_dir().context("cannot determine data directory")?;
+        Ok(base.join("sw-assist"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::config_path()?;
+        let mut value = if path.exists() {
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read config at {}", path.display()))?;
+            let mut value = raw
+                .parse::<toml::Value>()
+                .with_context(|| format!("invalid config at {}", path.display()))?;
+            Self::migrate_file(&mut value, &path)?;
+            value
+        } else {
+            toml::Value::Table(toml::map::Map::new())
+        };
+        // The nearest `.sw.toml` up from the current directory overlays
+        // the user config, so a repository can pin its own settings.
+        if let Some(project) = Self::project_config_path() {
+            let raw = std::fs::read_to_string(&project)
+                .with_context(|| format!("failed to read {}", project.display()))?;
+            let overlay = raw
+                .parse::<toml::Value>()
+                .with_context(|| format!("invalid project config at {}", project.display()))?;
+            merge_toml(&mut value, overlay);
+        }
+        let cfg: Config = value
+            .try_into()
+            .with_context(|| format!("invalid config at {}", path.display()))?;
+        Ok(cfg)
+    }
+
+    /// Upgrade an older on-disk layout in place: back up the prior file,
+    /// run the migrations, and persist the current schema.
+    fn migrate_file(value: &mut toml::Value, path: &std::path::Path) -> Result<()> {
+        let table = value.as_table_mut().context("config root is not a table")?;
+        let version = table
+            .get("version")
+            .and_then(toml::Value::as_integer)
+            .unwrap_or(1) as u32;
+        if version >= CONFIG_VERSION {
+            return Ok(());
+        }
+        let backup = path.with_extension(format!("toml.v{version}.bak"));
+        std::fs::copy(path, &backup)
+            .with_context(|| format!("failed to back up config to {}", backup.display()))?;
+        apply_migrations(table, version);
+        std::fs::write(path, toml::to_string_pretty(value)?)
+            .with_context(|| format!("failed to write migrated config at {}", path.display()))?;
+        // The renderer does not exist yet at config-load time.
+        eprintln!(
+            "config migrated from v{version} to v{CONFIG_VERSION} (backup at {})",
+            backup.display()
+        );
+        Ok(())
+    }
+
+    /// The project config file governing the current directory, if any.
+    fn project_config_path() -> Option<PathBuf> {
+        let mut dir = std::env::current_dir().ok()?;
+        loop {
+            let candidate = dir.join(".sw.toml");
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            if !dir.pop() {
+                return None;
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::config_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let raw = toml::to_string_pretty(self)?;
+        std::fs::write(&path, raw)
+            .with_context(|| format!("failed to write config at {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        self.profiles
+            .get(name)
+            .with_context(|| format!("profile '{name}' not found; run `sw init` or check config"))
+    }
+
+    /// The `[style_hints]` snippet for a file, matched by extension first
+    /// and then by (lowercased) language name.
+    pub fn style_hint_for(&self, path: &std::path::Path) -> Option<&str> {
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            if let Some(hint) = self.style_hints.get(&ext.to_ascii_lowercase()) {
+                return Some(hint);
+            }
+        }
+        let language = crate::analysis::language_for_path(path).to_ascii_lowercase();
+        self.style_hints.get(&language).map(String::as_str)
+    }
+
+    /// Context window for a model, honouring `model_caps` overrides.
+    pub fn context_window_for(&self, model: &str) -> usize {
+        if let Some(caps) = self.model_caps.get(model) {
+            if let Some(cw) = caps.context_window {
+                return cw;
+            }
+        }
+        default_context_window(model)
+    }
+}
+
+/// Run every migration the file predates, in order, then stamp the
+/// current version. Each step upgrades one schema revision.
+fn apply_migrations(table: &mut toml::map::Map<String, toml::Value>, from_version: u32) {
+    if from_version < 2 {
+        migrate_v1_to_v2(table);
+    }
+    table.insert("version".to_string(), (CONFIG_VERSION as i64).into());
+}
+
+/// v1 → v2: the `[profile.*]` table was renamed `[profiles.*]`, and the
+/// flat `redact_patterns` list moved under `[redact] patterns`.
+fn migrate_v1_to_v2(table: &mut toml::map::Map<String, toml::Value>) {
+    if let Some(profiles) = table.remove("profile") {
+        table.entry("profiles").or_insert(profiles);
+    }
+    if let Some(patterns) = table.remove("redact_patterns") {
+        let redact = table
+            .entry("redact")
+            .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+        if let Some(redact) = redact.as_table_mut() {
+            redact.entry("patterns").or_insert(patterns);
+        }
+    }
+}
+
+/// Deep-merge `overlay` into `base`: tables merge key by key, everything
+/// else (scalars, arrays) is replaced outright.
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(slot) => merge_toml(slot, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (slot, value) => *slot = value,
+    }
+}
+
+/// Conservative built-in context window guesses for well-known model families.
+fn default_context_window(model: &str) -> usize {
+    let m = model.to_ascii_lowercase();
+    if m.contains("gpt-4o") || m.contains("gpt-4-turbo") || m.starts_with("o1") {
+        128_000
+    } else if m.contains("claude") {
+        200_000
+    } else if m.contains("gpt-3.5") {
+        16_000
+    } else {
+        8_000
+    }
+}
+
+impl Profile {
+    /// Resolve the API key: inline value, then configured env var, then the
+    /// provider's conventional environment variable.
+    pub fn resolve_api_key(&self) -> Option<String> {
+        if let Some(k) = self.api_keys.first() {
+            return Some(k.clone());
+        }
+        if let Some(k) = &self.api_key {
+            return Some(k.clone());
+        }
+        if let Some(var) = &self.api_key_env {
+            if let Ok(v) = std::env::var(var) {
+                return Some(v);
+            }
+        }
+        let conventional = match self.provider.as_str() {
+            "openai" => "OPENAI_API_KEY",
+            "anthropic" => "ANTHROPIC_API_KEY",
+            "ollama" => return Some(String::new()),
+            _ => return None,
+        };
+        std::env::var(conventional).ok()
+    }
+
+    /// Every usable key: the `api_keys` pool when configured, otherwise
+    /// the single resolved key.
+    pub fn resolve_api_keys(&self) -> Vec<String> {
+        if !self.api_keys.is_empty() {
+            return self.api_keys.clone();
+        }
+        self.resolve_api_key().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_has_default_profile() {
+        let cfg = Config::default();
+        assert!(cfg.profiles.contains_key(DEFAULT_PROFILE));
+        assert_eq!(cfg.default_profile, DEFAULT_PROFILE);
+    }
+
+    #[test]
+    fn parses_custom_providers() {
+        let cfg: Config = toml::from_str(
+            "[providers.vllm]\napi_base = \"http://gpu-box:8000/v1\"\napi_key_env = \"VLLM_KEY\"\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.providers["vllm"].api_base, "http://gpu-box:8000/v1");
+        assert_eq!(
+            cfg.providers["vllm"].api_key_env.as_deref(),
+            Some("VLLM_KEY")
+        );
+    }
+
+    #[test]
+    fn parses_context_overflow_policy() {
+        let cfg: Config =
+            toml::from_str("context_overflow = \"fallback\"\nfallback_model = \"gpt-4o\"\n")
+                .unwrap();
+        assert_eq!(cfg.context_overflow, ContextOverflowPolicy::Fallback);
+        assert_eq!(cfg.fallback_model.as_deref(), Some("gpt-4o"));
+        assert_eq!(
+            Config::default().context_overflow,
+            ContextOverflowPolicy::Error
+        );
+    }
+
+    #[test]
+    fn migrates_v1_layout_to_current() {
+        let mut table = "redact_patterns = [\"secret-\\\\w+\"]\n\n\
+                         [profile.default]\nprovider = \"openai\"\nmodel = \"gpt-4o\"\n"
+            .parse::<toml::Value>()
+            .unwrap()
+            .as_table()
+            .cloned()
+            .unwrap();
+        apply_migrations(&mut table, 1);
+        let cfg: Config = toml::Value::Table(table).try_into().unwrap();
+        assert_eq!(cfg.version, CONFIG_VERSION);
+        assert_eq!(cfg.profiles["default"].model, "gpt-4o");
+        assert_eq!(cfg.redact.patterns, vec!["secret-\\w+".to_string()]);
+    }
+
+    #[test]
+    fn project_overlay_merges_tables_and_replaces_scalars() {
+        let mut base: toml::Value =
+            "stats = true\n[profiles.default]\nprovider = \"openai\"\nmodel = \"gpt-4o-mini\"\n"
+                .parse()
+                .unwrap();
+        let overlay: toml::Value = "[profiles.default]\nmodel = \"llama3.1\"\n"
+            .parse()
+            .unwrap();
+        merge_toml(&mut base, overlay);
+        let cfg: Config = base.try_into().unwrap();
+        assert_eq!(cfg.profiles["default"].model, "llama3.1");
+        assert_eq!(cfg.profiles["default"].provider, "openai");
+        assert!(cfg.stats);
+    }
+
+    #[test]
+    fn parses_review_routes() {
+        let cfg: Config = toml::from_str(
+            "[[review_routes]]\npaths = \"crypto/**\"\nfocus = \"security\"\n\n\
+             [[review_routes]]\npaths = \"docs/**\"\nskip = true\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.review_routes.len(), 2);
+        assert_eq!(cfg.review_routes[0].focus.as_deref(), Some("security"));
+        assert!(!cfg.review_routes[0].skip);
+        assert!(cfg.review_routes[1].skip);
+    }
+
+    #[test]
+    fn style_hints_match_extension_then_language() {
+        let cfg: Config = toml::from_str(
+            "[style_hints]\nrust = \"use anyhow for errors\"\ntsx = \"prefer functional components\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.style_hint_for(std::path::Path::new("src/lib.rs")),
+            Some("use anyhow for errors")
+        );
+        // Extension keys win over the broader language name.
+        assert_eq!(
+            cfg.style_hint_for(std::path::Path::new("App.tsx")),
+            Some("prefer functional components")
+        );
+        assert_eq!(cfg.style_hint_for(std::path::Path::new("main.go")), None);
+    }
+
+    #[test]
+    fn model_caps_override_wins() {
+        let mut cfg = Config::default();
+        cfg.model_caps.insert(
+            "tiny".into(),
+            ModelCapsOverride {
+                context_window: Some(2048),
+                ..Default::default()
+            },
+        );
+        assert_eq!(cfg.context_window_for("tiny"), 2048);
+        assert_eq!(cfg.context_window_for("claude-3-haiku"), 200_000);
+    }
+}
@@ -0,0 +1,181 @@
+//! Prompt assembly: token estimation and history truncation.
+
+use crate::llm::{ChatMessage, Role};
+use crate::session::SessionRecord;
+
+/// Rough token estimate: ~4 characters per token works well enough for
+/// budgeting across the models we target.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count() / 4 + 1
+}
+
+/// Tokens reserved for the model's reply when budgeting prompt size.
+const OUTPUT_RESERVE_TOKENS: usize = 2048;
+
+/// Build the message list for a request: optional system prompt, as much
+/// history as fits the context window (dropping oldest turns first), then
+/// the new user prompt. The result is normalized for `provider`'s
+/// message-shape rules.
+pub fn build_messages_with_truncation(
+    system: Option<&str>,
+    history: &[SessionRecord],
+    user_prompt: &str,
+    context_window: usize,
+    provider: &str,
+) -> Vec<ChatMessage> {
+    let budget = context_window.saturating_sub(OUTPUT_RESERVE_TOKENS);
+    let mut used = estimate_tokens(user_prompt);
+    if let Some(s) = system {
+        used += estimate_tokens(s);
+    }
+
+    // Walk history newest-first, keeping turns while they fit.
+    let mut kept: Vec<&SessionRecord> = Vec::new();
+    for rec in history.iter().rev() {
+        let cost = estimate_tokens(&rec.content);
+        if used + cost > budget {
+            break;
+        }
+        used += cost;
+        kept.push(rec);
+    }
+    kept.reverse();
+
+    let mut messages = Vec::with_capacity(kept.len() + 2);
+    if let Some(s) = system {
+        messages.push(ChatMessage::system(s));
+    }
+    for rec in kept {
+        // Providers reject bare tool messages without call ids, so replay
+        // tool records as labelled user text; the structure stays in the
+        // session file.
+        if rec.role == Role::Tool {
+            let name = rec.tool.as_ref().map_or("tool", |t| t.name.as_str());
+            messages.push(ChatMessage::user(format!("[{name}] {}", rec.content)));
+            continue;
+        }
+        messages.push(ChatMessage {
+            role: rec.role,
+            content: rec.content.clone(),
+        });
+    }
+    messages.push(ChatMessage::user(user_prompt));
+    normalize_for_provider(messages, provider)
+}
+
+/// Reshape a message list for a provider's constraints. Anthropic's API
+/// requires strict user/assistant alternation with system text in a
+/// single leading message; OpenAI-style endpoints accept the list as-is.
+pub fn normalize_for_provider(messages: Vec<ChatMessage>, provider: &str) -> Vec<ChatMessage> {
+    if provider != "anthropic" {
+        return messages;
+    }
+    let mut system_parts: Vec<String> = Vec::new();
+    let mut out: Vec<ChatMessage> = Vec::new();
+    for m in messages {
+        match m.role {
+            Role::System => system_parts.push(m.content),
+            role => match out.last_mut() {
+                // Truncation or interrupted sessions can leave consecutive
+                // same-role turns; merge them to keep alternation valid.
+                Some(last) if last.role == role => {
+                    last.content.push_str("\n\n");
+                    last.content.push_str(&m.content);
+                }
+                _ => out.push(m),
+            },
+        }
+    }
+    // The conversation must open with a user turn.
+    if out.first().is_some_and(|m| m.role == Role::Assistant) {
+        out.remove(0);
+    }
+    if !system_parts.is_empty() {
+        out.insert(0, ChatMessage::system(system_parts.join("\n\n")));
+    }
+    out
+}
+
+/// Split text into chunks of roughly `max_tokens` each, breaking on line
+/// boundaries so chunks stay syntactically coherent.
+pub fn chunk_by_tokens(text: &str, max_tokens: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_tokens = 0usize;
+    for line in text.split_inclusive('\n') {
+        let cost = estimate_tokens(line);
+        if current_tokens + cost > max_tokens && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        current.push_str(line);
+        current_tokens += cost;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::Role;
+    use chrono::Utc;
+
+    fn rec(role: Role, content: &str) -> SessionRecord {
+        SessionRecord {
+            role,
+            content: content.to_string(),
+            timestamp: Utc::now(),
+            model: None,
+            tool: None,
+        }
+    }
+
+    #[test]
+    fn truncation_drops_oldest_first() {
+        let history = vec![
+            rec(Role::User, &"x".repeat(4000)),
+            rec(Role::Assistant, "short answer"),
+            rec(Role::User, "recent question"),
+        ];
+        // Budget small enough that the 1000-token first record cannot fit.
+        let messages = build_messages_with_truncation(None, &history, "now", 2048 + 600, "openai");
+        assert!(messages.len() < history.len() + 1);
+        assert_eq!(messages.last().unwrap().content, "now");
+        assert!(messages.iter().all(|m| m.content != history[0].content));
+    }
+
+    #[test]
+    fn anthropic_normalization_merges_and_hoists() {
+        let messages = vec![
+            ChatMessage::system("be brief"),
+            ChatMessage {
+                role: Role::Assistant,
+                content: "orphaned".into(),
+            },
+            ChatMessage::user("first"),
+            ChatMessage::user("second"),
+            ChatMessage::system("also terse"),
+        ];
+        let out = normalize_for_provider(messages.clone(), "anthropic");
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].role, Role::System);
+        assert_eq!(out[0].content, "be brief\n\nalso terse");
+        assert_eq!(out[1].content, "first\n\nsecond");
+        // OpenAI-style providers keep the list untouched.
+        assert_eq!(normalize_for_provider(messages, "openai").len(), 5);
+    }
+
+    #[test]
+    fn chunking_respects_line_boundaries() {
+        let text = "line one\nline two\nline three\n";
+        let chunks = chunk_by_tokens(text, 3);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), text);
+        for c in &chunks {
+            assert!(c.ends_with('\n'));
+        }
+    }
+}
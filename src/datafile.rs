@@ -0,0 +1,352 @@
+//! Schema-and-sample profiles for tabular data files.
+//!
+//! When a prompt input is a CSV/TSV export or a JSON(-lines) record dump,
+//! sending the raw bytes wastes the context window on repetition. These
+//! helpers infer a column schema (name plus a coarse type) and pick a few
+//! sample rows, so a multi-megabyte file costs a few hundred prompt
+//! tokens. Real columnar formats like Parquet need a binary reader and
+//! are out of scope; those files go through the normal raw path.
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+/// Rows scanned for type inference; more than are ever shown, so a
+/// numeric column with a late stray string still reads as `string`.
+const INFER_ROWS: usize = 200;
+
+/// One inferred column.
+pub struct Column {
+    pub name: String,
+    pub kind: &'static str,
+}
+
+/// Schema plus sampled rows for one data file.
+pub struct DataProfile {
+    pub format: &'static str,
+    pub rows: usize,
+    pub columns: Vec<Column>,
+    pub sample: Vec<Vec<String>>,
+}
+
+impl DataProfile {
+    /// Render the profile as the prompt block that replaces the raw file.
+    pub fn prompt_block(&self, path: &str) -> String {
+        let mut s = format!(
+            "Data file `{path}` ({}, {} row(s), {} column(s)). Schema:\n",
+            self.format,
+            self.rows,
+            self.columns.len()
+        );
+        for col in &self.columns {
+            s.push_str(&format!("- {}: {}\n", col.name, col.kind));
+        }
+        s.push_str(&format!("\nSample (first {} row(s)):\n", self.sample.len()));
+        let header: Vec<&str> = self.columns.iter().map(|c| c.name.as_str()).collect();
+        s.push_str(&header.join(","));
+        s.push('\n');
+        for row in &self.sample {
+            s.push_str(&row.join(","));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+/// The coarse type of one textual value.
+fn classify(value: &str) -> &'static str {
+    let t = value.trim();
+    if t.is_empty() {
+        return "empty";
+    }
+    if t.eq_ignore_ascii_case("true") || t.eq_ignore_ascii_case("false") {
+        return "boolean";
+    }
+    if t.parse::<i64>().is_ok() {
+        return "integer";
+    }
+    if t.parse::<f64>().is_ok() {
+        return "float";
+    }
+    if looks_like_date(t) {
+        return "date";
+    }
+    "string"
+}
+
+/// `YYYY-MM-DD` at the front is enough; timestamps count as dates too.
+fn looks_like_date(t: &str) -> bool {
+    let b = t.as_bytes();
+    b.len() >= 10
+        && b[..4].iter().all(u8::is_ascii_digit)
+        && b[4] == b'-'
+        && b[5..7].iter().all(u8::is_ascii_digit)
+        && b[7] == b'-'
+        && b[8..10].iter().all(u8::is_ascii_digit)
+}
+
+/// Merge the types of two values in the same column.
+fn unify(a: &'static str, b: &'static str) -> &'static str {
+    match (a, b) {
+        _ if a == b => a,
+        ("empty", other) | (other, "empty") => other,
+        ("integer", "float") | ("float", "integer") => "float",
+        _ => "string",
+    }
+}
+
+/// Split one CSV record, honouring double-quoted fields.
+fn split_record(line: &str, sep: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            if c == '"' {
+                // A doubled quote inside a quoted field is a literal one.
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' && field.is_empty() {
+            quoted = true;
+        } else if c == sep {
+            fields.push(std::mem::take(&mut field));
+        } else {
+            field.push(c);
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Build a profile from header + rows, applying column selection.
+fn build(
+    format: &'static str,
+    names: Vec<String>,
+    rows: Vec<Vec<String>>,
+    total: usize,
+    sample: usize,
+    columns: Option<&str>,
+) -> Result<DataProfile> {
+    let keep: Vec<usize> = match columns {
+        Some(spec) => {
+            let mut keep = Vec::new();
+            for want in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                match names.iter().position(|n| n == want) {
+                    Some(i) => keep.push(i),
+                    None => bail!("unknown column '{want}' (file has: {})", names.join(", ")),
+                }
+            }
+            keep
+        }
+        None => (0..names.len()).collect(),
+    };
+    let mut cols: Vec<Column> = keep
+        .iter()
+        .map(|&i| Column {
+            name: names[i].clone(),
+            kind: "empty",
+        })
+        .collect();
+    for row in rows.iter().take(INFER_ROWS) {
+        for (c, &i) in keep.iter().enumerate() {
+            let value = row.get(i).map(String::as_str).unwrap_or("");
+            cols[c].kind = unify(cols[c].kind, classify(value));
+        }
+    }
+    for col in &mut cols {
+        // An entirely empty column has no evidence either way.
+        if col.kind == "empty" {
+            col.kind = "string";
+        }
+    }
+    let sample_rows = rows
+        .iter()
+        .take(sample.max(1))
+        .map(|row| {
+            keep.iter()
+                .map(|&i| row.get(i).cloned().unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    Ok(DataProfile {
+        format,
+        rows: total,
+        columns: cols,
+        sample: sample_rows,
+    })
+}
+
+/// One JSON value as a sample cell.
+fn cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Header + rows from a slice of JSON objects; column order is
+/// first-seen, so it follows the file rather than the alphabet.
+fn from_objects(
+    format: &'static str,
+    objects: &[&serde_json::Map<String, serde_json::Value>],
+    total: usize,
+    sample: usize,
+    columns: Option<&str>,
+) -> Result<DataProfile> {
+    let mut names: Vec<String> = Vec::new();
+    for obj in objects.iter().take(INFER_ROWS) {
+        for key in obj.keys() {
+            if !names.iter().any(|n| n == key) {
+                names.push(key.clone());
+            }
+        }
+    }
+    let rows: Vec<Vec<String>> = objects
+        .iter()
+        .take(INFER_ROWS)
+        .map(|obj| {
+            names
+                .iter()
+                .map(|n| obj.get(n).map(cell).unwrap_or_default())
+                .collect()
+        })
+        .collect();
+    build(format, names, rows, total, sample, columns)
+}
+
+/// Profile `content` when `path` looks like a data file. `Ok(None)` means
+/// "not tabular" — wrong extension, a JSON config object rather than an
+/// array of records, or no data rows — and the caller should fall back to
+/// the raw contents.
+pub fn profile(
+    path: &Path,
+    content: &str,
+    sample: usize,
+    columns: Option<&str>,
+) -> Result<Option<DataProfile>> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "csv" | "tsv" => {
+            let sep = if ext == "tsv" { '\t' } else { ',' };
+            let mut lines = content.lines().filter(|l| !l.trim().is_empty());
+            let Some(header) = lines.next() else {
+                return Ok(None);
+            };
+            let names = split_record(header, sep);
+            let rows: Vec<Vec<String>> = lines
+                .clone()
+                .take(INFER_ROWS)
+                .map(|l| split_record(l, sep))
+                .collect();
+            if rows.is_empty() {
+                return Ok(None);
+            }
+            let total = lines.count();
+            Ok(Some(build("csv", names, rows, total, sample, columns)?))
+        }
+        "jsonl" | "ndjson" => {
+            let values: Vec<serde_json::Value> = content
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .take(INFER_ROWS)
+                .map(serde_json::from_str)
+                .collect::<std::result::Result<_, _>>()
+                .unwrap_or_default();
+            let objects: Vec<_> = values.iter().filter_map(|v| v.as_object()).collect();
+            if objects.is_empty() || objects.len() != values.len() {
+                return Ok(None);
+            }
+            let total = content.lines().filter(|l| !l.trim().is_empty()).count();
+            Ok(Some(from_objects(
+                "jsonl", &objects, total, sample, columns,
+            )?))
+        }
+        "json" => {
+            let Ok(serde_json::Value::Array(items)) = serde_json::from_str(content) else {
+                return Ok(None);
+            };
+            let objects: Vec<_> = items.iter().filter_map(|v| v.as_object()).collect();
+            if objects.is_empty() || objects.len() != items.len() {
+                return Ok(None);
+            }
+            Ok(Some(from_objects(
+                "json",
+                &objects,
+                items.len(),
+                sample,
+                columns,
+            )?))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_schema_is_inferred_and_columns_filter() {
+        let csv = "id,name,score,joined\n\
+                   1,\"Doe, Jane\",9.5,2024-01-02\n\
+                   2,Smith,7,2024-03-04\n";
+        let p = profile(Path::new("users.csv"), csv, 20, None)
+            .unwrap()
+            .unwrap();
+        let kinds: Vec<_> = p
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("id", "integer"),
+                ("name", "string"),
+                ("score", "float"),
+                ("joined", "date")
+            ]
+        );
+        assert_eq!(p.rows, 2);
+        assert_eq!(p.sample[0][1], "Doe, Jane");
+
+        let picked = profile(Path::new("users.csv"), csv, 20, Some("name,id"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(picked.columns.len(), 2);
+        assert_eq!(picked.columns[0].name, "name");
+        assert!(profile(Path::new("users.csv"), csv, 20, Some("nope")).is_err());
+    }
+
+    #[test]
+    fn only_tabular_json_is_profiled() {
+        let records = r#"[{"id": 1, "ok": true}, {"id": 2, "ok": false}]"#;
+        let p = profile(Path::new("a.json"), records, 1, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.rows, 2);
+        assert_eq!(p.sample.len(), 1);
+        assert_eq!(p.columns[1].kind, "boolean");
+
+        let config = r#"{"name": "sw", "debug": false}"#;
+        assert!(profile(Path::new("b.json"), config, 1, None)
+            .unwrap()
+            .is_none());
+        assert!(profile(Path::new("c.rs"), records, 1, None)
+            .unwrap()
+            .is_none());
+    }
+}
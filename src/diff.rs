@@ -0,0 +1,246 @@
+//! Unified diff parsing and application.
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub old_path: Option<PathBuf>,
+    pub new_path: Option<PathBuf>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileDiff {
+    /// The path this diff writes to.
+    pub fn target_path(&self) -> Option<&PathBuf> {
+        self.new_path.as_ref().or(self.old_path.as_ref())
+    }
+
+    pub fn is_deletion(&self) -> bool {
+        self.new_path.is_none() && self.old_path.is_some()
+    }
+
+    pub fn is_creation(&self) -> bool {
+        self.old_path.is_none() && self.new_path.is_some()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    /// Each line tagged with ' ', '+', or '-'.
+    pub lines: Vec<(char, String)>,
+}
+
+impl Hunk {
+    /// Render the `@@` header, used in diagnostics.
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_lines, self.new_start, self.new_lines
+        )
+    }
+}
+
+fn parse_diff_path(raw: &str) -> Option<PathBuf> {
+    let raw = raw.split('\t').next().unwrap_or(raw).trim();
+    if raw == "/dev/null" {
+        return None;
+    }
+    let stripped = raw
+        .strip_prefix("a/")
+        .or_else(|| raw.strip_prefix("b/"))
+        .unwrap_or(raw);
+    Some(PathBuf::from(stripped))
+}
+
+fn parse_hunk_header(line: &str) -> Result<(usize, usize, usize, usize)> {
+    // @@ -old_start,old_lines +new_start,new_lines @@
+    let inner = line
+        .trim_start_matches('@')
+        .trim_end_matches(|c| c != '@')
+        .trim_matches('@')
+        .trim();
+    let mut parts = inner.split_whitespace();
+    let parse_side = |s: Option<&str>, sign: char| -> Result<(usize, usize)> {
+        let s = s.and_then(|s| s.strip_prefix(sign)).unwrap_or("");
+        if s.is_empty() {
+            bail!("malformed hunk header: {line}");
+        }
+        match s.split_once(',') {
+            Some((a, b)) => Ok((a.parse()?, b.parse()?)),
+            None => Ok((s.parse()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = parse_side(parts.next(), '-')?;
+    let (new_start, new_lines) = parse_side(parts.next(), '+')?;
+    Ok((old_start, old_lines, new_start, new_lines))
+}
+
+/// Parse a unified diff into per-file diffs. Tolerates `diff --git` and
+/// `index` header lines and ignores anything outside file sections.
+pub fn parse_unified_diff(text: &str) -> Result<Vec<FileDiff>> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut current: Option<FileDiff> = None;
+
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("--- ") {
+            if let Some(f) = current.take() {
+                if !f.hunks.is_empty() {
+                    files.push(f);
+                }
+            }
+            current = Some(FileDiff {
+                old_path: parse_diff_path(rest),
+                new_path: None,
+                hunks: Vec::new(),
+            });
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            if let Some(f) = current.as_mut() {
+                f.new_path = parse_diff_path(rest);
+            }
+        } else if line.starts_with("@@") {
+            let Some(f) = current.as_mut() else {
+                bail!("hunk header before file header: {line}");
+            };
+            let (old_start, old_lines, new_start, new_lines) = parse_hunk_header(line)?;
+            f.hunks.push(Hunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                lines: Vec::new(),
+            });
+        } else if let Some(f) = current.as_mut() {
+            if let Some(hunk) = f.hunks.last_mut() {
+                let mut chars = line.chars();
+                match chars.next() {
+                    Some(tag @ (' ' | '+' | '-')) => {
+                        hunk.lines.push((tag, chars.collect()));
+                    }
+                    _ if line.starts_with('\\') => {} // "\ No newline at end of file"
+                    _ => {}
+                }
+            }
+        }
+    }
+    if let Some(f) = current.take() {
+        if !f.hunks.is_empty() {
+            files.push(f);
+        }
+    }
+    if files.is_empty() {
+        bail!("no file diffs found in input");
+    }
+    Ok(files)
+}
+
+/// Files a diff would write to, in order of appearance.
+pub fn parse_diff_target_files(text: &str) -> Result<Vec<PathBuf>> {
+    let files = parse_unified_diff(text)?;
+    Ok(files
+        .iter()
+        .filter_map(|f| f.target_path().cloned())
+        .collect())
+}
+
+/// Apply a single file's hunks to its current content, returning the new
+/// content. Fails when hunk context does not match.
+pub fn apply_file_diff(diff: &FileDiff, content: &str) -> Result<String> {
+    let old_lines: Vec<&str> = content.lines().collect();
+    let mut result: Vec<String> = Vec::with_capacity(old_lines.len());
+    let mut cursor = 0usize; // index into old_lines
+
+    for (i, hunk) in diff.hunks.iter().enumerate() {
+        let start = hunk.old_start.saturating_sub(1);
+        if start < cursor {
+            bail!("hunk {} overlaps a previous hunk", i + 1);
+        }
+        if start > old_lines.len() {
+            bail!("hunk {} starts past end of file", i + 1);
+        }
+        result.extend(old_lines[cursor..start].iter().map(|s| s.to_string()));
+        cursor = start;
+
+        for (tag, text) in &hunk.lines {
+            match tag {
+                ' ' | '-' => {
+                    let actual = old_lines.get(cursor).copied();
+                    if actual != Some(text.as_str()) {
+                        bail!(
+                            "hunk {} ({}) context mismatch at line {}: expected {:?}, found {:?}",
+                            i + 1,
+                            hunk.header(),
+                            cursor + 1,
+                            text,
+                            actual
+                        );
+                    }
+                    if *tag == ' ' {
+                        result.push(text.clone());
+                    }
+                    cursor += 1;
+                }
+                '+' => result.push(text.clone()),
+                _ => unreachable!(),
+            }
+        }
+    }
+    result.extend(old_lines[cursor..].iter().map(|s| s.to_string()));
+
+    let mut out = result.join("\n");
+    if content.ends_with('\n') || content.is_empty() {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+--- a/hello.txt
++++ b/hello.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+";
+
+    #[test]
+    fn parses_and_applies() {
+        let diffs = parse_unified_diff(SAMPLE).unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].target_path().unwrap(), &PathBuf::from("hello.txt"));
+        let applied = apply_file_diff(&diffs[0], "one\ntwo\nthree\n").unwrap();
+        assert_eq!(applied, "one\nTWO\nthree\n");
+    }
+
+    #[test]
+    fn rejects_context_mismatch() {
+        let diffs = parse_unified_diff(SAMPLE).unwrap();
+        assert!(apply_file_diff(&diffs[0], "one\nDIFFERENT\nthree\n").is_err());
+    }
+
+    #[test]
+    fn target_files_creation_and_deletion() {
+        let text = "\
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,1 @@
++hello
+";
+        let files = parse_unified_diff(text).unwrap();
+        assert!(files[0].is_creation());
+        assert_eq!(
+            parse_diff_target_files(text).unwrap(),
+            vec![PathBuf::from("new.txt")]
+        );
+    }
+}
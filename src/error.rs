@@ -0,0 +1,318 @@
+//! Error classification.
+//!
+//! [`SwError`] carries stable machine-readable codes through the anyhow
+//! chain. `classify_error` prefers a typed variant anywhere in the chain
+//! and only falls back to message sniffing for errors raised outside our
+//! own modules (kept as a compat shim for scripts matching `error[CODE]`).
+
+use std::fmt;
+
+/// Structured errors with stable codes, raised at the source instead of
+/// being guessed from message wording.
+#[derive(Debug)]
+pub enum SwError {
+    FileNotFound {
+        path: String,
+    },
+    MissingApiKey {
+        provider: String,
+    },
+    /// A stream connected but went quiet; `phase` is `first-token` or
+    /// `mid-response`.
+    StreamStalled {
+        phase: &'static str,
+        limit_secs: u64,
+    },
+    ProviderHttp {
+        status: u16,
+        body: String,
+        /// Parsed from the `Retry-After` header when the provider sent one.
+        retry_after_secs: Option<u64>,
+    },
+    /// A file the content policy forbids from ever entering a prompt.
+    Blocked {
+        path: String,
+        pattern: String,
+    },
+    /// A binary (or non-UTF-8) file reached a text-only code path.
+    BinaryFile {
+        path: String,
+    },
+    /// A write target that resolves outside the workspace root.
+    BlockedPath {
+        path: String,
+        reason: String,
+    },
+    /// Raised by the preflight check before a request is sent; shares its
+    /// code with the provider-reported equivalent.
+    ContextOverflow {
+        model: String,
+        estimated_tokens: usize,
+        context_window: usize,
+    },
+}
+
+/// Map a provider HTTP failure onto a code, looking at well-known body
+/// markers before falling back to the status class.
+fn provider_code(status: u16, body: &str) -> &'static str {
+    let b = body.to_ascii_lowercase();
+    if b.contains("insufficient_quota") || b.contains("quota exceeded") {
+        "quota_exceeded"
+    } else if b.contains("context_length_exceeded") || b.contains("maximum context length") {
+        "context_length_exceeded"
+    } else if b.contains("model_not_found")
+        || b.contains("invalid model")
+        || (b.contains("model") && b.contains("does not exist"))
+    {
+        "invalid_model"
+    } else {
+        match status {
+            401 | 403 => "missing_api_key",
+            429 => "rate_limited",
+            _ => "provider_http",
+        }
+    }
+}
+
+impl SwError {
+    /// The stable code printed as `error[CODE]` on stderr.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SwError::FileNotFound { .. } => "not_found",
+            SwError::MissingApiKey { .. } => "missing_api_key",
+            SwError::StreamStalled { .. } => "stream_stalled",
+            SwError::ProviderHttp { status, body, .. } => provider_code(*status, body),
+            SwError::Blocked { .. } => "blocked",
+            SwError::BinaryFile { .. } => "binary_file",
+            SwError::BlockedPath { .. } => "blocked_path",
+            SwError::ContextOverflow { .. } => "context_length_exceeded",
+        }
+    }
+
+    /// A one-line suggestion for fixing the failure, printed after the
+    /// error message when there is something actionable to say.
+    pub fn hint(&self) -> Option<String> {
+        if let SwError::Blocked { .. } = self {
+            return Some(
+                "pass --allow-denied to send it anyway, or adjust deny_paths \
+                 under [redact] in config"
+                    .to_string(),
+            );
+        }
+        if let SwError::BlockedPath { .. } = self {
+            return Some(
+                "diffs may only touch files inside the workspace; set \
+                 allow_outside_workspace = true in config to override"
+                    .to_string(),
+            );
+        }
+        if let SwError::ContextOverflow { .. } = self {
+            return Some(
+                "chunk the input into smaller pieces (summarize does this \
+                 automatically), pick a larger-context model with --model, or \
+                 set fallback_model with context_overflow = \"fallback\""
+                    .to_string(),
+            );
+        }
+        let SwError::ProviderHttp {
+            retry_after_secs, ..
+        } = self
+        else {
+            return None;
+        };
+        match self.code() {
+            "rate_limited" => Some(match retry_after_secs {
+                Some(s) => format!(
+                    "rate limited; wait {s}s before retrying, or add more keys \
+                     to the profile's api_keys pool"
+                ),
+                None => "wait a moment and retry, or add more keys to the \
+                         profile's api_keys pool"
+                    .to_string(),
+            }),
+            "quota_exceeded" => Some(
+                "the account's quota is exhausted; check billing or switch \
+                 profiles with --profile"
+                    .to_string(),
+            ),
+            "context_length_exceeded" => Some(
+                "the prompt exceeds the model's context window; chunk the \
+                 input into smaller pieces or pick a larger-context model \
+                 with --model"
+                    .to_string(),
+            ),
+            "invalid_model" => Some(
+                "the provider does not know this model; run `sw models list` \
+                 to see what it offers"
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SwError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwError::FileNotFound { path } => write!(f, "{path}: no such file"),
+            SwError::MissingApiKey { provider } => write!(
+                f,
+                "no API key configured for provider '{provider}'; set it in the \
+                 profile or the provider's environment variable"
+            ),
+            SwError::StreamStalled { phase, limit_secs } => {
+                write!(f, "stream stalled: no data within {limit_secs}s ({phase})")
+            }
+            SwError::ProviderHttp { status, body, .. } => {
+                write!(f, "provider returned HTTP {status}: {body}")
+            }
+            SwError::Blocked { path, pattern } => write!(
+                f,
+                "{path}: blocked by the content policy (deny pattern '{pattern}')"
+            ),
+            SwError::BinaryFile { path } => {
+                write!(f, "{path}: binary or non-UTF-8 content cannot be edited")
+            }
+            SwError::BlockedPath { path, reason } => {
+                write!(
+                    f,
+                    "{path}: refusing to write outside the workspace ({reason})"
+                )
+            }
+            SwError::ContextOverflow {
+                model,
+                estimated_tokens,
+                context_window,
+            } => write!(
+                f,
+                "prompt (~{estimated_tokens} tokens) exceeds {model}'s context \
+                 window of {context_window}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SwError {}
+
+/// Compat shim: derive a code from an error message for errors that were
+/// not raised as [`SwError`].
+pub fn derive_error_code(message: &str) -> &'static str {
+    let m = message.to_ascii_lowercase();
+    if m == "interrupted" {
+        "interrupted"
+    } else if m.contains("api key") || m.contains("unauthorized") || m.contains("http 401") {
+        "missing_api_key"
+    } else if m.contains("http 429") || m.contains("rate limit") {
+        "rate_limited"
+    } else if m.contains("stream stalled") {
+        "stream_stalled"
+    } else if m.contains("no such file") || m.contains("not found") {
+        "not_found"
+    } else if m.contains("timed out") || m.contains("timeout") {
+        "timeout"
+    } else if m.contains("connect") || m.contains("connection") {
+        "connection_failed"
+    } else {
+        "error"
+    }
+}
+
+/// Classify an error chain into a code: typed variants first, then I/O
+/// error kinds, then the message shim.
+pub fn classify_error(err: &anyhow::Error) -> &'static str {
+    for cause in err.chain() {
+        if let Some(sw) = cause.downcast_ref::<SwError>() {
+            return sw.code();
+        }
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            if io.kind() == std::io::ErrorKind::NotFound {
+                return "not_found";
+            }
+        }
+    }
+    for cause in err.chain() {
+        let code = derive_error_code(&cause.to_string());
+        if code != "error" {
+            return code;
+        }
+    }
+    "error"
+}
+
+/// The first actionable hint in the chain, if any variant carries one.
+pub fn error_hint(err: &anyhow::Error) -> Option<String> {
+    err.chain()
+        .find_map(|cause| cause.downcast_ref::<SwError>().and_then(|sw| sw.hint()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_known_codes() {
+        assert_eq!(
+            derive_error_code("provider returned HTTP 429: slow down"),
+            "rate_limited"
+        );
+        assert_eq!(
+            derive_error_code("No API key configured"),
+            "missing_api_key"
+        );
+        assert_eq!(
+            derive_error_code("stream stalled: no data for 30s mid-response"),
+            "stream_stalled"
+        );
+        assert_eq!(derive_error_code("something odd"), "error");
+    }
+
+    #[test]
+    fn typed_variants_win_over_wording() {
+        let err = anyhow::Error::from(SwError::ProviderHttp {
+            status: 429,
+            body: "be patient".into(),
+            retry_after_secs: None,
+        })
+        .context("wording that mentions no known phrase");
+        assert_eq!(classify_error(&err), "rate_limited");
+
+        let err = anyhow::Error::from(SwError::StreamStalled {
+            phase: "first-token",
+            limit_secs: 90,
+        });
+        assert_eq!(classify_error(&err), "stream_stalled");
+    }
+
+    #[test]
+    fn provider_bodies_refine_the_code() {
+        assert_eq!(
+            provider_code(400, "This model's maximum context length is 8192 tokens"),
+            "context_length_exceeded"
+        );
+        assert_eq!(
+            provider_code(429, r#"{"error":{"code":"insufficient_quota"}}"#),
+            "quota_exceeded"
+        );
+        assert_eq!(
+            provider_code(404, "The model `gpt-9` does not exist"),
+            "invalid_model"
+        );
+        assert_eq!(provider_code(429, "slow down"), "rate_limited");
+        assert_eq!(provider_code(500, "oops"), "provider_http");
+    }
+
+    #[test]
+    fn rate_limit_hint_includes_retry_after() {
+        let err = SwError::ProviderHttp {
+            status: 429,
+            body: "slow down".into(),
+            retry_after_secs: Some(12),
+        };
+        assert!(err.hint().unwrap().contains("wait 12s"));
+        let err = anyhow::Error::from(SwError::StreamStalled {
+            phase: "mid-response",
+            limit_secs: 30,
+        });
+        assert_eq!(error_hint(&err), None);
+    }
+}
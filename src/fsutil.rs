@@ -0,0 +1,363 @@
+//! Small async filesystem helpers shared by the file-writing commands.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+pub async fn read_file_to_string_async(path: &Path) -> Result<String> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(crate::error::SwError::FileNotFound {
+                path: path.display().to_string(),
+            }
+            .into())
+        }
+        Err(e) => Err(e).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Bytes sniffed from the head of a file to decide text vs binary.
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Cheap binary sniff: a NUL byte or invalid UTF-8 in the first
+/// [`SNIFF_BYTES`] marks the file as binary. Unreadable files pass — the
+/// actual read reports its own error.
+pub fn is_binary(path: &Path) -> bool {
+    use std::io::Read;
+    let Ok(mut f) = std::fs::File::open(path) else {
+        return false;
+    };
+    let mut buf = [0u8; SNIFF_BYTES];
+    let Ok(n) = f.read(&mut buf) else {
+        return false;
+    };
+    let head = &buf[..n];
+    if head.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => false,
+        // A multi-byte sequence cut off by the sniff window is fine;
+        // anything invalid earlier than the tail is real binary.
+        Err(e) => head.len() - e.valid_up_to() > 3,
+    }
+}
+
+/// Refuse write targets that resolve outside `root`: absolute paths,
+/// `..` traversal past the root, and symlinked directories pointing
+/// elsewhere. Model-proposed diffs choose these paths, so they are
+/// validated like any other untrusted input.
+pub fn ensure_within_workspace(path: &Path, root: &Path) -> Result<()> {
+    let blocked = |reason: &str| crate::error::SwError::BlockedPath {
+        path: path.display().to_string(),
+        reason: reason.to_string(),
+    };
+    if path.is_absolute() {
+        anyhow::bail!(blocked("absolute path"));
+    }
+    let mut depth = 0i32;
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                depth -= 1;
+                if depth < 0 {
+                    anyhow::bail!(blocked("path traversal above the workspace root"));
+                }
+            }
+            _ => depth += 1,
+        }
+    }
+    // A symlinked directory inside the tree can still point out of it;
+    // resolve the deepest existing ancestor and check where it landed.
+    let root_canon = root
+        .canonicalize()
+        .with_context(|| format!("cannot resolve workspace root {}", root.display()))?;
+    let mut probe = root.join(path);
+    while !probe.exists() {
+        match probe.parent() {
+            Some(parent) => probe = parent.to_path_buf(),
+            None => return Ok(()),
+        }
+    }
+    let landed = probe.canonicalize()?;
+    if !landed.starts_with(&root_canon) {
+        anyhow::bail!(blocked("symlink escapes the workspace"));
+    }
+    Ok(())
+}
+
+/// Refuse binary targets on text-only paths (LLM edits are lossy for
+/// them) with a specific `binary_file` code instead of a late read error.
+pub fn ensure_text_file(path: &Path) -> Result<()> {
+    if path.exists() && is_binary(path) {
+        anyhow::bail!(crate::error::SwError::BinaryFile {
+            path: path.display().to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Conventions of an existing file that a rewrite must not disturb:
+/// permission bits, CRLF vs LF line endings, and whether the file ends
+/// with a newline.
+struct WriteConventions {
+    permissions: std::fs::Permissions,
+    crlf: bool,
+    final_newline: bool,
+}
+
+impl WriteConventions {
+    /// Sample the file before it is overwritten; `None` for new files.
+    fn capture(path: &Path) -> Option<Self> {
+        use std::io::Read;
+        let permissions = std::fs::metadata(path).ok()?.permissions();
+        let mut f = std::fs::File::open(path).ok()?;
+        let mut buf = [0u8; SNIFF_BYTES];
+        let n = f.read(&mut buf).ok()?;
+        let head = &buf[..n];
+        let crlf_count = head.windows(2).filter(|w| w == b"\r\n").count();
+        let lf_count = head.iter().filter(|&&b| b == b'\n').count();
+        let final_newline = last_byte(path).is_none_or(|b| b == b'\n');
+        Some(Self {
+            permissions,
+            crlf: crlf_count > 0 && crlf_count * 2 >= lf_count,
+            final_newline,
+        })
+    }
+
+    /// Reshape new content (models and diffs emit LF) to the captured
+    /// line-ending and final-newline conventions.
+    fn adapt(&self, content: &str) -> String {
+        let mut out = content.replace("\r\n", "\n");
+        while out.ends_with('\n') {
+            out.pop();
+        }
+        if self.final_newline && !out.is_empty() {
+            out.push('\n');
+        }
+        if self.crlf {
+            out = out.replace('\n', "\r\n");
+        }
+        out
+    }
+}
+
+/// The last byte of a file, or `None` for empty/unreadable files.
+fn last_byte(path: &Path) -> Option<u8> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut f = std::fs::File::open(path).ok()?;
+    f.seek(SeekFrom::End(-1)).ok()?;
+    let mut b = [0u8; 1];
+    f.read_exact(&mut b).ok()?;
+    Some(b[0])
+}
+
+/// Write `content` to `path`. When overwriting, the existing file's
+/// permission bits, line endings, and final-newline convention are
+/// preserved so diff apply and friends do not churn unrelated metadata.
+///
+/// The write goes to a temp file in the same directory, is fsynced, and
+/// is renamed over the target, so a crash mid-write leaves either the
+/// old file or the new one — never a truncated mix.
+pub async fn write_file_async(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+    }
+    let prior = WriteConventions::capture(path);
+    let body = match &prior {
+        Some(conventions) => conventions.adapt(content),
+        None => content.to_string(),
+    };
+    // Same directory as the target so the rename cannot cross
+    // filesystems (which would fall back to a non-atomic copy).
+    let mut tmp_name = path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    tmp_name.push(format!(".sw-tmp{}", std::process::id()));
+    let tmp = path.with_file_name(tmp_name);
+    let result: Result<()> = async {
+        {
+            let file = tokio::fs::File::create(&tmp).await?;
+            let mut file = tokio::io::BufWriter::new(file);
+            tokio::io::AsyncWriteExt::write_all(&mut file, body.as_bytes()).await?;
+            tokio::io::AsyncWriteExt::flush(&mut file).await?;
+            file.into_inner().sync_all().await?;
+        }
+        if let Some(conventions) = &prior {
+            std::fs::set_permissions(&tmp, conventions.permissions.clone())?;
+        }
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp).await;
+    }
+    result.with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Copy `path` into the managed backup store (`.sw/backups/`) before a
+/// destructive write; see [`crate::backups`].
+pub async fn backup_file_async(path: &Path) -> Result<()> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || crate::backups::backup_file(&path))
+        .await
+        .context("backup task panicked")?
+}
+
+/// Hex-encoded SHA-256 of a file's contents.
+pub fn hash_file(path: &Path) -> Result<String> {
+    use sha2::{Digest, Sha256};
+    let data = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    hasher.update(&data);
+    Ok(hex_string(&hasher.finalize()))
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// Hash many files on worker threads. Results come back in input order;
+/// `progress(done)` fires as files finish.
+pub fn hash_files_parallel(
+    paths: &[&Path],
+    progress: &(dyn Fn(usize) + Sync),
+) -> Result<Vec<String>> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(paths.len());
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let chunks: Vec<Vec<(usize, Result<String>)>> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut out = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= paths.len() {
+                            break;
+                        }
+                        out.push((i, hash_file(paths[i])));
+                        progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+                    }
+                    out
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("hash worker panicked"))
+            .collect()
+    });
+    let mut results: Vec<Option<String>> = vec![None; paths.len()];
+    for (i, r) in chunks.into_iter().flatten() {
+        results[i] = Some(r?);
+    }
+    Ok(results
+        .into_iter()
+        .map(|r| r.expect("index hashed"))
+        .collect())
+}
+
+/// Cheap identity tiers before hashing: different sizes can never match,
+/// and equal size plus equal mtime is treated as unchanged. Returns
+/// `None` when only a content hash can decide.
+pub fn quick_same(a: &Path, b: &Path) -> Result<Option<bool>> {
+    let ma = std::fs::metadata(a)?;
+    let mb = std::fs::metadata(b)?;
+    if ma.len() != mb.len() {
+        return Ok(Some(false));
+    }
+    if let (Ok(ta), Ok(tb)) = (ma.modified(), mb.modified()) {
+        if ta == tb {
+            return Ok(Some(true));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_encoding() {
+        assert_eq!(hex_string(&[0x00, 0xff, 0x2c]), "00ff2c");
+    }
+
+    #[tokio::test]
+    async fn rewrites_preserve_eol_final_newline_and_mode() {
+        let dir = std::env::temp_dir().join(format!("sw-writeconv-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let crlf = dir.join("dos.txt");
+        std::fs::write(&crlf, "one\r\ntwo\r\n").unwrap();
+        write_file_async(&crlf, "one\nthree\n").await.unwrap();
+        assert_eq!(std::fs::read(&crlf).unwrap(), b"one\r\nthree\r\n");
+
+        let no_newline = dir.join("tail.txt");
+        std::fs::write(&no_newline, "x").unwrap();
+        write_file_async(&no_newline, "y\n").await.unwrap();
+        assert_eq!(std::fs::read(&no_newline).unwrap(), b"y");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let script = dir.join("run.sh");
+            std::fs::write(&script, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+            write_file_async(&script, "#!/bin/sh\necho hi\n")
+                .await
+                .unwrap();
+            let mode = std::fs::metadata(&script).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn workspace_confinement_blocks_escapes() {
+        let dir = std::env::temp_dir().join(format!("sw-confine-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        assert!(ensure_within_workspace(Path::new("src/lib.rs"), &dir).is_ok());
+        assert!(ensure_within_workspace(Path::new("src/../other.rs"), &dir).is_ok());
+        assert!(ensure_within_workspace(Path::new("../outside.rs"), &dir).is_err());
+        assert!(ensure_within_workspace(Path::new("/etc/passwd"), &dir).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/tmp", dir.join("link")).unwrap();
+            assert!(ensure_within_workspace(Path::new("link/evil.rs"), &dir).is_err());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn binary_sniff_flags_nul_but_not_utf8() {
+        let dir = std::env::temp_dir().join(format!("sw-binsniff-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let text = dir.join("text.rs");
+        std::fs::write(&text, "fn main() {} // héllo\n").unwrap();
+        let bin = dir.join("blob.bin");
+        std::fs::write(&bin, [0x7fu8, b'E', b'L', b'F', 0x00, 0x01]).unwrap();
+        assert!(!is_binary(&text));
+        assert!(is_binary(&bin));
+        assert!(ensure_text_file(&text).is_ok());
+        assert!(ensure_text_file(&bin).is_err());
+        // Missing files pass; the read path owns that error.
+        assert!(ensure_text_file(&dir.join("absent")).is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,46 @@
+//! Thin git helpers used by the commands that read repository state.
+
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+/// Run git with the given args and return trimmed stdout.
+pub fn git(args: &[&str]) -> Result<String> {
+    let out = Command::new("git")
+        .args(args)
+        .output()
+        .context("failed to run git — is it installed?")?;
+    if !out.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim_end().to_string())
+}
+
+pub fn staged_diff() -> Result<String> {
+    git(&["diff", "--staged"])
+}
+
+pub fn working_diff() -> Result<String> {
+    git(&["diff"])
+}
+
+/// True when the current directory is inside a git work tree.
+pub fn in_work_tree() -> bool {
+    git(&["rev-parse", "--is-inside-work-tree"]).is_ok()
+}
+
+/// Commits in a revision range as `(short_hash, subject)`, newest first.
+pub fn commits_in_range(range: &str) -> Result<Vec<(String, String)>> {
+    let raw = git(&["log", "--format=%h%x09%s", range])?;
+    Ok(raw
+        .lines()
+        .filter_map(|l| {
+            let (hash, subject) = l.split_once('\t')?;
+            Some((hash.to_string(), subject.to_string()))
+        })
+        .collect())
+}
@@ -0,0 +1,92 @@
+//! Invocation history.
+//!
+//! Every run appends one JSONL record (command, raw arguments, an inputs
+//! hash) under the data dir; `sw history list` shows them and `sw rerun`
+//! replays one, optionally under a different model.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub id: u64,
+    pub timestamp: DateTime<Utc>,
+    pub command: String,
+    /// The raw arguments as typed, minus the binary name.
+    pub args: Vec<String>,
+    /// Hash of the arguments, for spotting identical reruns at a glance.
+    pub inputs_hash: String,
+    pub success: bool,
+}
+
+fn history_path() -> Result<PathBuf> {
+    Ok(Config::data_dir()?.join("history.jsonl"))
+}
+
+pub fn inputs_hash(args: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for arg in args {
+        hasher.update(arg.as_bytes());
+        hasher.update([0]);
+    }
+    format!("{:x}", hasher.finalize())[..12].to_string()
+}
+
+/// Append one invocation; ids are sequential.
+pub fn record(command: &str, args: &[String], success: bool) -> Result<()> {
+    use std::io::Write;
+    let id = load()?.last().map_or(1, |r| r.id + 1);
+    let rec = HistoryRecord {
+        id,
+        timestamp: Utc::now(),
+        command: command.to_string(),
+        args: args.to_vec(),
+        inputs_hash: inputs_hash(args),
+        success,
+    };
+    let path = history_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut f = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    writeln!(f, "{}", serde_json::to_string(&rec)?)?;
+    Ok(())
+}
+
+/// All recorded invocations, oldest first; corrupt lines are skipped.
+pub fn load() -> Result<Vec<HistoryRecord>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str::<HistoryRecord>(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_hash_is_stable_and_order_sensitive() {
+        let a = vec!["ask".to_string(), "hi".to_string()];
+        let b = vec!["hi".to_string(), "ask".to_string()];
+        assert_eq!(inputs_hash(&a), inputs_hash(&a));
+        assert_ne!(inputs_hash(&a), inputs_hash(&b));
+        assert_eq!(inputs_hash(&a).len(), 12);
+    }
+}
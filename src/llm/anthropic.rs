@@ -0,0 +1,440 @@
+//! Native Anthropic Messages API adapter.
+//!
+//! Anthropic does not speak `/chat/completions`: the system prompt is a
+//! top-level field, auth uses `x-api-key`, and streaming is typed SSE
+//! events rather than chat-completion chunks.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::openai::{call_meta, check_status, CONNECT_TIMEOUT, FIRST_TOKEN_TIMEOUT, IDLE_TIMEOUT};
+use super::{ChatRequest, ChatResponse, Delta, DeltaFn, ModelInfo, Provider, Role, Usage};
+use crate::context::estimate_tokens;
+use crate::error::SwError;
+use crate::ratelimit::RateLimiter;
+
+/// The Messages API requires `max_tokens`; used when the profile sets none.
+const DEFAULT_MAX_TOKENS: u32 = 4096;
+
+pub struct AnthropicProvider {
+    api_base: String,
+    /// Key pool; requests rotate round-robin and fail over on HTTP 429.
+    api_keys: Vec<String>,
+    cursor: std::sync::atomic::AtomicUsize,
+    client: reqwest::Client,
+    limiter: Option<std::sync::Arc<RateLimiter>>,
+}
+
+impl AnthropicProvider {
+    pub fn new(
+        api_base: String,
+        api_keys: Vec<String>,
+        mut headers: reqwest::header::HeaderMap,
+        limiter: Option<std::sync::Arc<RateLimiter>>,
+    ) -> Self {
+        // Profile headers may pin a different API revision.
+        headers
+            .entry("anthropic-version")
+            .or_insert(reqwest::header::HeaderValue::from_static("2023-06-01"));
+        Self {
+            api_base: api_base.trim_end_matches('/').to_string(),
+            api_keys,
+            cursor: std::sync::atomic::AtomicUsize::new(0),
+            client: reqwest::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .default_headers(headers)
+                .build()
+                .expect("static client config"),
+            limiter,
+        }
+    }
+
+    /// The key `offset` steps past the round-robin cursor position.
+    fn key_at(&self, base: usize, offset: usize) -> Option<&str> {
+        if self.api_keys.is_empty() {
+            return None;
+        }
+        Some(self.api_keys[(base + offset) % self.api_keys.len()].as_str())
+    }
+
+    /// Advance the round-robin cursor and return its previous position.
+    fn take_cursor(&self) -> usize {
+        self.cursor
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// POST `/messages`, rotating through the key pool and moving to the
+    /// next key when one is rate-limited.
+    async fn post_messages(&self, body: &serde_json::Value) -> Result<reqwest::Response> {
+        let base = self.take_cursor();
+        let attempts = self.api_keys.len().max(1);
+        for attempt in 0..attempts {
+            let mut rb = self.client.post(format!("{}/messages", self.api_base));
+            if let Some(key) = self.key_at(base, attempt).filter(|k| !k.is_empty()) {
+                rb = rb.header("x-api-key", key);
+            }
+            let resp = rb
+                .json(body)
+                .send()
+                .await
+                .context("request to provider failed")?;
+            if resp.status().as_u16() == 429 && attempt + 1 < attempts {
+                continue;
+            }
+            return check_status(resp).await;
+        }
+        unreachable!("key rotation loop always returns")
+    }
+
+    /// One streaming request, watched by the first-token and inter-token
+    /// idle timeouts. Returns `(content, model, usage, first_token)`.
+    async fn stream_attempt(
+        &self,
+        req: &ChatRequest,
+        on_delta: &mut (dyn FnMut(Delta) + Send),
+        started: std::time::Instant,
+    ) -> Result<(String, String, Option<Usage>, Option<std::time::Duration>)> {
+        let resp = self.post_messages(&self.body(req, true)).await?;
+        let mut stream = resp.bytes_stream();
+        let mut buf = String::new();
+        let mut content = String::new();
+        let mut usage = Usage::default();
+        let mut model = req.model.clone();
+        let mut first_token: Option<std::time::Duration> = None;
+        loop {
+            let limit = if first_token.is_none() {
+                FIRST_TOKEN_TIMEOUT
+            } else {
+                IDLE_TIMEOUT
+            };
+            let chunk = match tokio::time::timeout(limit, stream.next()).await {
+                Ok(Some(chunk)) => chunk.context("stream read failed")?,
+                Ok(None) => break,
+                Err(_) => bail!(SwError::StreamStalled {
+                    phase: if first_token.is_none() {
+                        "first-token"
+                    } else {
+                        "mid-response"
+                    },
+                    limit_secs: limit.as_secs(),
+                }),
+            };
+            buf.push_str(&String::from_utf8_lossy(&chunk));
+            while let Some(pos) = buf.find('\n') {
+                let line = buf[..pos].trim().to_string();
+                buf.drain(..=pos);
+                // `event:` lines just name the following data payload,
+                // which carries its own `type` field.
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let Ok(event) = serde_json::from_str::<StreamEvent>(data.trim()) else {
+                    continue;
+                };
+                match event {
+                    StreamEvent::MessageStart { message } => {
+                        model = message.model;
+                        usage.prompt_tokens = message.usage.input_tokens;
+                    }
+                    StreamEvent::ContentBlockDelta { delta } => {
+                        let (delta, thinking) = match &delta {
+                            BlockDelta::TextDelta { text } => (text.as_str(), false),
+                            BlockDelta::ThinkingDelta { thinking } => (thinking.as_str(), true),
+                            BlockDelta::Other => continue,
+                        };
+                        if first_token.is_none() {
+                            first_token = Some(started.elapsed());
+                        }
+                        if thinking {
+                            on_delta(Delta::Thinking(delta));
+                        } else {
+                            content.push_str(delta);
+                            on_delta(Delta::Content(delta));
+                        }
+                    }
+                    StreamEvent::MessageDelta { usage: tail } => {
+                        usage.completion_tokens = tail.output_tokens;
+                    }
+                    StreamEvent::Other => {}
+                }
+            }
+        }
+        usage.total_tokens = usage.prompt_tokens + usage.completion_tokens;
+        Ok((content, model, Some(usage), first_token))
+    }
+
+    /// Block until the provider's configured quota allows this request.
+    async fn throttle(&self, req: &ChatRequest) {
+        if let Some(limiter) = &self.limiter {
+            let estimated = req
+                .messages
+                .iter()
+                .map(|m| estimate_tokens(&m.content))
+                .sum();
+            limiter.acquire(estimated).await;
+        }
+    }
+
+    fn body(&self, req: &ChatRequest, stream: bool) -> serde_json::Value {
+        // System turns become the top-level `system` field; everything
+        // else alternates user/assistant (tool results are already
+        // rendered as user text by the time a provider sees them).
+        let system: Vec<&str> = req
+            .messages
+            .iter()
+            .filter(|m| m.role == Role::System)
+            .map(|m| m.content.as_str())
+            .collect();
+        let messages: Vec<serde_json::Value> = req
+            .messages
+            .iter()
+            .filter(|m| m.role != Role::System)
+            .map(|m| {
+                json!({
+                    "role": if m.role == Role::Assistant { "assistant" } else { "user" },
+                    "content": m.content,
+                })
+            })
+            .collect();
+        let mut body = json!({
+            "model": req.model,
+            "max_tokens": req.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            "messages": messages,
+        });
+        if !system.is_empty() {
+            body["system"] = json!(system.join("\n\n"));
+        }
+        if let Some(t) = req.temperature {
+            body["temperature"] = json!(t);
+        }
+        if !req.stop.is_empty() {
+            body["stop_sequences"] = json!(req.stop);
+        }
+        if let Some(effort) = &req.reasoning_effort {
+            // Anthropic takes an explicit thinking budget instead of a
+            // named effort level.
+            let budget = match effort.as_str() {
+                "low" => 2048,
+                "high" => 16384,
+                _ => 8192,
+            };
+            body["thinking"] = json!({"type": "enabled", "budget_tokens": budget});
+        }
+        if stream {
+            body["stream"] = json!(true);
+        }
+        body
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    model: Option<String>,
+    content: Vec<ContentBlock>,
+    usage: Option<AnthropicUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+impl AnthropicUsage {
+    fn into_usage(self) -> Usage {
+        Usage {
+            prompt_tokens: self.input_tokens,
+            completion_tokens: self.output_tokens,
+            total_tokens: self.input_tokens + self.output_tokens,
+            completion_tokens_details: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: StreamMessageStart,
+    },
+    ContentBlockDelta {
+        delta: BlockDelta,
+    },
+    MessageDelta {
+        usage: AnthropicUsage,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessageStart {
+    model: String,
+    #[serde(default)]
+    usage: AnthropicUsage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockDelta {
+    TextDelta {
+        text: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+#[async_trait]
+impl Provider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "anthropic"
+    }
+
+    async fn send(&self, req: &ChatRequest) -> Result<ChatResponse> {
+        self.throttle(req).await;
+        let started = std::time::Instant::now();
+        let resp = self.post_messages(&self.body(req, false)).await?;
+        let parsed: MessagesResponse = resp
+            .json()
+            .await
+            .context("failed to parse provider response")?;
+        let content: String = parsed
+            .content
+            .iter()
+            .filter(|b| b.kind == "text")
+            .map(|b| b.text.as_str())
+            .collect();
+        let model = parsed.model.unwrap_or_else(|| req.model.clone());
+        let usage = parsed.usage.map(AnthropicUsage::into_usage);
+        let meta = call_meta(&model, usage.as_ref(), None, started, 0);
+        Ok(ChatResponse {
+            content,
+            model,
+            meta,
+        })
+    }
+
+    async fn send_stream(&self, req: &ChatRequest, on_delta: DeltaFn<'_>) -> Result<ChatResponse> {
+        self.throttle(req).await;
+        let started = std::time::Instant::now();
+        let mut retries = 0u32;
+        loop {
+            match self.stream_attempt(req, on_delta, started).await {
+                Ok((content, model, usage, first_token)) => {
+                    let meta = call_meta(&model, usage.as_ref(), first_token, started, retries);
+                    return Ok(ChatResponse {
+                        content,
+                        model,
+                        meta,
+                    });
+                }
+                // Retry once, but only when nothing reached the caller yet —
+                // a mid-response stall cannot be resumed transparently.
+                Err(e)
+                    if retries == 0
+                        && matches!(
+                            e.downcast_ref::<SwError>(),
+                            Some(SwError::StreamStalled {
+                                phase: "first-token",
+                                ..
+                            })
+                        ) =>
+                {
+                    retries += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let mut rb = self.client.get(format!("{}/models", self.api_base));
+        if let Some(key) = self.key_at(self.take_cursor(), 0).filter(|k| !k.is_empty()) {
+            rb = rb.header("x-api-key", key);
+        }
+        let resp = rb.send().await.context("request to provider failed")?;
+        let resp = check_status(resp).await?;
+        let parsed: ModelList = resp.json().await.context("failed to parse model list")?;
+        Ok(parsed
+            .data
+            .into_iter()
+            .map(|m| ModelInfo {
+                id: m.id,
+                owned_by: Some("anthropic".to_string()),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ChatMessage;
+
+    fn provider() -> AnthropicProvider {
+        AnthropicProvider::new(
+            "https://api.anthropic.com/v1".to_string(),
+            vec![],
+            reqwest::header::HeaderMap::new(),
+            None,
+        )
+    }
+
+    #[test]
+    fn system_turns_become_the_top_level_field() {
+        let req = ChatRequest {
+            model: "claude-test".to_string(),
+            messages: vec![ChatMessage::system("be brief"), ChatMessage::user("hi")],
+            temperature: None,
+            max_tokens: None,
+            stop: vec![],
+            reasoning_effort: None,
+        };
+        let body = provider().body(&req, false);
+        assert_eq!(body["system"], "be brief");
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+        assert_eq!(body["messages"][0]["role"], "user");
+        assert_eq!(body["max_tokens"], DEFAULT_MAX_TOKENS);
+    }
+
+    #[test]
+    fn reasoning_effort_maps_to_a_thinking_budget() {
+        let req = ChatRequest {
+            model: "claude-test".to_string(),
+            messages: vec![ChatMessage::user("hi")],
+            temperature: None,
+            max_tokens: Some(1024),
+            stop: vec![],
+            reasoning_effort: Some("high".to_string()),
+        };
+        let body = provider().body(&req, false);
+        assert_eq!(body["thinking"]["budget_tokens"], 16384);
+        assert_eq!(body["max_tokens"], 1024);
+    }
+}
@@ -0,0 +1,283 @@
+//! Provider-agnostic LLM plumbing: message types, the [`Provider`] trait,
+//! and the [`ProviderRegistry`] that maps profile settings onto adapters.
+
+pub mod anthropic;
+pub mod openai;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Profile};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    /// A tool invocation result. Stored in sessions with a structured
+    /// payload; rendered as user text before reaching a provider.
+    Tool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::System,
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Stop sequences, usually from a per-model config preset.
+    pub stop: Vec<String>,
+    /// `low`/`medium`/`high` for reasoning models; sent as
+    /// `reasoning_effort`, or mapped to a thinking budget for Anthropic.
+    pub reasoning_effort: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Usage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub total_tokens: u64,
+    /// Present on reasoning models; its `reasoning_tokens` are already
+    /// included in `completion_tokens`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens_details: Option<CompletionTokensDetails>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompletionTokensDetails {
+    #[serde(default)]
+    pub reasoning_tokens: u64,
+}
+
+/// Per-call metrics measured in the provider layer and surfaced with `-v`
+/// (and under `meta` in JSON output).
+#[derive(Debug, Clone, Serialize)]
+pub struct CallMeta {
+    pub model: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_tokens: Option<u64>,
+    /// Share of `completion_tokens` a reasoning model spent thinking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_tokens: Option<u64>,
+    /// Only measured on streaming calls.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_to_first_token_ms: Option<u64>,
+    pub latency_ms: u64,
+    pub retries: u32,
+}
+
+impl CallMeta {
+    /// One-line human summary for verbose diagnostics.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("model={}", self.model)];
+        if let (Some(p), Some(c)) = (self.prompt_tokens, self.completion_tokens) {
+            parts.push(format!("tokens={p}+{c}"));
+        }
+        if let Some(r) = self.reasoning_tokens {
+            parts.push(format!("reasoning={r}"));
+        }
+        if let Some(ttft) = self.time_to_first_token_ms {
+            parts.push(format!("ttft={ttft}ms"));
+        }
+        parts.push(format!("latency={}ms", self.latency_ms));
+        if self.retries > 0 {
+            parts.push(format!("retries={}", self.retries));
+        }
+        parts.join(" ")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ChatResponse {
+    pub content: String,
+    pub model: String,
+    pub meta: CallMeta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owned_by: Option<String>,
+}
+
+/// One streamed chunk: visible answer text, or reasoning tokens from an
+/// extended-thinking model.
+#[derive(Debug, Clone, Copy)]
+pub enum Delta<'a> {
+    Content(&'a str),
+    Thinking(&'a str),
+}
+
+/// Callback invoked with each streamed delta.
+pub type DeltaFn<'a> = &'a mut (dyn FnMut(Delta) + Send);
+
+#[async_trait]
+pub trait Provider: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn send(&self, req: &ChatRequest) -> Result<ChatResponse>;
+
+    /// Stream a response, invoking `on_delta` for each content chunk, and
+    /// return the assembled response when the stream completes.
+    async fn send_stream(&self, req: &ChatRequest, on_delta: DeltaFn<'_>) -> Result<ChatResponse>;
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>>;
+}
+
+/// Default API base URLs for the providers we know how to talk to.
+pub fn resolve_api_base_for_provider(provider: &str) -> Option<&'static str> {
+    match provider {
+        "openai" => Some("https://api.openai.com/v1"),
+        "anthropic" => Some("https://api.anthropic.com/v1"),
+        "ollama" => Some("http://localhost:11434/v1"),
+        _ => None,
+    }
+}
+
+pub struct ProviderRegistry;
+
+impl ProviderRegistry {
+    /// Build the adapter for a profile. Anthropic gets its native
+    /// Messages API adapter; everything else — including config-defined
+    /// `[providers.*]` endpoints — speaks the OpenAI wire format.
+    pub fn create(config: &Config, profile: &Profile) -> Result<Box<dyn Provider>> {
+        let custom = config.providers.get(&profile.provider);
+        let api_base = match profile.api_base.clone() {
+            Some(base) => base,
+            None => match custom
+                .map(|c| c.api_base.clone())
+                .or_else(|| resolve_api_base_for_provider(&profile.provider).map(str::to_string))
+            {
+                Some(base) => base,
+                None => bail!(
+                    "unknown provider '{}' and no api_base configured; define it \
+                     under [providers.{}] in config",
+                    profile.provider,
+                    profile.provider
+                ),
+            },
+        };
+        let mut api_keys = profile.resolve_api_keys();
+        if api_keys.is_empty() {
+            if let Some(key) = custom.and_then(|c| c.resolve_api_key()) {
+                api_keys.push(key);
+            }
+        }
+        // Hosted providers reject unauthenticated requests anyway; fail
+        // here with an actionable message instead of at request time.
+        if api_keys.is_empty() && matches!(profile.provider.as_str(), "openai" | "anthropic") {
+            bail!(crate::error::SwError::MissingApiKey {
+                provider: profile.provider.clone(),
+            });
+        }
+        let limiter = crate::ratelimit::limiter_for(&profile.provider, config);
+        if profile.provider == "anthropic" {
+            return Ok(Box::new(anthropic::AnthropicProvider::new(
+                api_base,
+                api_keys,
+                default_headers(profile)?,
+                limiter,
+            )));
+        }
+        Ok(Box::new(openai::OpenAiProvider::new(
+            profile.provider.clone(),
+            api_base,
+            api_keys,
+            default_headers(profile)?,
+            limiter,
+        )))
+    }
+}
+
+/// Headers a profile attaches to every request: the explicit
+/// `[profiles.<name>.headers]` table plus the well-known
+/// organization/project/beta conveniences.
+pub fn default_headers(profile: &Profile) -> Result<reqwest::header::HeaderMap> {
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+    let mut pairs: Vec<(String, String)> = profile
+        .headers
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    if let Some(org) = &profile.organization {
+        pairs.push(("OpenAI-Organization".to_string(), org.clone()));
+    }
+    if let Some(project) = &profile.project {
+        pairs.push(("OpenAI-Project".to_string(), project.clone()));
+    }
+    if !profile.betas.is_empty() {
+        pairs.push(("anthropic-beta".to_string(), profile.betas.join(",")));
+    }
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in pairs {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("invalid header name '{name}' in profile config"))?;
+        let value = HeaderValue::from_str(&value)
+            .with_context(|| format!("invalid value for header '{name}' in profile config"))?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_headers_include_the_well_known_conveniences() {
+        let profile = Profile {
+            organization: Some("org-123".to_string()),
+            project: Some("proj-456".to_string()),
+            betas: vec!["prompt-caching-2024-07-31".to_string()],
+            headers: [("X-Gateway-Tenant".to_string(), "alpha".to_string())]
+                .into_iter()
+                .collect(),
+            ..Profile::default()
+        };
+        let headers = default_headers(&profile).unwrap();
+        assert_eq!(headers["openai-organization"], "org-123");
+        assert_eq!(headers["openai-project"], "proj-456");
+        assert_eq!(headers["anthropic-beta"], "prompt-caching-2024-07-31");
+        assert_eq!(headers["x-gateway-tenant"], "alpha");
+    }
+
+    #[test]
+    fn invalid_header_names_are_rejected() {
+        let profile = Profile {
+            headers: [("bad header".to_string(), "x".to_string())]
+                .into_iter()
+                .collect(),
+            ..Profile::default()
+        };
+        assert!(default_headers(&profile).is_err());
+    }
+}
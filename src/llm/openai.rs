@@ -0,0 +1,388 @@
+//! OpenAI-compatible chat completions adapter.
+//!
+//! This speaks the `/chat/completions` wire format, which is also what
+//! Ollama and most self-hosted inference servers expose.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{CallMeta, ChatRequest, ChatResponse, Delta, DeltaFn, ModelInfo, Provider, Usage};
+use crate::context::estimate_tokens;
+use crate::error::SwError;
+use crate::ratelimit::RateLimiter;
+
+/// Streaming watchdogs: a request timeout never fires once the connection
+/// is up, so stalls are detected per phase instead. Shared with the
+/// Anthropic adapter.
+pub(super) const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+pub(super) const FIRST_TOKEN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(90);
+pub(super) const IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+pub struct OpenAiProvider {
+    name: String,
+    api_base: String,
+    /// Key pool; requests rotate round-robin and fail over on HTTP 429.
+    api_keys: Vec<String>,
+    cursor: std::sync::atomic::AtomicUsize,
+    client: reqwest::Client,
+    limiter: Option<std::sync::Arc<RateLimiter>>,
+}
+
+impl OpenAiProvider {
+    pub fn new(
+        name: String,
+        api_base: String,
+        api_keys: Vec<String>,
+        headers: reqwest::header::HeaderMap,
+        limiter: Option<std::sync::Arc<RateLimiter>>,
+    ) -> Self {
+        Self {
+            name,
+            api_base: api_base.trim_end_matches('/').to_string(),
+            api_keys,
+            cursor: std::sync::atomic::AtomicUsize::new(0),
+            client: reqwest::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .default_headers(headers)
+                .build()
+                .expect("static client config"),
+            limiter,
+        }
+    }
+
+    /// The key `offset` steps past the round-robin cursor position.
+    fn key_at(&self, base: usize, offset: usize) -> Option<&str> {
+        if self.api_keys.is_empty() {
+            return None;
+        }
+        Some(self.api_keys[(base + offset) % self.api_keys.len()].as_str())
+    }
+
+    /// Advance the round-robin cursor and return its previous position.
+    fn take_cursor(&self) -> usize {
+        self.cursor
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// POST `/chat/completions`, rotating through the key pool and moving
+    /// to the next key when one is rate-limited.
+    async fn post_completions(&self, body: &serde_json::Value) -> Result<reqwest::Response> {
+        let base = self.take_cursor();
+        let attempts = self.api_keys.len().max(1);
+        for attempt in 0..attempts {
+            let mut rb = self
+                .client
+                .post(format!("{}/chat/completions", self.api_base));
+            if let Some(key) = self.key_at(base, attempt).filter(|k| !k.is_empty()) {
+                rb = rb.bearer_auth(key);
+            }
+            let resp = rb
+                .json(body)
+                .send()
+                .await
+                .context("request to provider failed")?;
+            if resp.status().as_u16() == 429 && attempt + 1 < attempts {
+                continue;
+            }
+            return check_status(resp).await;
+        }
+        unreachable!("key rotation loop always returns")
+    }
+
+    /// One streaming request, watched by the first-token and inter-token
+    /// idle timeouts. Returns `(content, model, usage, first_token)`.
+    async fn stream_attempt(
+        &self,
+        req: &ChatRequest,
+        on_delta: &mut (dyn FnMut(Delta) + Send),
+        started: std::time::Instant,
+    ) -> Result<(String, String, Option<Usage>, Option<std::time::Duration>)> {
+        let resp = self.post_completions(&self.body(req, true)).await?;
+        let mut stream = resp.bytes_stream();
+        let mut buf = String::new();
+        let mut content = String::new();
+        let mut usage = None;
+        let mut model = req.model.clone();
+        let mut first_token: Option<std::time::Duration> = None;
+        loop {
+            let limit = if first_token.is_none() {
+                FIRST_TOKEN_TIMEOUT
+            } else {
+                IDLE_TIMEOUT
+            };
+            let chunk = match tokio::time::timeout(limit, stream.next()).await {
+                Ok(Some(chunk)) => chunk.context("stream read failed")?,
+                Ok(None) => break,
+                Err(_) => bail!(SwError::StreamStalled {
+                    phase: if first_token.is_none() {
+                        "first-token"
+                    } else {
+                        "mid-response"
+                    },
+                    limit_secs: limit.as_secs(),
+                }),
+            };
+            buf.push_str(&String::from_utf8_lossy(&chunk));
+            while let Some(pos) = buf.find('\n') {
+                let line = buf[..pos].trim().to_string();
+                buf.drain(..=pos);
+                let Some(data) = line.strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    continue;
+                }
+                let Ok(parsed) = serde_json::from_str::<StreamChunk>(data) else {
+                    continue;
+                };
+                if let Some(m) = parsed.model {
+                    model = m;
+                }
+                if let Some(u) = parsed.usage {
+                    usage = Some(u);
+                }
+                // Reasoning models think before they answer; count the
+                // first thinking token against the first-token watchdog
+                // so a long deliberation is not mistaken for a stall.
+                if let Some(delta) = parsed
+                    .choices
+                    .first()
+                    .and_then(|c| c.delta.reasoning_content.as_deref())
+                {
+                    if first_token.is_none() {
+                        first_token = Some(started.elapsed());
+                    }
+                    on_delta(Delta::Thinking(delta));
+                }
+                if let Some(delta) = parsed
+                    .choices
+                    .first()
+                    .and_then(|c| c.delta.content.as_deref())
+                {
+                    if first_token.is_none() {
+                        first_token = Some(started.elapsed());
+                    }
+                    content.push_str(delta);
+                    on_delta(Delta::Content(delta));
+                }
+            }
+        }
+        Ok((content, model, usage, first_token))
+    }
+
+    /// Block until the provider's configured quota allows this request.
+    async fn throttle(&self, req: &ChatRequest) {
+        if let Some(limiter) = &self.limiter {
+            let estimated = req
+                .messages
+                .iter()
+                .map(|m| estimate_tokens(&m.content))
+                .sum();
+            limiter.acquire(estimated).await;
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let mut rb = self
+            .client
+            .request(method, format!("{}{path}", self.api_base));
+        if let Some(key) = self.key_at(self.take_cursor(), 0).filter(|k| !k.is_empty()) {
+            rb = rb.bearer_auth(key);
+        }
+        rb
+    }
+
+    fn body(&self, req: &ChatRequest, stream: bool) -> serde_json::Value {
+        let mut body = json!({
+            "model": req.model,
+            "messages": req.messages,
+        });
+        if let Some(t) = req.temperature {
+            body["temperature"] = json!(t);
+        }
+        if let Some(m) = req.max_tokens {
+            body["max_tokens"] = json!(m);
+        }
+        if !req.stop.is_empty() {
+            body["stop"] = json!(req.stop);
+        }
+        if let Some(effort) = &req.reasoning_effort {
+            body["reasoning_effort"] = json!(effort);
+        }
+        if stream {
+            body["stream"] = json!(true);
+        }
+        body
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CompletionResponse {
+    model: Option<String>,
+    choices: Vec<Choice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChunk {
+    model: Option<String>,
+    choices: Vec<StreamChoice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    content: Option<String>,
+    /// Thinking text; `reasoning_content` on most compatible servers,
+    /// `reasoning` on a few.
+    #[serde(alias = "reasoning")]
+    reasoning_content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelEntry {
+    id: String,
+    owned_by: Option<String>,
+}
+
+pub(super) fn call_meta(
+    model: &str,
+    usage: Option<&Usage>,
+    first_token: Option<std::time::Duration>,
+    started: std::time::Instant,
+    retries: u32,
+) -> CallMeta {
+    CallMeta {
+        model: model.to_string(),
+        prompt_tokens: usage.map(|u| u.prompt_tokens),
+        completion_tokens: usage.map(|u| u.completion_tokens),
+        reasoning_tokens: usage
+            .and_then(|u| u.completion_tokens_details.as_ref())
+            .map(|d| d.reasoning_tokens),
+        time_to_first_token_ms: first_token.map(|d| d.as_millis() as u64),
+        latency_ms: started.elapsed().as_millis() as u64,
+        retries,
+    }
+}
+
+pub(super) async fn check_status(resp: reqwest::Response) -> Result<reqwest::Response> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(resp);
+    }
+    let retry_after_secs = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok());
+    let body = resp.text().await.unwrap_or_default();
+    bail!(SwError::ProviderHttp {
+        status: status.as_u16(),
+        body,
+        retry_after_secs,
+    });
+}
+
+#[async_trait]
+impl Provider for OpenAiProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, req: &ChatRequest) -> Result<ChatResponse> {
+        self.throttle(req).await;
+        let started = std::time::Instant::now();
+        let resp = self.post_completions(&self.body(req, false)).await?;
+        let parsed: CompletionResponse = resp
+            .json()
+            .await
+            .context("failed to parse provider response")?;
+        let content = parsed
+            .choices
+            .first()
+            .and_then(|c| c.message.content.clone())
+            .unwrap_or_default();
+        let model = parsed.model.unwrap_or_else(|| req.model.clone());
+        let meta = call_meta(&model, parsed.usage.as_ref(), None, started, 0);
+        Ok(ChatResponse {
+            content,
+            model,
+            meta,
+        })
+    }
+
+    async fn send_stream(&self, req: &ChatRequest, on_delta: DeltaFn<'_>) -> Result<ChatResponse> {
+        self.throttle(req).await;
+        let started = std::time::Instant::now();
+        let mut retries = 0u32;
+        loop {
+            match self.stream_attempt(req, on_delta, started).await {
+                Ok((content, model, usage, first_token)) => {
+                    let meta = call_meta(&model, usage.as_ref(), first_token, started, retries);
+                    return Ok(ChatResponse {
+                        content,
+                        model,
+                        meta,
+                    });
+                }
+                // Retry once, but only when nothing reached the caller yet —
+                // a mid-response stall cannot be resumed transparently.
+                Err(e)
+                    if retries == 0
+                        && matches!(
+                            e.downcast_ref::<SwError>(),
+                            Some(SwError::StreamStalled {
+                                phase: "first-token",
+                                ..
+                            })
+                        ) =>
+                {
+                    retries += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let resp = self
+            .request(reqwest::Method::GET, "/models")
+            .send()
+            .await
+            .context("request to provider failed")?;
+        let resp = check_status(resp).await?;
+        let parsed: ModelList = resp.json().await.context("failed to parse model list")?;
+        Ok(parsed
+            .data
+            .into_iter()
+            .map(|m| ModelInfo {
+                id: m.id,
+                owned_by: m.owned_by,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,333 @@
+//! Optional Language Server Protocol client.
+//!
+//! Speaks JSON-RPC over stdio to a configured server (rust-analyzer,
+//! pyright, …) to fetch hover and reference facts — ground truth from a
+//! real semantic model where [`crate::analysis`] only pattern-matches.
+//! Everything here is best effort: callers fall back to the regex path
+//! on any failure, so a missing or slow server never breaks a command.
+
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+
+use crate::config::LspConfig;
+
+/// A connection to one spawned language server, initialized against a
+/// workspace root. Dropped clients kill the server process.
+pub struct LspClient {
+    child: std::process::Child,
+    stdin: std::process::ChildStdin,
+    incoming: std::sync::mpsc::Receiver<Value>,
+    timeout: Duration,
+    next_id: i64,
+}
+
+/// What the server knows about one symbol occurrence.
+pub struct SymbolFacts {
+    /// Rendered hover text (usually markdown with the precise signature).
+    pub hover: Option<String>,
+    /// Reference locations as `(path, 1-based line)`, definition included
+    /// or not at the server's discretion.
+    pub references: Vec<(PathBuf, usize)>,
+}
+
+impl LspClient {
+    /// Spawn `command` (split on whitespace) and run the LSP handshake
+    /// against `root`.
+    pub fn start(command: &str, root: &Path, timeout: Duration) -> Result<Self> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next().context("empty LSP server command")?;
+        let mut child = std::process::Command::new(program)
+            .args(parts)
+            .current_dir(root)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .with_context(|| format!("failed to start language server `{program}`"))?;
+        let stdin = child.stdin.take().expect("piped stdin");
+        let stdout = child.stdout.take().expect("piped stdout");
+
+        // Frames are parsed on a thread so requests can time out instead
+        // of blocking forever on a wedged server.
+        let (tx, incoming) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            while let Ok(Some(frame)) = read_frame(&mut reader) {
+                if tx.send(frame).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut client = Self {
+            child,
+            stdin,
+            incoming,
+            timeout,
+            next_id: 0,
+        };
+        let root_uri = uri_for(root)?;
+        client.request(
+            "initialize",
+            json!({
+                "processId": std::process::id(),
+                "rootUri": root_uri,
+                "capabilities": {
+                    "textDocument": {
+                        "hover": {"contentFormat": ["markdown", "plaintext"]}
+                    }
+                },
+            }),
+        )?;
+        client.notify("initialized", json!({}))?;
+        Ok(client)
+    }
+
+    /// Announce a document so position requests against it resolve.
+    pub fn open(&mut self, path: &Path, language_id: &str, content: &str) -> Result<()> {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": uri_for(path)?,
+                    "languageId": language_id,
+                    "version": 1,
+                    "text": content,
+                }
+            }),
+        )
+    }
+
+    /// Hover text at a 0-based position, if the server has any.
+    pub fn hover(&mut self, path: &Path, line: usize, character: usize) -> Result<Option<String>> {
+        let result = self.request(
+            "textDocument/hover",
+            position_params(path, line, character)?,
+        )?;
+        Ok(hover_text(&result))
+    }
+
+    /// Reference locations for the symbol at a 0-based position.
+    pub fn references(
+        &mut self,
+        path: &Path,
+        line: usize,
+        character: usize,
+    ) -> Result<Vec<(PathBuf, usize)>> {
+        let mut params = position_params(path, line, character)?;
+        params["context"] = json!({"includeDeclaration": false});
+        let result = self.request("textDocument/references", params)?;
+        let mut refs = Vec::new();
+        for loc in result.as_array().into_iter().flatten() {
+            let Some(path) = loc.get("uri").and_then(Value::as_str).and_then(path_for) else {
+                continue;
+            };
+            let line = loc
+                .pointer("/range/start/line")
+                .and_then(Value::as_u64)
+                .unwrap_or(0) as usize;
+            refs.push((path, line + 1));
+        }
+        Ok(refs)
+    }
+
+    /// Polite teardown; the Drop impl covers servers that ignore it.
+    pub fn shutdown(&mut self) {
+        let _ = self.request("shutdown", Value::Null);
+        let _ = self.notify("exit", Value::Null);
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        write_frame(
+            &mut self.stdin,
+            &json!({"jsonrpc": "2.0", "method": method, "params": params}),
+        )
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let id = self.next_id;
+        write_frame(
+            &mut self.stdin,
+            &json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}),
+        )?;
+        let deadline = std::time::Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline
+                .checked_duration_since(std::time::Instant::now())
+                .with_context(|| format!("language server timed out on {method}"))?;
+            let msg = self
+                .incoming
+                .recv_timeout(remaining)
+                .with_context(|| format!("language server timed out on {method}"))?;
+            // Server-to-client requests (registerCapability, configuration)
+            // expect an answer; a null result keeps the session moving.
+            if msg.get("method").is_some() {
+                if let Some(req_id) = msg.get("id") {
+                    write_frame(
+                        &mut self.stdin,
+                        &json!({"jsonrpc": "2.0", "id": req_id, "result": Value::Null}),
+                    )?;
+                }
+                continue;
+            }
+            if msg.get("id") != Some(&json!(id)) {
+                continue;
+            }
+            if let Some(err) = msg.get("error") {
+                bail!(
+                    "{method} failed: {}",
+                    err.get("message").and_then(Value::as_str).unwrap_or("?")
+                );
+            }
+            return Ok(msg.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Hover and references for the symbol `name` on `line` (1-based) of
+/// `path`, or `None` when no server is configured for the language.
+/// Spawns a fresh server per query; fine for one-shot commands.
+pub fn symbol_facts(
+    root: &Path,
+    path: &Path,
+    line: usize,
+    name: &str,
+    cfg: &LspConfig,
+) -> Result<Option<SymbolFacts>> {
+    let language = crate::analysis::language_for_path(path);
+    let Some(command) = cfg.servers.get(language) else {
+        return Ok(None);
+    };
+    let content = std::fs::read_to_string(path)?;
+    let Some(character) = content
+        .lines()
+        .nth(line.saturating_sub(1))
+        .and_then(|text| text.find(name))
+    else {
+        return Ok(None);
+    };
+
+    let mut client = LspClient::start(command, root, Duration::from_secs(cfg.timeout_secs))?;
+    client.open(path, &language.to_ascii_lowercase(), &content)?;
+    let hover = client.hover(path, line - 1, character)?;
+    let references = client
+        .references(path, line - 1, character)
+        .unwrap_or_default();
+    client.shutdown();
+    Ok(Some(SymbolFacts { hover, references }))
+}
+
+fn uri_for(path: &Path) -> Result<String> {
+    let abs = path
+        .canonicalize()
+        .with_context(|| format!("cannot resolve {}", path.display()))?;
+    Ok(format!("file://{}", abs.display()))
+}
+
+fn path_for(uri: &str) -> Option<PathBuf> {
+    uri.strip_prefix("file://").map(PathBuf::from)
+}
+
+fn position_params(path: &Path, line: usize, character: usize) -> Result<Value> {
+    Ok(json!({
+        "textDocument": {"uri": uri_for(path)?},
+        "position": {"line": line, "character": character},
+    }))
+}
+
+/// Flatten the protocol's `MarkedString | MarkedString[] | MarkupContent`
+/// hover shapes into plain text.
+fn hover_text(result: &Value) -> Option<String> {
+    let contents = result.get("contents")?;
+    let mut parts = Vec::new();
+    let mut push = |v: &Value| {
+        if let Some(s) = v.as_str() {
+            parts.push(s.to_string());
+        } else if let Some(s) = v.get("value").and_then(Value::as_str) {
+            parts.push(s.to_string());
+        }
+    };
+    match contents {
+        Value::Array(items) => items.iter().for_each(push),
+        other => push(other),
+    }
+    let text = parts.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn write_frame(out: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(out, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    out.flush()?;
+    Ok(())
+}
+
+/// One `Content-Length`-framed message; `None` on clean EOF.
+fn read_frame(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = Some(value.trim().parse()?);
+        }
+    }
+    let length = length.context("frame without Content-Length header")?;
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip() {
+        let msg = json!({"jsonrpc": "2.0", "id": 1, "result": {"ok": true}});
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &msg).unwrap();
+        let mut reader = BufReader::new(buf.as_slice());
+        assert_eq!(read_frame(&mut reader).unwrap(), Some(msg));
+        assert_eq!(read_frame(&mut reader).unwrap(), None);
+    }
+
+    #[test]
+    fn hover_text_handles_the_protocol_shapes() {
+        assert_eq!(
+            hover_text(&json!({"contents": "fn foo()"})).as_deref(),
+            Some("fn foo()")
+        );
+        assert_eq!(
+            hover_text(
+                &json!({"contents": {"kind": "markdown", "value": "```rust\nfn foo()\n```"}})
+            )
+            .as_deref(),
+            Some("```rust\nfn foo()\n```")
+        );
+        assert_eq!(
+            hover_text(&json!({"contents": ["a", {"value": "b"}]})).as_deref(),
+            Some("a\nb")
+        );
+        assert_eq!(hover_text(&json!({"contents": []})), None);
+        assert_eq!(hover_text(&Value::Null), None);
+    }
+}
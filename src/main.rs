@@ -0,0 +1,278 @@
+mod analysis;
+mod app;
+mod artifacts;
+mod backups;
+mod cancel;
+mod checkpoint;
+mod cli;
+mod commands;
+mod config;
+mod context;
+mod datafile;
+mod diff;
+mod error;
+mod fsutil;
+mod gitutil;
+mod history;
+mod llm;
+mod lsp;
+mod markdown;
+mod owners;
+mod platform;
+mod provenance;
+mod ratelimit;
+mod redact;
+mod render;
+mod repomap;
+mod session;
+mod stats;
+mod stt;
+mod workspace;
+
+use clap::Parser;
+
+use crate::app::AppContext;
+use crate::cli::{
+    BackupsCommands, BatchCommands, CheckpointCommands, CiCommands, Cli, Commands, DebugCommands,
+    DepsCommands, DiffCommands, FilesCommands, HistoryCommands, I18nCommands, IssueCommands,
+    ModelsCommands, ProvenanceCommands, ReleaseCommands, ReportCommands, ScriptCommands,
+    ServeCommands, SessionCommands, TemplateCommands, TestsCommands,
+};
+use crate::config::Config;
+use crate::render::Renderer;
+
+#[tokio::main]
+async fn main() {
+    let config = match Config::load() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            std::process::exit(1);
+        }
+    };
+    // Config-level default flags are merged into the raw command line so
+    // clap still owns validation; explicit flags win.
+    let args = cli::apply_default_flags(std::env::args().collect(), &config.defaults);
+    let cli = Cli::parse_from(args);
+    let render = Renderer::new(
+        cli.format,
+        cli.quiet,
+        cli.json_stream,
+        cli.output.clone(),
+        cli.raw,
+    );
+
+    let profile_name = cli
+        .profile
+        .clone()
+        .unwrap_or_else(|| config.default_profile.clone());
+    let redactor = if cli.no_redact {
+        None
+    } else {
+        match redact::Redactor::from_config(&config) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                std::process::exit(1);
+            }
+        }
+    };
+    let deny = if cli.allow_denied {
+        None
+    } else {
+        match redact::PathPolicy::from_config(&config) {
+            Ok(p) => Some(p),
+            Err(e) => {
+                eprintln!("error: {e:#}");
+                std::process::exit(1);
+            }
+        }
+    };
+    let workspace = match &cli.workspace {
+        Some(path) => path.clone(),
+        None => match std::env::current_dir() {
+            Ok(cwd) => workspace::detect_workspace_root(&cwd),
+            Err(e) => {
+                eprintln!("error: cannot determine current directory: {e:#}");
+                std::process::exit(1);
+            }
+        },
+    };
+    let ctx = AppContext {
+        config,
+        render,
+        verbose: cli.verbose,
+        profile_name,
+        model_override: cli.model.clone(),
+        provider_override: cli.provider.clone(),
+        cancel: cancel::install_ctrl_c(),
+        workspace,
+        redactor,
+        deny,
+        show_prompt: cli.show_prompt,
+        usage: std::sync::Arc::new(std::sync::Mutex::new(stats::UsageTally::default())),
+    };
+
+    let started = std::time::Instant::now();
+    let result = run(&cli.command, &ctx).await;
+    // A prompt preview ends the run on purpose; it is not a failure.
+    let dry_run = result
+        .as_ref()
+        .err()
+        .is_some_and(|e| e.to_string() == app::DRY_RUN_LLM);
+
+    if ctx.config.stats && cli.command.name() != "stats" {
+        let tally = ctx.usage.lock().unwrap();
+        let cost = tally.model.as_deref().and_then(|m| {
+            stats::cost_usd(&ctx.config, m, tally.prompt_tokens, tally.completion_tokens)
+        });
+        let rec = stats::StatsRecord {
+            timestamp: chrono::Utc::now(),
+            command: cli.command.name().to_string(),
+            project: stats::current_project(),
+            model: tally.model.clone(),
+            prompt_tokens: tally.prompt_tokens,
+            completion_tokens: tally.completion_tokens,
+            cost_usd: cost,
+            duration_ms: started.elapsed().as_millis() as u64,
+            success: result.is_ok() || dry_run,
+        };
+        if let Err(e) = stats::record(&rec) {
+            if cli.verbose {
+                eprintln!("warning: failed to record stats: {e:#}");
+            }
+        }
+    }
+    // Reruns record the replayed command themselves (in the child
+    // process), so recording the wrapper would double-count.
+    if !matches!(cli.command.name(), "rerun" | "history list" | "stats") {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if let Err(e) = history::record(cli.command.name(), &args, result.is_ok() || dry_run) {
+            if cli.verbose {
+                eprintln!("warning: failed to record history: {e:#}");
+            }
+        }
+    }
+
+    if let Err(e) = result {
+        if dry_run {
+            return;
+        }
+        let code = error::classify_error(&e);
+        eprintln!("error[{code}]: {e:#}");
+        if let Some(hint) = error::error_hint(&e) {
+            eprintln!("hint: {hint}");
+        }
+        // 130 is the conventional exit status for SIGINT.
+        std::process::exit(if code == cancel::INTERRUPTED { 130 } else { 1 });
+    }
+}
+
+async fn run(command: &Commands, ctx: &AppContext) -> anyhow::Result<()> {
+    match command {
+        Commands::Init => commands::init::cmd_init(ctx).await,
+        Commands::Ask(args) => commands::ask::cmd_ask(args, ctx).await,
+        Commands::Chat(args) => commands::chat::cmd_chat(args, ctx).await,
+        Commands::Summarize(args) => commands::summarize::cmd_summarize(args, ctx).await,
+        Commands::Explain(args) => commands::explain::cmd_explain(args, ctx).await,
+        Commands::Review(args) => commands::review::cmd_review(args, ctx).await,
+        Commands::CommitMsg(args) => commands::commitmsg::cmd_commit_msg(args, ctx).await,
+        Commands::CommitLint(args) => commands::commitlint::cmd_commit_lint(args, ctx).await,
+        Commands::Generate(args) => commands::generate::cmd_generate(args, ctx).await,
+        Commands::Diff(args) => match &args.command {
+            DiffCommands::Propose(a) => commands::diffcmd::cmd_diff_propose(a, ctx).await,
+            DiffCommands::Apply(a) => commands::diffcmd::cmd_diff_apply(a, ctx).await,
+        },
+        Commands::Grep(args) => commands::grep::cmd_grep(args, ctx).await,
+        Commands::Map(args) => commands::map::cmd_map(args, ctx).await,
+        Commands::Diagram(args) => commands::diagram::cmd_diagram(args, ctx).await,
+        Commands::Files(args) => match &args.command {
+            FilesCommands::List(a) => commands::files::cmd_files_list(a, ctx).await,
+            FilesCommands::Compare(a) => commands::files::cmd_files_compare(a, ctx).await,
+            FilesCommands::Sync(a) => commands::files::cmd_files_sync(a, ctx).await,
+            FilesCommands::Duplicates(a) => commands::files::cmd_files_duplicates(a, ctx).await,
+            FilesCommands::Analyze(a) => commands::files::cmd_files_analyze(a, ctx).await,
+            FilesCommands::Security(a) => commands::files::cmd_files_security(a, ctx).await,
+            FilesCommands::Stats(a) => commands::files::cmd_files_stats(a, ctx).await,
+        },
+        Commands::Script(args) => match &args.command {
+            ScriptCommands::Run(a) => commands::script::cmd_script_run(a, ctx).await,
+        },
+        Commands::Run(args) => commands::run::cmd_run(args, ctx).await,
+        Commands::Ci(args) => match &args.command {
+            CiCommands::Triage(a) => commands::ci::cmd_ci_triage(a, ctx).await,
+        },
+        Commands::Tests(args) => match &args.command {
+            TestsCommands::Flaky(a) => commands::testscmd::cmd_tests_flaky(a, ctx).await,
+        },
+        Commands::Checkpoint(args) => match &args.command {
+            CheckpointCommands::Create(a) => {
+                commands::checkpoint::cmd_checkpoint_create(a, ctx).await
+            }
+            CheckpointCommands::List(a) => commands::checkpoint::cmd_checkpoint_list(a, ctx).await,
+            CheckpointCommands::Show(a) => commands::checkpoint::cmd_checkpoint_show(a, ctx).await,
+            CheckpointCommands::Restore(a) => {
+                commands::checkpoint::cmd_checkpoint_restore(a, ctx).await
+            }
+        },
+        Commands::Backups(args) => match &args.command {
+            BackupsCommands::List => commands::backups::cmd_backups_list(ctx).await,
+            BackupsCommands::Restore(a) => commands::backups::cmd_backups_restore(a, ctx).await,
+        },
+        Commands::Template(args) => match &args.command {
+            TemplateCommands::List => commands::template::cmd_template_list(ctx).await,
+            TemplateCommands::Generate(a) => {
+                commands::template::cmd_template_generate(a, ctx).await
+            }
+            TemplateCommands::Upgrade(a) => commands::template::cmd_template_upgrade(a, ctx).await,
+        },
+        Commands::Batch(args) => match &args.command {
+            BatchCommands::Transform(a) => commands::batch::cmd_batch_transform(a, ctx).await,
+        },
+        Commands::Migrate(args) => commands::migrate::cmd_migrate(args, ctx).await,
+        Commands::I18n(args) => match &args.command {
+            I18nCommands::Extract(a) => commands::i18n::cmd_i18n_extract(a, ctx).await,
+            I18nCommands::Translate(a) => commands::i18n::cmd_i18n_translate(a, ctx).await,
+        },
+        Commands::Models(args) => match &args.command {
+            ModelsCommands::List(a) => commands::models::cmd_models_list(a, ctx).await,
+            ModelsCommands::Refresh(a) => commands::models::cmd_models_refresh(a, ctx).await,
+        },
+        Commands::Session(args) => match &args.command {
+            SessionCommands::List => commands::sessioncmd::cmd_session_list(ctx).await,
+            SessionCommands::Show(a) => commands::sessioncmd::cmd_session_show(a, ctx).await,
+            SessionCommands::Clear(a) => commands::sessioncmd::cmd_session_clear(a, ctx).await,
+            SessionCommands::Publish(a) => commands::sessioncmd::cmd_session_publish(a, ctx).await,
+            SessionCommands::Export(a) => commands::sessioncmd::cmd_session_export(a, ctx).await,
+            SessionCommands::Import(a) => commands::sessioncmd::cmd_session_import(a, ctx).await,
+        },
+        Commands::Agent(args) => commands::agent::cmd_agent(args, ctx).await,
+        Commands::Issue(args) => match &args.command {
+            IssueCommands::Start(a) => commands::issue::cmd_issue_start(a, ctx).await,
+        },
+        Commands::Deps(args) => match &args.command {
+            DepsCommands::Audit(a) => commands::deps::cmd_deps_audit(a, ctx).await,
+        },
+        Commands::Debug(args) => match &args.command {
+            DebugCommands::Bundle(a) => commands::debug::cmd_debug_bundle(a, ctx).await,
+        },
+        Commands::Stats(args) => commands::stats::cmd_stats(args, ctx).await,
+        Commands::Serve(args) => match &args.command {
+            ServeCommands::Openai(a) => commands::serve::cmd_serve_openai(a, ctx).await,
+        },
+        Commands::Compare(args) => commands::compare::cmd_compare(args, ctx).await,
+        Commands::Rerun(args) => commands::rerun::cmd_rerun(args, ctx).await,
+        Commands::History(args) => match &args.command {
+            HistoryCommands::List => commands::rerun::cmd_history_list(ctx).await,
+        },
+        Commands::Report(args) => match &args.command {
+            ReportCommands::Nightly(a) => commands::report::cmd_report_nightly(a, ctx).await,
+        },
+        Commands::Provenance(args) => match &args.command {
+            ProvenanceCommands::Show(a) => commands::provenance::cmd_provenance_show(a, ctx).await,
+        },
+        Commands::Release(args) => match &args.command {
+            ReleaseCommands::Prepare(a) => commands::release::cmd_release_prepare(a, ctx).await,
+        },
+    }
+}
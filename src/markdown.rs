@@ -0,0 +1,236 @@
+//! Minimal terminal markdown rendering.
+//!
+//! Model responses are markdown; in text mode on a terminal we style
+//! headings, emphasis, lists, and fenced code blocks with ANSI escapes
+//! instead of printing the markup raw. This is deliberately a small
+//! hand-rolled pass, not a full CommonMark implementation.
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const ITALIC: &str = "\x1b[3m";
+const CYAN: &str = "\x1b[36m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// Keywords highlighted inside fenced code blocks; one shared set covers
+/// the languages we meet in practice without a grammar per language.
+const KEYWORDS: &[&str] = &[
+    "fn", "let", "mut", "pub", "use", "mod", "struct", "enum", "impl", "trait", "match", "if",
+    "else", "for", "while", "loop", "return", "async", "await", "const", "static", "def", "class",
+    "import", "from", "function", "var", "true", "false", "None", "null",
+];
+
+/// Render markdown with ANSI styling for terminal display.
+pub fn render_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_code = false;
+    for line in text.lines() {
+        if let Some(styled) = render_line(line, &mut in_code) {
+            out.push_str(&styled);
+            out.push('\n');
+        }
+    }
+    out.trim_end_matches('\n').to_string()
+}
+
+/// Style one line under the current fence state, toggling it on fence
+/// markers. `None` means the line is swallowed (the fence itself).
+fn render_line(line: &str, in_code: &mut bool) -> Option<String> {
+    if line.trim_start().starts_with("```") {
+        *in_code = !*in_code;
+        return None;
+    }
+    if *in_code {
+        return Some(format!("  {}", highlight_code(line)));
+    }
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    if let Some(heading) = trimmed.strip_prefix('#') {
+        let title = heading.trim_start_matches('#').trim();
+        Some(format!("{BOLD}{CYAN}{title}{RESET}"))
+    } else if let Some(item) = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+    {
+        Some(format!("{indent}• {}", style_inline(item)))
+    } else {
+        Some(format!("{indent}{}", style_inline(trimmed)))
+    }
+}
+
+/// Incremental renderer for streamed tokens. Deltas are buffered only
+/// until their line completes — enough context to style fences, headings,
+/// and lists correctly without perceptible latency — and fence state
+/// carries across lines so code blocks stream highlighted.
+pub struct StreamRenderer {
+    /// Pass-through mode: deltas are returned unchanged (non-terminal,
+    /// `--raw`, `--output`).
+    styled: bool,
+    buf: String,
+    in_code: bool,
+}
+
+impl StreamRenderer {
+    pub fn new(styled: bool) -> Self {
+        Self {
+            styled,
+            buf: String::new(),
+            in_code: false,
+        }
+    }
+
+    /// Feed one delta; returns whatever is ready to print, which may be
+    /// empty (mid-line) or several lines (a chunk with embedded newlines).
+    pub fn push(&mut self, delta: &str) -> String {
+        if !self.styled {
+            return delta.to_string();
+        }
+        self.buf.push_str(delta);
+        let mut out = String::new();
+        while let Some(pos) = self.buf.find('\n') {
+            let line: String = self.buf.drain(..=pos).collect();
+            if let Some(styled) = render_line(line.trim_end_matches('\n'), &mut self.in_code) {
+                out.push_str(&styled);
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Flush anything after the final newline once the stream ends.
+    pub fn finish(&mut self) -> String {
+        if !self.styled || self.buf.is_empty() {
+            return String::new();
+        }
+        let line = std::mem::take(&mut self.buf);
+        render_line(&line, &mut self.in_code).unwrap_or_default()
+    }
+}
+
+/// Apply `**bold**`, `*italic*`, and `` `code` `` spans within one line.
+fn style_inline(line: &str) -> String {
+    let styled = replace_span(line, "**", BOLD);
+    let styled = replace_span(&styled, "`", CYAN);
+    replace_span(&styled, "*", ITALIC)
+}
+
+/// Replace paired `marker`s with `style`…reset, leaving unpaired markers
+/// untouched.
+fn replace_span(line: &str, marker: &str, style: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    loop {
+        let Some(start) = rest.find(marker) else {
+            out.push_str(rest);
+            return out;
+        };
+        let after = &rest[start + marker.len()..];
+        let Some(end) = after.find(marker) else {
+            out.push_str(rest);
+            return out;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(style);
+        out.push_str(&after[..end]);
+        out.push_str(RESET);
+        rest = &after[end + marker.len()..];
+    }
+}
+
+/// Tiny keyword/string/comment highlighter for fenced code blocks.
+fn highlight_code(line: &str) -> String {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("//") || trimmed.starts_with('#') {
+        return format!("{DIM}{line}{RESET}");
+    }
+    let mut out = String::with_capacity(line.len());
+    let mut word = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        flush_word(&mut out, &mut word);
+        if c == '"' || c == '\'' {
+            // Consume through the matching quote as one string literal.
+            let mut literal = String::from(c);
+            for s in chars.by_ref() {
+                literal.push(s);
+                if s == c {
+                    break;
+                }
+            }
+            out.push_str(&format!("{GREEN}{literal}{RESET}"));
+        } else {
+            out.push(c);
+        }
+    }
+    flush_word(&mut out, &mut word);
+    out
+}
+
+fn flush_word(out: &mut String, word: &mut String) {
+    if word.is_empty() {
+        return;
+    }
+    if KEYWORDS.contains(&word.as_str()) {
+        out.push_str(&format!("{BOLD}{word}{RESET}"));
+    } else {
+        out.push_str(word);
+    }
+    word.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn styles_headings_lists_and_spans() {
+        let rendered = render_markdown("# Title\n- item with **bold**\nplain `code`");
+        assert!(rendered.contains(&format!("{BOLD}{CYAN}Title{RESET}")));
+        assert!(rendered.contains("• item"));
+        assert!(rendered.contains(&format!("{BOLD}bold{RESET}")));
+        assert!(rendered.contains(&format!("{CYAN}code{RESET}")));
+    }
+
+    #[test]
+    fn highlights_fenced_code() {
+        let rendered = render_markdown("```rust\nfn main() {}\n// note\n```");
+        assert!(rendered.contains(&format!("{BOLD}fn{RESET}")));
+        assert!(rendered.contains(&format!("{DIM}// note{RESET}")));
+        assert!(!rendered.contains("```"));
+    }
+
+    #[test]
+    fn leaves_unpaired_markers_alone() {
+        assert_eq!(render_markdown("a * b"), "a * b");
+    }
+
+    #[test]
+    fn stream_renderer_matches_the_batch_pass() {
+        let text = "# Title\n```rust\nfn main() {}\n```\n- done";
+        let mut streamed = String::new();
+        let mut renderer = StreamRenderer::new(true);
+        // Deliberately awkward chunk boundaries: mid-word, mid-fence.
+        for chunk in [
+            "# Ti",
+            "tle\n``",
+            "`rust\nfn ma",
+            "in() {}\n```\n- do",
+            "ne",
+        ] {
+            streamed.push_str(&renderer.push(chunk));
+        }
+        streamed.push_str(&renderer.finish());
+        assert_eq!(streamed.trim_end_matches('\n'), render_markdown(text));
+    }
+
+    #[test]
+    fn stream_renderer_passes_through_when_unstyled() {
+        let mut renderer = StreamRenderer::new(false);
+        assert_eq!(renderer.push("# raw\n```"), "# raw\n```");
+        assert_eq!(renderer.finish(), "");
+    }
+}
@@ -0,0 +1,147 @@
+//! File ownership from CODEOWNERS, with a git shortlog fallback.
+//!
+//! Implements the GitHub semantics that matter here: one gitignore-style
+//! pattern plus owners per line, later rules win, `/`-anchored patterns
+//! are root-relative, and a pattern with no owners clears ownership.
+//! Matching is glob-based and approximate rather than a full gitignore
+//! engine; CODEOWNERS files in the wild stay well within this subset.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// Where a CODEOWNERS file may live, in lookup order.
+const LOCATIONS: &[&str] = &["CODEOWNERS", ".github/CODEOWNERS", "docs/CODEOWNERS"];
+
+struct Rule {
+    matcher: globset::GlobSet,
+    owners: Vec<String>,
+}
+
+pub struct Owners {
+    rules: Vec<Rule>,
+}
+
+fn build_matcher(pattern: &str) -> Result<globset::GlobSet> {
+    let anchored = pattern.starts_with('/');
+    let mut base = pattern
+        .trim_start_matches('/')
+        .trim_end_matches('/')
+        .to_string();
+    // Unanchored bare names match at any depth, like gitignore.
+    if !anchored && !base.contains('/') {
+        base = format!("**/{base}");
+    }
+    let mut builder = globset::GlobSetBuilder::new();
+    // A pattern owns both the path itself and everything beneath it.
+    for candidate in [base.clone(), format!("{base}/**")] {
+        builder.add(
+            globset::Glob::new(&candidate)
+                .with_context(|| format!("invalid CODEOWNERS pattern '{pattern}'"))?,
+        );
+    }
+    Ok(builder.build()?)
+}
+
+impl Owners {
+    /// Load the first CODEOWNERS file found under `root`; `None` when the
+    /// repository has none.
+    pub fn load(root: &Path) -> Result<Option<Owners>> {
+        for location in LOCATIONS {
+            let path = root.join(location);
+            if path.is_file() {
+                let content = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                return Ok(Some(Self::parse(&content)?));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn parse(content: &str) -> Result<Owners> {
+        let mut rules = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next().expect("non-empty line");
+            rules.push(Rule {
+                matcher: build_matcher(pattern)?,
+                owners: parts.map(|s| s.to_string()).collect(),
+            });
+        }
+        Ok(Owners { rules })
+    }
+
+    /// Owners of a root-relative `path`; the last matching rule wins, and
+    /// an owner-less winning rule means the file is explicitly unowned.
+    pub fn owners_for(&self, path: &Path) -> Option<&[String]> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|r| r.matcher.is_match(path))
+            .map(|r| r.owners.as_slice())
+            .filter(|owners| !owners.is_empty())
+    }
+}
+
+/// The author with the most commits touching `path`, per `git shortlog`.
+/// Used as a fallback for files no CODEOWNERS rule covers.
+pub fn top_committer(path: &Path) -> Option<String> {
+    let spec = path.to_string_lossy();
+    let out = crate::gitutil::git(&["shortlog", "-ns", "HEAD", "--", &spec]).ok()?;
+    let name = out.lines().next()?.split('\t').nth(1)?.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Display owner of `path` under `root`: CODEOWNERS owners joined with
+/// commas, falling back to the top committer when no rule matches.
+pub fn owner_of(owners: &Owners, root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    match owners.owners_for(rel) {
+        Some(list) => Some(list.join(", ")),
+        None => top_committer(path),
+    }
+}
+
+/// True when `filter` (with or without a leading `@`) names one of `owners`.
+pub fn owner_matches(filter: &str, owners: &[String]) -> bool {
+    let want = filter.trim_start_matches('@').to_ascii_lowercase();
+    owners
+        .iter()
+        .any(|o| o.trim_start_matches('@').to_ascii_lowercase() == want)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_rules_win_and_bare_patterns_float() {
+        let owners = Owners::parse(
+            "# comment\n\
+             * @org/all\n\
+             *.rs @org/rust\n\
+             /docs/ @org/docs\n\
+             /docs/internal/\n",
+        )
+        .unwrap();
+        let of = |p: &str| owners.owners_for(Path::new(p)).map(|o| o.join(","));
+        assert_eq!(of("src/main.rs"), Some("@org/rust".to_string()));
+        assert_eq!(of("README.md"), Some("@org/all".to_string()));
+        assert_eq!(of("docs/guide.md"), Some("@org/docs".to_string()));
+        // The owner-less rule clears ownership for that subtree.
+        assert_eq!(of("docs/internal/notes.md"), None);
+    }
+
+    #[test]
+    fn owner_filter_ignores_the_at_sign_and_case() {
+        let owners = vec!["@Org/Team".to_string(), "alice".to_string()];
+        assert!(owner_matches("@org/team", &owners));
+        assert!(owner_matches("org/team", &owners));
+        assert!(owner_matches("@alice", &owners));
+        assert!(!owner_matches("@bob", &owners));
+    }
+}
@@ -0,0 +1,117 @@
+//! Platform abstraction: shell selection, path normalization, and
+//! permission handling that degrades to no-ops where a concept does not
+//! exist (e.g. unix modes on Windows).
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+
+/// True when `name` resolves to an executable on PATH.
+pub fn has_command(name: &str) -> bool {
+    let probe = if cfg!(windows) { "where" } else { "which" };
+    Command::new(probe)
+        .arg(name)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Build the interpreter invocation for a script file.
+///
+/// Unix runs everything through `bash`. Windows picks by extension:
+/// PowerShell for `.ps1`, `cmd` for `.bat`/`.cmd`, and falls back to
+/// `pwsh`/`powershell` for anything else.
+pub fn script_invocation(file: &Path) -> tokio::process::Command {
+    if cfg!(windows) {
+        let ext = file
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "bat" | "cmd" => {
+                let mut cmd = tokio::process::Command::new("cmd");
+                cmd.arg("/C").arg(file);
+                cmd
+            }
+            _ => {
+                let shell = if has_command("pwsh") {
+                    "pwsh"
+                } else {
+                    "powershell"
+                };
+                let mut cmd = tokio::process::Command::new(shell);
+                cmd.arg("-File").arg(file);
+                cmd
+            }
+        }
+    } else {
+        let mut cmd = tokio::process::Command::new("bash");
+        cmd.arg(file);
+        cmd
+    }
+}
+
+/// Run a one-off shell command line and capture its output.
+pub fn shell_exec(command: &str) -> Result<std::process::Output> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").arg("/C").arg(command).output()?
+    } else {
+        Command::new("sh").arg("-c").arg(command).output()?
+    };
+    Ok(output)
+}
+
+/// Render a path with forward slashes regardless of platform, for stable
+/// JSON output and manifests.
+pub fn to_portable(path: &Path) -> String {
+    let s = path.display().to_string();
+    if cfg!(windows) {
+        s.replace('\\', "/")
+    } else {
+        s
+    }
+}
+
+/// Copy the permission bits of `from` onto `to`. No-op on Windows, where
+/// unix modes do not exist.
+#[cfg(unix)]
+pub fn copy_permissions(from: &Path, to: &Path) -> Result<()> {
+    let perms = std::fs::metadata(from)?.permissions();
+    std::fs::set_permissions(to, perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn copy_permissions(_from: &Path, _to: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Place text on the system clipboard.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    use anyhow::Context;
+    let mut clipboard = arboard::Clipboard::new().context("clipboard unavailable")?;
+    clipboard
+        .set_text(text.to_string())
+        .context("failed to write to clipboard")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn portable_paths_use_forward_slashes() {
+        let p = Path::new("a").join("b").join("c.rs");
+        assert_eq!(to_portable(&p), "a/b/c.rs");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_scripts_run_through_bash() {
+        let cmd = script_invocation(Path::new("run.sh"));
+        assert_eq!(cmd.as_std().get_program(), "bash");
+    }
+}
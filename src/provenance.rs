@@ -0,0 +1,138 @@
+//! Provenance tracking for generated code.
+//!
+//! When `provenance = true` in config, files written by `generate` and
+//! `diff apply` gain a comment trailer (command, model, timestamp, prompt
+//! hash) and an entry in `.sw/provenance.jsonl`, which `sw provenance
+//! show` reads back — the audit trail some organizations require for
+//! AI-generated code.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceRecord {
+    pub file: String,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    /// Hash of the prompt or patch that produced the content.
+    pub prompt_hash: String,
+}
+
+fn log_path() -> PathBuf {
+    PathBuf::from(".sw").join("provenance.jsonl")
+}
+
+pub fn prompt_hash(input: &str) -> String {
+    format!("{:x}", Sha256::digest(input.as_bytes()))[..12].to_string()
+}
+
+/// The trailer comment for `path`, or `None` for languages without a
+/// line-comment syntax (those are tracked in the log only).
+pub fn trailer(path: &Path, rec: &ProvenanceRecord) -> Option<String> {
+    let prefix = match crate::analysis::language_for_path(path) {
+        "Rust" | "JavaScript" | "TypeScript" | "Go" | "Java" | "C" | "C++" => "//",
+        "Python" | "Ruby" | "Shell" | "TOML" | "YAML" => "#",
+        "SQL" => "--",
+        _ => return None,
+    };
+    Some(format!(
+        "{prefix} sw:generated command={} model={} time={} prompt={}",
+        rec.command,
+        rec.model.as_deref().unwrap_or("none"),
+        rec.timestamp
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        rec.prompt_hash
+    ))
+}
+
+/// Append one record to `.sw/provenance.jsonl`.
+pub fn record(rec: &ProvenanceRecord) -> Result<()> {
+    use std::io::Write;
+    let path = log_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut f = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    writeln!(f, "{}", serde_json::to_string(rec)?)?;
+    Ok(())
+}
+
+/// All records for `file`, oldest first; corrupt lines are skipped.
+pub fn records_for(file: &Path) -> Result<Vec<ProvenanceRecord>> {
+    let path = log_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let wanted = file.display().to_string();
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str::<ProvenanceRecord>(line).ok())
+        .filter(|r| r.file == wanted)
+        .collect())
+}
+
+/// Annotate a freshly written file and log it, when enabled in config.
+/// `content` must already be on disk; the trailer is appended in place.
+pub fn track(
+    enabled: bool,
+    file: &Path,
+    command: &str,
+    model: Option<&str>,
+    prompt: &str,
+) -> Result<Option<ProvenanceRecord>> {
+    if !enabled {
+        return Ok(None);
+    }
+    let rec = ProvenanceRecord {
+        file: file.display().to_string(),
+        command: command.to_string(),
+        model: model.map(str::to_string),
+        timestamp: Utc::now(),
+        prompt_hash: prompt_hash(prompt),
+    };
+    if let Some(line) = trailer(file, &rec) {
+        let mut content = std::fs::read_to_string(file)
+            .with_context(|| format!("failed to read {}", file.display()))?;
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&line);
+        content.push('\n');
+        std::fs::write(file, content)?;
+    }
+    record(&rec)?;
+    Ok(Some(rec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailer_uses_the_file_comment_syntax() {
+        let rec = ProvenanceRecord {
+            file: "x.rs".into(),
+            command: "generate".into(),
+            model: Some("gpt-4o-mini".into()),
+            timestamp: Utc::now(),
+            prompt_hash: prompt_hash("hello"),
+        };
+        let line = trailer(Path::new("x.rs"), &rec).unwrap();
+        assert!(line.starts_with("// sw:generated command=generate"));
+        assert!(line.contains(&format!("prompt={}", rec.prompt_hash)));
+        assert!(trailer(Path::new("x.py"), &rec).unwrap().starts_with('#'));
+        assert_eq!(trailer(Path::new("x.bin"), &rec), None);
+    }
+}
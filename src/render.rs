@@ -0,0 +1,207 @@
+//! Unified output layer.
+//!
+//! All commands route their output through a [`Renderer`] so that stdout
+//! carries only data (text, JSON, or NDJSON depending on `--format`) and
+//! diagnostics always go to stderr. `--quiet` suppresses status chatter
+//! but never data or errors.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text (default).
+    Text,
+    /// A single pretty-printed JSON document.
+    Json,
+    /// Newline-delimited JSON, one object per record.
+    Ndjson,
+}
+
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub format: OutputFormat,
+    pub quiet: bool,
+    /// `--json-stream`: emit NDJSON events while the model responds.
+    pub json_stream: bool,
+    /// `--output`: data goes to this file instead of (or, for streams,
+    /// alongside) stdout; diagnostics stay on stderr either way.
+    output: Option<std::path::PathBuf>,
+    /// `--raw`: print model output without terminal markdown styling.
+    pub raw: bool,
+}
+
+impl Renderer {
+    pub fn new(
+        format: OutputFormat,
+        quiet: bool,
+        json_stream: bool,
+        output: Option<std::path::PathBuf>,
+        raw: bool,
+    ) -> Self {
+        if let Some(path) = &output {
+            // Truncate once up front so later writes can all append.
+            if let Err(e) = std::fs::write(path, "") {
+                eprintln!("warning: cannot write {}: {e}", path.display());
+            }
+        }
+        Self {
+            format,
+            quiet,
+            json_stream,
+            output,
+            raw,
+        }
+    }
+
+    /// Style a markdown document for terminal display. Passes the text
+    /// through untouched with `--raw`, in non-text modes, or when stdout
+    /// is not a terminal (pipes and `--output` files get clean text).
+    pub fn markdown(&self, s: &str) -> String {
+        use std::io::IsTerminal;
+        if self.raw
+            || self.format != OutputFormat::Text
+            || self.output.is_some()
+            || !std::io::stdout().is_terminal()
+        {
+            return s.to_string();
+        }
+        crate::markdown::render_markdown(s)
+    }
+
+    /// Incremental markdown styling for streamed tokens, enabled under
+    /// exactly the same conditions as [`Self::markdown`]; otherwise the
+    /// renderer passes deltas through untouched.
+    pub fn markdown_stream(&self) -> crate::markdown::StreamRenderer {
+        use std::io::IsTerminal;
+        let styled = !self.raw
+            && self.format == OutputFormat::Text
+            && self.output.is_none()
+            && std::io::stdout().is_terminal();
+        crate::markdown::StreamRenderer::new(styled)
+    }
+
+    /// Append data to the `--output` file.
+    fn tee(&self, s: &str) {
+        let Some(path) = &self.output else {
+            return;
+        };
+        use std::io::Write;
+        let result = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut f| f.write_all(s.as_bytes()));
+        if let Err(e) = result {
+            eprintln!("warning: cannot write {}: {e}", path.display());
+        }
+    }
+
+    /// Whether streaming commands should emit NDJSON events (`delta`,
+    /// `usage`, `done`, `error`) instead of raw text deltas.
+    pub fn streams_events(&self) -> bool {
+        self.json_stream
+    }
+
+    /// Emit a data value on stdout. In text mode the provided closure
+    /// renders the human representation; JSON modes serialize the value.
+    pub fn emit<T: Serialize>(&self, value: &T, text: impl FnOnce() -> String) {
+        let rendered = match self.format {
+            OutputFormat::Text => text(),
+            OutputFormat::Json => match serde_json::to_string_pretty(value) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("error: failed to serialize output: {e}");
+                    return;
+                }
+            },
+            OutputFormat::Ndjson => match serde_json::to_string(value) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("error: failed to serialize output: {e}");
+                    return;
+                }
+            },
+        };
+        if let Some(path) = &self.output {
+            self.tee(&format!("{rendered}\n"));
+            self.status(&format!("wrote {}", path.display()));
+        } else if !rendered.is_empty() {
+            println!("{rendered}");
+        }
+    }
+
+    /// Emit one record of a streamed sequence. In NDJSON mode each record is
+    /// its own line; in the other modes the caller should collect records and
+    /// call [`Renderer::emit`] once.
+    pub fn emit_record<T: Serialize>(&self, value: &T) {
+        if let Ok(s) = serde_json::to_string(value) {
+            println!("{s}");
+            self.tee(&format!("{s}\n"));
+        }
+    }
+
+    /// Whether the caller should stream records individually instead of
+    /// collecting them into a single document.
+    pub fn streams_records(&self) -> bool {
+        self.format == OutputFormat::Ndjson
+    }
+
+    /// Raw data on stdout (text mode only callers: streamed model tokens),
+    /// teed into the `--output` file when one is set.
+    pub fn data(&self, s: &str) {
+        print!("{s}");
+        use std::io::Write;
+        let _ = std::io::stdout().flush();
+        self.tee(s);
+    }
+
+    /// Status/progress line on stderr, suppressed by `--quiet`.
+    pub fn status(&self, s: &str) {
+        if !self.quiet {
+            eprintln!("{s}");
+        }
+    }
+
+    /// In-place progress counter on stderr, rewritten with `\r` and
+    /// terminated once `done` reaches `total`. Suppressed by `--quiet`.
+    pub fn progress(&self, label: &str, done: usize, total: usize) {
+        if self.quiet {
+            return;
+        }
+        use std::io::Write;
+        eprint!("\r{label}: {done}/{total}");
+        let _ = std::io::stderr().flush();
+        if done >= total {
+            eprintln!();
+        }
+    }
+
+    /// Warning on stderr; not suppressed by `--quiet`.
+    pub fn warn(&self, s: &str) {
+        eprintln!("warning: {s}");
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.format == OutputFormat::Text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_is_default_streaming_off() {
+        let r = Renderer::new(OutputFormat::Text, false, false, None, false);
+        assert!(!r.streams_records());
+        assert!(r.is_text());
+        assert!(!r.streams_events());
+    }
+
+    #[test]
+    fn ndjson_streams_records() {
+        let r = Renderer::new(OutputFormat::Ndjson, false, false, None, false);
+        assert!(r.streams_records());
+    }
+}
@@ -0,0 +1,330 @@
+//! Ranked repository map: a compact outline of paths, key symbols, and
+//! sizes used to ground prompts in what actually exists in the codebase.
+//! Built from the heuristic symbol extractor, ranked by how often other
+//! files import a file, and cached per repository with per-file mtime
+//! checks so rebuilds only touch what changed.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::analysis::{extract_symbols, language_for_path};
+use crate::commands::files::walk_files;
+use crate::config::Config;
+use crate::context::estimate_tokens;
+
+/// Symbols kept per file; an outline past this adds noise, not grounding.
+const MAX_SYMBOLS_PER_FILE: usize = 30;
+
+/// Files larger than this are listed by path only, never parsed.
+const MAX_PARSE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapEntry {
+    /// Path relative to the mapped root.
+    pub path: String,
+    pub language: String,
+    pub lines: usize,
+    pub size_bytes: u64,
+    /// `kind name` pairs, declaration order, capped.
+    pub symbols: Vec<String>,
+    /// Local module stems this file imports; feeds the ranking.
+    imports: Vec<String>,
+    /// How often other files import this one, plus a symbol-count nudge.
+    /// Recomputed on every load; cached only so JSON output carries it.
+    pub score: usize,
+    mtime_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoMap {
+    pub root: String,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    /// Sorted by descending score.
+    pub entries: Vec<MapEntry>,
+}
+
+fn cache_path(root: &Path) -> Result<PathBuf> {
+    let canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let digest = Sha256::digest(canonical.display().to_string().as_bytes());
+    let key: String = digest.iter().take(6).map(|b| format!("{b:02x}")).collect();
+    Ok(Config::data_dir()?.join("maps").join(format!("{key}.json")))
+}
+
+fn load_cache(root: &Path) -> BTreeMap<String, MapEntry> {
+    let Ok(path) = cache_path(root) else {
+        return BTreeMap::new();
+    };
+    let Ok(raw) = std::fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    let Ok(map) = serde_json::from_str::<RepoMap>(&raw) else {
+        return BTreeMap::new();
+    };
+    map.entries
+        .into_iter()
+        .map(|e| (e.path.clone(), e))
+        .collect()
+}
+
+/// Local import stems, used to rank files by incoming references.
+fn extract_imports(content: &str, language: &str) -> Vec<String> {
+    let re = match language {
+        "Rust" => regex::Regex::new(r"(?m)^\s*(?:pub\s+)?(?:mod|use crate::)\s*([A-Za-z0-9_]+)"),
+        "Python" => regex::Regex::new(r"(?m)^\s*(?:from|import)\s+\.?([A-Za-z0-9_]+)"),
+        "JavaScript" | "TypeScript" => {
+            regex::Regex::new(r#"(?m)from\s+["']\.{1,2}/([A-Za-z0-9_\-/]+)["']"#)
+        }
+        "Go" => regex::Regex::new(r#"(?m)^\s*(?:import\s+)?"[^"]*/([A-Za-z0-9_\-]+)""#),
+        _ => return Vec::new(),
+    }
+    .expect("static regex");
+    let mut imports: Vec<String> = Vec::new();
+    for caps in re.captures_iter(content) {
+        let stem = caps[1].rsplit('/').next().unwrap_or(&caps[1]).to_string();
+        if !imports.contains(&stem) {
+            imports.push(stem);
+        }
+    }
+    imports
+}
+
+fn analyze_entry(path: &Path, rel: String) -> Option<MapEntry> {
+    let meta = std::fs::metadata(path).ok()?;
+    let mtime_secs = meta
+        .modified()
+        .ok()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let language = language_for_path(path).to_string();
+    let (lines, symbols, imports) = if meta.len() <= MAX_PARSE_BYTES {
+        match std::fs::read_to_string(path) {
+            Ok(content) => {
+                let mut symbols: Vec<String> = extract_symbols(&content, &language)
+                    .into_iter()
+                    .map(|s| format!("{} {}", s.kind, s.name))
+                    .collect();
+                symbols.truncate(MAX_SYMBOLS_PER_FILE);
+                let imports = extract_imports(&content, &language);
+                (content.lines().count(), symbols, imports)
+            }
+            // Binary or unreadable: keep the path, skip the outline.
+            Err(_) => (0, Vec::new(), Vec::new()),
+        }
+    } else {
+        (0, Vec::new(), Vec::new())
+    };
+    Some(MapEntry {
+        path: rel,
+        language,
+        lines,
+        size_bytes: meta.len(),
+        symbols,
+        imports,
+        score: 0,
+        mtime_secs,
+    })
+}
+
+/// Build the map for `root`, reusing cached per-file entries whose mtime
+/// and size are unchanged. Pass `refresh` to ignore the cache entirely.
+pub fn load_or_build(root: &Path, refresh: bool) -> Result<RepoMap> {
+    let cached = if refresh {
+        BTreeMap::new()
+    } else {
+        load_cache(root)
+    };
+
+    let mut entries: Vec<MapEntry> = Vec::new();
+    for path in walk_files(root, &[])? {
+        let rel = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        let reuse = cached.get(&rel).filter(|e| {
+            std::fs::metadata(&path).is_ok_and(|m| {
+                m.len() == e.size_bytes
+                    && m.modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                        .is_some_and(|d| d.as_secs() == e.mtime_secs)
+            })
+        });
+        match reuse {
+            Some(entry) => entries.push(entry.clone()),
+            None => {
+                if let Some(entry) = analyze_entry(&path, rel) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+
+    // Rank: incoming imports dominate, symbol count breaks ties, so the
+    // modules everything depends on surface first.
+    let mut incoming: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in &entries {
+        for import in &entry.imports {
+            *incoming.entry(import.clone()).or_default() += 1;
+        }
+    }
+    for entry in &mut entries {
+        let stem = Path::new(&entry.path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        entry.score = incoming.get(&stem).copied().unwrap_or(0) * 10 + entry.symbols.len().min(9);
+    }
+    entries.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+
+    let map = RepoMap {
+        root: root.display().to_string(),
+        generated_at: chrono::Utc::now(),
+        entries,
+    };
+    if let Ok(path) = cache_path(root) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string(&map)?)
+            .with_context(|| format!("failed to write map cache at {}", path.display()))?;
+    }
+    Ok(map)
+}
+
+impl RepoMap {
+    /// Render the outline highest-ranked first, stopping at roughly
+    /// `budget` tokens. Files whose full outline no longer fits degrade
+    /// to a path-only line.
+    pub fn render(&self, budget: usize) -> String {
+        let mut out = String::new();
+        let mut remaining = budget;
+        for entry in &self.entries {
+            let mut block = format!(
+                "{} ({}, {} lines)\n",
+                entry.path, entry.language, entry.lines
+            );
+            for symbol in &entry.symbols {
+                block.push_str(&format!("  {symbol}\n"));
+            }
+            let cost = estimate_tokens(&block);
+            if cost <= remaining {
+                out.push_str(&block);
+                remaining -= cost;
+                continue;
+            }
+            let line = format!("{}\n", entry.path);
+            let cost = estimate_tokens(&line);
+            if cost > remaining {
+                break;
+            }
+            out.push_str(&line);
+            remaining -= cost;
+        }
+        out.trim_end().to_string()
+    }
+
+    /// Local import edges `(from, to)` resolved to entry paths, for
+    /// consumers that need the graph rather than the ranking (e.g. `sw
+    /// diagram`). Stems that match no mapped file are dropped; when two
+    /// files share a stem the higher-ranked one wins.
+    pub fn import_edges(&self) -> Vec<(String, String)> {
+        let mut by_stem: BTreeMap<&str, &str> = BTreeMap::new();
+        for entry in &self.entries {
+            if let Some(stem) = Path::new(&entry.path).file_stem().and_then(|s| s.to_str()) {
+                by_stem.entry(stem).or_insert(entry.path.as_str());
+            }
+        }
+        let mut edges = Vec::new();
+        for entry in &self.entries {
+            for import in &entry.imports {
+                if let Some(&to) = by_stem.get(import.as_str()) {
+                    if to != entry.path {
+                        edges.push((entry.path.clone(), to.to_string()));
+                    }
+                }
+            }
+        }
+        edges
+    }
+}
+
+/// The rendered map for inclusion in a prompt, or `None` when the map is
+/// empty or cannot be built (never fail a command over grounding).
+pub fn prompt_block(root: &Path, budget: usize) -> Option<String> {
+    let map = load_or_build(root, false).ok()?;
+    let rendered = map.render(budget);
+    (!rendered.is_empty()).then_some(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_rust_imports() {
+        let content = "mod render;\npub mod cli;\nuse crate::config::Config;\nuse std::fmt;\n";
+        assert_eq!(
+            extract_imports(content, "Rust"),
+            vec!["render", "cli", "config"]
+        );
+    }
+
+    #[test]
+    fn import_edges_resolve_stems_to_paths() {
+        let entry = |path: &str, imports: &[&str]| MapEntry {
+            path: path.to_string(),
+            language: "Rust".to_string(),
+            lines: 10,
+            size_bytes: 100,
+            symbols: Vec::new(),
+            imports: imports.iter().map(|s| s.to_string()).collect(),
+            score: 0,
+            mtime_secs: 0,
+        };
+        let map = RepoMap {
+            root: ".".to_string(),
+            generated_at: chrono::Utc::now(),
+            entries: vec![
+                entry("src/main.rs", &["config", "missing"]),
+                entry("src/config.rs", &[]),
+            ],
+        };
+        assert_eq!(
+            map.import_edges(),
+            vec![("src/main.rs".to_string(), "src/config.rs".to_string())]
+        );
+    }
+
+    #[test]
+    fn render_respects_budget_and_rank() {
+        let entry = |path: &str, score: usize| MapEntry {
+            path: path.to_string(),
+            language: "Rust".to_string(),
+            lines: 10,
+            size_bytes: 100,
+            symbols: vec!["fn main".to_string()],
+            imports: Vec::new(),
+            score,
+            mtime_secs: 0,
+        };
+        let map = RepoMap {
+            root: ".".to_string(),
+            generated_at: chrono::Utc::now(),
+            entries: vec![entry("src/core.rs", 20), entry("src/leaf.rs", 1)],
+        };
+        let full = map.render(1000);
+        assert!(full.starts_with("src/core.rs"));
+        assert!(full.contains("  fn main"));
+        // A tight budget degrades the top-ranked file to a path-only
+        // line and drops the rest.
+        let tight = map.render(5);
+        assert_eq!(tight, "src/core.rs");
+    }
+}
@@ -0,0 +1,248 @@
+//! Session persistence.
+//!
+//! Conversations are stored as JSONL under the data dir, one file per
+//! named session, one [`SessionRecord`] per line.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::llm::Role;
+
+/// Implicit session holding the most recent `ask` exchange, consumed by
+/// `ask --continue`.
+pub const LAST_SESSION: &str = "last";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub role: Role,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Structured payload for `tool` records; `content` keeps a plain
+    /// rendering so history truncation and display need no special case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<ToolCallRecord>,
+}
+
+/// What a tool invocation did, stored alongside the `tool` role so agent
+/// runs replay with full fidelity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCallRecord {
+    pub name: String,
+    pub arguments: serde_json::Value,
+    pub result: String,
+}
+
+impl SessionRecord {
+    pub fn now(role: Role, content: impl Into<String>, model: Option<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            timestamp: Utc::now(),
+            model,
+            tool: None,
+        }
+    }
+}
+
+/// How much of an externalized body stays inline as a stub, so follow-up
+/// prompts keep the gist without the bulk.
+const STUB_HEAD_CHARS: usize = 400;
+
+/// Record a one-shot command's exchange (summary, explanation, review…)
+/// into a named session so `ask --session NAME` follow-ups have the
+/// artifact in context without re-sending the file. `max_record_bytes`
+/// comes from config; oversized bodies are externalized.
+pub fn record_artifact(
+    name: &str,
+    user: &str,
+    assistant: &str,
+    model: Option<String>,
+    max_record_bytes: usize,
+) -> Result<()> {
+    let store = SessionStore::open()?;
+    store.append_capped(
+        name,
+        &SessionRecord::now(Role::User, user, None),
+        max_record_bytes,
+    )?;
+    store.append_capped(
+        name,
+        &SessionRecord::now(Role::Assistant, assistant, model),
+        max_record_bytes,
+    )?;
+    Ok(())
+}
+
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    pub fn open() -> Result<Self> {
+        let dir = Config::data_dir()?.join("sessions");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create session dir {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.jsonl"))
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.path(name).exists()
+    }
+
+    pub fn load(&self, name: &str) -> Result<Vec<SessionRecord>> {
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let raw = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read session {}", path.display()))?;
+        let mut records = Vec::new();
+        for (i, line) in raw.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let rec: SessionRecord = serde_json::from_str(line).with_context(|| {
+                format!("corrupt session record at {}:{}", path.display(), i + 1)
+            })?;
+            records.push(rec);
+        }
+        Ok(records)
+    }
+
+    pub fn append(&self, name: &str, record: &SessionRecord) -> Result<()> {
+        use std::io::Write;
+        let path = self.path(name);
+        let mut f = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open session {}", path.display()))?;
+        let line = serde_json::to_string(record)?;
+        writeln!(f, "{line}")?;
+        Ok(())
+    }
+
+    /// Append `record`, externalizing bodies over `max_bytes` (0 disables
+    /// the cap): the full content goes to a numbered side file under
+    /// `<session>.attachments/` and the stored record keeps the head plus
+    /// a pointer, so replaying the session stays cheap.
+    pub fn append_capped(
+        &self,
+        name: &str,
+        record: &SessionRecord,
+        max_bytes: usize,
+    ) -> Result<()> {
+        if max_bytes == 0 || record.content.len() <= max_bytes {
+            return self.append(name, record);
+        }
+        let dir = self.dir.join(format!("{name}.attachments"));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create attachment dir {}", dir.display()))?;
+        let count = std::fs::read_dir(&dir)?.count();
+        let path = dir.join(format!("{:04}.txt", count + 1));
+        std::fs::write(&path, &record.content)
+            .with_context(|| format!("failed to externalize record to {}", path.display()))?;
+        let head: String = record.content.chars().take(STUB_HEAD_CHARS).collect();
+        let mut capped = record.clone();
+        capped.content = format!(
+            "{head}…\n[{} bytes externalized to {}]",
+            record.content.len(),
+            path.display()
+        );
+        self.append(name, &capped)
+    }
+
+    pub fn list(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(stem) = name.strip_suffix(".jsonl") {
+                names.push(stem.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn clear(&self, name: &str) -> Result<()> {
+        let path = self.path(name);
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("failed to remove session {}", path.display()))?;
+        }
+        let attachments = self.dir.join(format!("{name}.attachments"));
+        if attachments.exists() {
+            std::fs::remove_dir_all(&attachments).with_context(|| {
+                format!("failed to remove attachments {}", attachments.display())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_records_are_externalized_with_a_stub() {
+        let dir = std::env::temp_dir().join(format!("sw-session-cap-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = SessionStore { dir: dir.clone() };
+
+        let small = SessionRecord::now(Role::User, "short prompt", None);
+        store.append_capped("s", &small, 64).unwrap();
+        let big = SessionRecord::now(Role::Assistant, "x".repeat(200), None);
+        store.append_capped("s", &big, 64).unwrap();
+
+        let records = store.load("s").unwrap();
+        assert_eq!(records[0].content, "short prompt");
+        assert!(records[1].content.len() < 200 + 100);
+        assert!(records[1].content.contains("200 bytes externalized"));
+        let side = dir.join("s.attachments").join("0001.txt");
+        assert_eq!(std::fs::read_to_string(&side).unwrap().len(), 200);
+
+        store.clear("s").unwrap();
+        assert!(!side.exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn tool_records_round_trip_with_payload() {
+        let rec = SessionRecord {
+            role: Role::Tool,
+            content: "src/main.rs:36".into(),
+            timestamp: Utc::now(),
+            model: None,
+            tool: Some(ToolCallRecord {
+                name: "grep".into(),
+                arguments: serde_json::json!({"pattern": "fn main"}),
+                result: "src/main.rs:36".into(),
+            }),
+        };
+        let line = serde_json::to_string(&rec).unwrap();
+        let back: SessionRecord = serde_json::from_str(&line).unwrap();
+        assert_eq!(back.role, Role::Tool);
+        let tool = back.tool.unwrap();
+        assert_eq!(tool.name, "grep");
+        assert_eq!(tool.arguments["pattern"], "fn main");
+        assert_eq!(back.content, tool.result);
+        // Pre-tool records without the field still parse.
+        let legacy: SessionRecord = serde_json::from_str(
+            r#"{"role":"user","content":"hi","timestamp":"2026-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(legacy.tool.is_none());
+    }
+}
@@ -0,0 +1,150 @@
+//! Telemetry-free local usage stats.
+//!
+//! Every invocation appends one JSONL record under the data dir; `sw
+//! stats` reads them back for breakdowns. Nothing ever leaves the
+//! machine, and `stats = false` in config turns recording off.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsRecord {
+    pub timestamp: DateTime<Utc>,
+    pub command: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    /// Only present when the model has configured prices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    pub duration_ms: u64,
+    pub success: bool,
+}
+
+/// Token usage accumulated across an invocation's LLM calls, fed by
+/// [`crate::app::AppContext::report_meta`].
+#[derive(Debug, Default)]
+pub struct UsageTally {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub model: Option<String>,
+}
+
+pub type SharedTally = std::sync::Arc<Mutex<UsageTally>>;
+
+fn stats_path() -> Result<PathBuf> {
+    Ok(Config::data_dir()?.join("stats.jsonl"))
+}
+
+/// Append one record; failures are reported by the caller as warnings at
+/// most, since stats must never break a command.
+pub fn record(rec: &StatsRecord) -> Result<()> {
+    use std::io::Write;
+    let path = stats_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut f = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("failed to open {}", path.display()))?;
+    writeln!(f, "{}", serde_json::to_string(rec)?)?;
+    Ok(())
+}
+
+/// All records at or after `cutoff`; corrupt lines are skipped rather
+/// than failing the whole query.
+pub fn load_since(cutoff: DateTime<Utc>) -> Result<Vec<StatsRecord>> {
+    let path = stats_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(raw
+        .lines()
+        .filter_map(|line| serde_json::from_str::<StatsRecord>(line).ok())
+        .filter(|r| r.timestamp >= cutoff)
+        .collect())
+}
+
+/// Parse a `--since` spec like `7d`, `12h`, `30m`, or `2w`.
+pub fn parse_since(spec: &str) -> Result<Duration> {
+    let spec = spec.trim();
+    let (value, unit) = spec.split_at(spec.len().saturating_sub(1));
+    let n: i64 = value
+        .parse()
+        .with_context(|| format!("invalid --since '{spec}', expected e.g. 7d, 12h, 30m"))?;
+    match unit {
+        "m" => Ok(Duration::minutes(n)),
+        "h" => Ok(Duration::hours(n)),
+        "d" => Ok(Duration::days(n)),
+        "w" => Ok(Duration::weeks(n)),
+        _ => anyhow::bail!("invalid --since '{spec}', expected a unit of m, h, d, or w"),
+    }
+}
+
+/// Cost from configured per-model prices (USD per million tokens),
+/// or `None` when the model has no price entries.
+pub fn cost_usd(config: &Config, model: &str, prompt: u64, completion: u64) -> Option<f64> {
+    let caps = config.model_caps.get(model)?;
+    let input = caps.price_per_mtok_prompt?;
+    let output = caps.price_per_mtok_completion.unwrap_or(input);
+    Some((prompt as f64 * input + completion as f64 * output) / 1_000_000.0)
+}
+
+/// The project this invocation ran in: the git work-tree directory name,
+/// falling back to the current directory's name.
+pub fn current_project() -> Option<String> {
+    if let Ok(top) = crate::gitutil::git(&["rev-parse", "--show-toplevel"]) {
+        let top = top.trim();
+        if !top.is_empty() {
+            return PathBuf::from(top)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string());
+        }
+    }
+    std::env::current_dir()
+        .ok()?
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_since_specs() {
+        assert_eq!(parse_since("7d").unwrap(), Duration::days(7));
+        assert_eq!(parse_since("30m").unwrap(), Duration::minutes(30));
+        assert!(parse_since("7x").is_err());
+        assert!(parse_since("").is_err());
+    }
+
+    #[test]
+    fn cost_uses_configured_prices() {
+        let mut config = Config::default();
+        config.model_caps.insert(
+            "m1".to_string(),
+            crate::config::ModelCapsOverride {
+                price_per_mtok_prompt: Some(1.0),
+                price_per_mtok_completion: Some(2.0),
+                ..Default::default()
+            },
+        );
+        let cost = cost_usd(&config, "m1", 1_000_000, 500_000).unwrap();
+        assert!((cost - 2.0).abs() < 1e-9);
+        assert_eq!(cost_usd(&config, "other", 1, 1), None);
+    }
+}
@@ -0,0 +1,161 @@
+//! Speech-to-text for `ask --voice`.
+//!
+//! Recording shells out to whatever capture tool the platform has
+//! (`arecord`, sox's `rec`); transcription goes through the provider's
+//! `/audio/transcriptions` endpoint when the profile supports it, and
+//! falls back to a local whisper.cpp binary otherwise.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::app::AppContext;
+
+/// OpenAI's hosted transcription model; compatible servers accept or
+/// ignore the field.
+const STT_MODEL: &str = "whisper-1";
+
+const STT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Build the capture invocation writing a wav to `out`, probing the
+/// common recorders in order.
+fn capture_command(out: &Path) -> Option<std::process::Command> {
+    if crate::platform::has_command("arecord") {
+        let mut cmd = std::process::Command::new("arecord");
+        cmd.arg("-q").arg("-f").arg("cd").arg(out);
+        return Some(cmd);
+    }
+    if crate::platform::has_command("rec") {
+        let mut cmd = std::process::Command::new("rec");
+        cmd.arg("-q").arg(out);
+        return Some(cmd);
+    }
+    None
+}
+
+/// Record from the default microphone into a temp wav until the user
+/// presses Enter. The caller owns (and should remove) the returned file.
+pub fn record_audio(ctx: &AppContext) -> Result<PathBuf> {
+    let out = std::env::temp_dir().join(format!("sw-voice-{}.wav", std::process::id()));
+    let mut cmd = capture_command(&out)
+        .context("no audio recorder found; install alsa-utils (arecord) or sox (rec)")?;
+    let mut child = cmd
+        .stdin(std::process::Stdio::null())
+        .spawn()
+        .context("failed to start audio recorder")?;
+    ctx.render.status("recording — press Enter to stop");
+    let mut line = String::new();
+    let _ = std::io::stdin().read_line(&mut line);
+    let _ = child.kill();
+    let _ = child.wait();
+    let size = std::fs::metadata(&out).map(|m| m.len()).unwrap_or(0);
+    anyhow::ensure!(size > 0, "recorder produced no audio");
+    Ok(out)
+}
+
+/// The STT endpoint base for the active profile, when it plausibly has
+/// one: OpenAI itself, or a custom OpenAI-compatible server.
+fn stt_api_base(ctx: &AppContext) -> Result<Option<(String, Option<String>)>> {
+    let profile = ctx.profile()?;
+    if matches!(profile.provider.as_str(), "anthropic" | "ollama") {
+        return Ok(None);
+    }
+    let custom = ctx.config.providers.get(&profile.provider);
+    let base = match profile.api_base.clone() {
+        Some(base) => base,
+        None => match custom.map(|c| c.api_base.clone()).or_else(|| {
+            crate::llm::resolve_api_base_for_provider(&profile.provider).map(str::to_string)
+        }) {
+            Some(base) => base,
+            None => return Ok(None),
+        },
+    };
+    let key = profile
+        .resolve_api_key()
+        .or_else(|| custom.and_then(|c| c.resolve_api_key()));
+    Ok(Some((base.trim_end_matches('/').to_string(), key)))
+}
+
+/// Transcribe an audio file to text, preferring the provider endpoint and
+/// falling back to local whisper.cpp.
+pub async fn transcribe(path: &Path, ctx: &AppContext) -> Result<String> {
+    if let Some((base, key)) = stt_api_base(ctx)? {
+        ctx.render.status("transcribing via provider");
+        return transcribe_remote(path, &base, key.as_deref(), ctx).await;
+    }
+    for bin in ["whisper-cli", "whisper-cpp", "whisper"] {
+        if crate::platform::has_command(bin) {
+            ctx.render.status(&format!("transcribing with {bin}"));
+            return transcribe_local(path, bin, ctx).await;
+        }
+    }
+    bail!(
+        "no transcription backend: the active profile has no STT endpoint \
+         and no whisper.cpp binary is on PATH"
+    );
+}
+
+#[derive(serde::Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+async fn transcribe_remote(
+    path: &Path,
+    api_base: &str,
+    api_key: Option<&str>,
+    ctx: &AppContext,
+) -> Result<String> {
+    let bytes = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("audio.wav")
+        .to_string();
+    let part = reqwest::multipart::Part::bytes(bytes)
+        .file_name(file_name)
+        .mime_str("application/octet-stream")?;
+    let form = reqwest::multipart::Form::new()
+        .text("model", STT_MODEL)
+        .part("file", part);
+    let client = reqwest::Client::builder()
+        .timeout(STT_TIMEOUT)
+        .build()
+        .expect("static client config");
+    let mut rb = client.post(format!("{api_base}/audio/transcriptions"));
+    if let Some(key) = api_key.filter(|k| !k.is_empty()) {
+        rb = rb.bearer_auth(key);
+    }
+    let resp = tokio::select! {
+        r = rb.multipart(form).send() => r.context("transcription request failed")?,
+        _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+    };
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        bail!("transcription failed ({status}): {}", body.trim());
+    }
+    let parsed: TranscriptionResponse = resp.json().await.context("invalid STT response")?;
+    Ok(parsed.text.trim().to_string())
+}
+
+async fn transcribe_local(path: &Path, bin: &str, ctx: &AppContext) -> Result<String> {
+    // whisper.cpp: -np suppresses progress chatter, -nt the timestamps,
+    // leaving the bare transcript on stdout.
+    let mut cmd = tokio::process::Command::new(bin);
+    cmd.arg("-np").arg("-nt").arg("-f").arg(path);
+    cmd.kill_on_drop(true);
+    let output = tokio::select! {
+        r = cmd.output() => r.with_context(|| format!("failed to run {bin}"))?,
+        _ = ctx.cancel.cancelled() => bail!(crate::cancel::INTERRUPTED),
+    };
+    if !output.status.success() {
+        bail!(
+            "{bin} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,232 @@
+//! Monorepo awareness: enumerate the packages of a Cargo workspace, an
+//! npm/pnpm workspace, or a Go multi-module repo so commands can be
+//! scoped with `--package`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Package {
+    pub name: String,
+    pub root: PathBuf,
+}
+
+/// Find the workspace root: the nearest ancestor containing a `.git`
+/// directory or a recognized project marker, else the starting
+/// directory. The global `--workspace` flag overrides this.
+pub fn detect_workspace_root(start: &Path) -> PathBuf {
+    const MARKERS: &[&str] = &[
+        ".git",
+        "Cargo.toml",
+        "package.json",
+        "go.mod",
+        "pyproject.toml",
+    ];
+    let mut dir = Some(start);
+    while let Some(d) = dir {
+        if MARKERS.iter().any(|m| d.join(m).exists()) {
+            return d.to_path_buf();
+        }
+        dir = d.parent();
+    }
+    start.to_path_buf()
+}
+
+/// All packages discoverable from `root`, across the ecosystems we know.
+pub fn discover_packages(root: &Path) -> Vec<Package> {
+    let mut packages = Vec::new();
+    cargo_packages(root, &mut packages);
+    npm_packages(root, &mut packages);
+    go_packages(root, &mut packages);
+    packages
+}
+
+/// Resolve `--package NAME` against the workspace rooted at `root`.
+pub fn find_package(root: &Path, name: &str) -> Result<Package> {
+    let packages = discover_packages(root);
+    packages
+        .iter()
+        .find(|p| p.name == name)
+        .cloned()
+        .with_context(|| {
+            let known: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+            format!(
+                "package '{name}' not found (known: {})",
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            )
+        })
+}
+
+/// The package a file belongs to: the one with the longest root prefix.
+pub fn package_for_path<'a>(packages: &'a [Package], path: &Path) -> Option<&'a Package> {
+    packages
+        .iter()
+        .filter(|p| path.starts_with(&p.root))
+        .max_by_key(|p| p.root.components().count())
+}
+
+fn push_unique(out: &mut Vec<Package>, pkg: Package) {
+    if !out.iter().any(|p| p.root == pkg.root) {
+        out.push(pkg);
+    }
+}
+
+/// Expand a workspace member glob (e.g. `crates/*`) under `root`.
+fn expand_member_glob(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let full = root.join(pattern).display().to_string();
+    glob::glob(&full)
+        .map(|paths| {
+            paths
+                .filter_map(|p| p.ok())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn cargo_packages(root: &Path, out: &mut Vec<Package>) {
+    let Ok(raw) = std::fs::read_to_string(root.join("Cargo.toml")) else {
+        return;
+    };
+    let Ok(value) = raw.parse::<toml::Value>() else {
+        return;
+    };
+    if let Some(members) = value
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+    {
+        for member in members.iter().filter_map(|m| m.as_str()) {
+            for dir in expand_member_glob(root, member) {
+                if let Some(pkg) = cargo_package_at(&dir) {
+                    push_unique(out, pkg);
+                }
+            }
+        }
+    }
+    if value.get("package").is_some() {
+        if let Some(pkg) = cargo_package_at(root) {
+            push_unique(out, pkg);
+        }
+    }
+}
+
+fn cargo_package_at(dir: &Path) -> Option<Package> {
+    let raw = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let value = raw.parse::<toml::Value>().ok()?;
+    let name = value
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(str::to_string)
+        .or_else(|| dir.file_name().map(|n| n.to_string_lossy().to_string()))?;
+    Some(Package {
+        name,
+        root: dir.to_path_buf(),
+    })
+}
+
+fn npm_packages(root: &Path, out: &mut Vec<Package>) {
+    let mut globs: Vec<String> = Vec::new();
+    if let Ok(raw) = std::fs::read_to_string(root.join("package.json")) {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&raw) {
+            // "workspaces" is either an array or `{ "packages": [...] }`.
+            let ws = value
+                .get("workspaces")
+                .map(|w| w.get("packages").unwrap_or(w));
+            if let Some(arr) = ws.and_then(|w| w.as_array()) {
+                globs.extend(arr.iter().filter_map(|g| g.as_str()).map(str::to_string));
+            }
+        }
+    }
+    if let Ok(raw) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        // Minimal YAML: list items under `packages:`.
+        for line in raw.lines() {
+            if let Some(item) = line.trim().strip_prefix("- ") {
+                globs.push(item.trim_matches(['\'', '"']).to_string());
+            }
+        }
+    }
+    for pattern in globs {
+        for dir in expand_member_glob(root, &pattern) {
+            if let Some(pkg) = npm_package_at(&dir) {
+                push_unique(out, pkg);
+            }
+        }
+    }
+}
+
+fn npm_package_at(dir: &Path) -> Option<Package> {
+    let raw = std::fs::read_to_string(dir.join("package.json")).ok()?;
+    let value = serde_json::from_str::<serde_json::Value>(&raw).ok()?;
+    let name = value
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(str::to_string)
+        .or_else(|| dir.file_name().map(|n| n.to_string_lossy().to_string()))?;
+    Some(Package {
+        name,
+        root: dir.to_path_buf(),
+    })
+}
+
+fn go_packages(root: &Path, out: &mut Vec<Package>) {
+    let Ok(raw) = std::fs::read_to_string(root.join("go.work")) else {
+        return;
+    };
+    // `use ./dir` lines, possibly grouped in a `use ( ... )` block.
+    let mut in_block = false;
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        let entry = if in_block {
+            if trimmed == ")" {
+                in_block = false;
+                continue;
+            }
+            Some(trimmed)
+        } else if trimmed == "use (" {
+            in_block = true;
+            continue;
+        } else {
+            trimmed.strip_prefix("use ").map(str::trim)
+        };
+        let Some(entry) = entry.filter(|e| !e.is_empty()) else {
+            continue;
+        };
+        let dir = root.join(entry.trim_start_matches("./"));
+        if dir.join("go.mod").exists() {
+            let name = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| entry.to_string());
+            push_unique(out, Package { name, root: dir });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_root_prefix_wins() {
+        let packages = vec![
+            Package {
+                name: "root".into(),
+                root: PathBuf::from("repo"),
+            },
+            Package {
+                name: "core".into(),
+                root: PathBuf::from("repo/crates/core"),
+            },
+        ];
+        let hit = package_for_path(&packages, Path::new("repo/crates/core/src/lib.rs"));
+        assert_eq!(hit.map(|p| p.name.as_str()), Some("core"));
+    }
+}